@@ -170,25 +170,25 @@ impl<'s> UserProtoServiceWithLifetime<'s> {
 #[get("/")]
 async fn hello(Component(user_service): Component<UserService>) -> Result<impl IntoResponse> {
     assert!(user_service.optional_comp.is_none());
-    Ok(user_service.query_db().await?)
+    user_service.query_db().await
 }
 
 #[get("/use-ref")]
 async fn hello_ref(
     Component(user_service): Component<UserServiceUseRef>,
 ) -> Result<impl IntoResponse> {
-    Ok(user_service.query_db().await?)
+    user_service.query_db().await
 }
 
 #[get("/prototype-service")]
 async fn prototype_service() -> Result<impl IntoResponse> {
     let service = UserProtoService::build(5).context("build service failed")?;
     assert!(service.optional_comp.is_none());
-    Ok(service.pv_count()?)
+    service.pv_count()
 }
 
 #[get("/prototype-service-lifetime")]
 async fn prototype_service_with_lifetime() -> Result<impl IntoResponse> {
     let service = UserProtoServiceWithLifetime::build(&10).context("build service failed")?;
-    Ok(service.pv_count()?)
+    service.pv_count()
 }
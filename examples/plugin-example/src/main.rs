@@ -16,7 +16,7 @@ impl Plugin for MyPlugin {
             Ok(config) => {
                 println!("{:#?}", config);
                 assert_eq!(config.a, 1);
-                assert_eq!(config.b, true);
+                assert!(config.b);
                 assert_eq!(config.c.g, "hello");
                 assert_eq!(config.d, "world");
                 assert_eq!(config.e, ConfigEnum::EA);
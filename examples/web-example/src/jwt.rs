@@ -105,7 +105,7 @@ pub fn encode(claims: Claims) -> Result<String> {
 pub fn decode(token: &str) -> Result<Claims> {
     let validation = Validation::new(Algorithm::RS256);
     let token_data =
-        jsonwebtoken::decode::<Claims>(&token, &DECODE_KEY, &validation).map_err(|e| {
+        jsonwebtoken::decode::<Claims>(token, &DECODE_KEY, &validation).map_err(|e| {
             tracing::error!("{:?}", e);
             KnownWebError::bad_request("invalid token")
         })?;
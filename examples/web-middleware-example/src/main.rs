@@ -32,7 +32,7 @@ async fn main() {
 }
 
 /// Example #1:
-
+///
 /// Example of using `middlewares` macro to apply middleware to all routes in a module.
 /// This module includes a problem detail middleware that handles errors and logs them to the database.
 /// It also includes a timeout layer to limit request processing time.
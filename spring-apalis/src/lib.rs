@@ -1,11 +1,11 @@
 use apalis::prelude::Monitor;
 use spring::{
-    app::AppBuilder,
+    app::{App, AppBuilder},
     async_trait,
     error::Result,
-    plugin::{component::ComponentRef, ComponentRegistry, MutableComponentRegistry, Plugin},
-    signal,
+    plugin::{ComponentRegistry, MutableComponentRegistry, Plugin},
 };
+use std::sync::Arc;
 
 pub use apalis;
 #[cfg(feature = "amqp")]
@@ -30,14 +30,13 @@ pub type WorkerRegister = fn(&mut AppBuilder, Monitor) -> Monitor;
 #[async_trait]
 impl Plugin for ApalisPlugin {
     async fn build(&self, app: &mut AppBuilder) {
-        if let Some(builders) = app.get_component::<Vec<WorkerRegister>>() {
+        let builders = app.get_components::<WorkerRegister>();
+        if !builders.is_empty() {
             let mut monitor = Monitor::new();
             for build_fn in &builders {
                 monitor = build_fn(app, monitor);
             }
-            if !builders.is_empty() {
-                app.add_scheduler(move |_app| Box::new(Self::schedule(monitor)));
-            }
+            app.add_scheduler(move |app| Box::new(Self::schedule(app, monitor)));
         }
     }
 
@@ -56,32 +55,23 @@ impl Plugin for ApalisPlugin {
 }
 
 impl ApalisPlugin {
-    async fn schedule(monitor: Monitor) -> Result<String> {
-        let _ = monitor.run_with_signal(shutdown_signal()).await;
+    async fn schedule(app: Arc<App>, monitor: Monitor) -> Result<String> {
+        let lifecycle = app.lifecycle().clone();
+        let shutdown = async move {
+            lifecycle.shutdown_signal("apalis").await;
+            Ok(())
+        };
+        let _ = monitor.run_with_signal(shutdown).await;
         Ok("apalis scheduled finished".to_string())
     }
 }
 
-async fn shutdown_signal() -> std::io::Result<()> {
-    let _ = signal::shutdown_signal("apalis").await;
-    Ok(())
-}
-
 pub trait ApalisConfigurator {
     fn add_worker(&mut self, worker_register: WorkerRegister) -> &mut Self;
 }
 
 impl ApalisConfigurator for AppBuilder {
     fn add_worker(&mut self, worker_register: WorkerRegister) -> &mut Self {
-        if let Some(workers) = self.get_component_ref::<Vec<WorkerRegister>>() {
-            unsafe {
-                let raw_ptr = ComponentRef::into_raw(workers);
-                let workers = &mut *(raw_ptr as *mut Vec<WorkerRegister>);
-                workers.push(worker_register);
-            }
-            self
-        } else {
-            self.add_component(vec![worker_register])
-        }
+        self.append_component(worker_register)
     }
 }
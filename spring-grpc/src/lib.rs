@@ -12,8 +12,9 @@ use spring::{
     app::AppBuilder,
     config::ConfigRegistry,
     error::Result,
-    plugin::{component::ComponentRef, ComponentRegistry, MutableComponentRegistry, Plugin},
-    signal, App,
+    lifecycle::StartupGuard,
+    plugin::{ComponentRegistry, MutableComponentRegistry, Plugin},
+    App,
 };
 use std::{convert::Infallible, net::SocketAddr, sync::Arc};
 use tonic::{
@@ -21,7 +22,7 @@ use tonic::{
     body::Body,
     server::NamedService,
     service::{Routes, RoutesBuilder},
-    transport::Server,
+    transport::{server::TcpIncoming, Server},
 };
 use tower::Service;
 
@@ -52,21 +53,17 @@ impl GrpcConfigurator for AppBuilder {
         S::Response: axum::response::IntoResponse,
         S::Future: Send + 'static,
     {
-        if let Some(routes) = self.get_component_ref::<RoutesBuilder>() {
-            unsafe {
-                let raw_ptr = ComponentRef::into_raw(routes);
-                let routes = &mut *(raw_ptr as *mut RoutesBuilder);
-                routes.add_service(svc);
-            }
-            self
-        } else {
-            let mut route_builder = Routes::builder();
-            route_builder.add_service(svc);
-            self.add_component(route_builder)
-        }
+        self.append_component(GrpcService(Arc::new(move |routes| {
+            routes.add_service(svc.clone());
+        })))
     }
 }
 
+/// Grpc service registered by [`GrpcConfigurator::add_service`],
+/// the services are added to the routes of the server when it starts
+#[derive(Clone)]
+pub struct GrpcService(Arc<dyn Fn(&mut RoutesBuilder) + Send + Sync>);
+
 /// Grpc Plugin Definition
 pub struct GrpcPlugin;
 
@@ -77,23 +74,32 @@ impl Plugin for GrpcPlugin {
             .get_config::<GrpcConfig>()
             .expect("grpc plugin config load failed");
 
-        app.add_scheduler(move |app| Box::new(Self::schedule(app, config)));
+        app.add_scheduler(move |app| {
+            let startup = app.lifecycle().startup_task("tonic grpc server");
+            Box::new(Self::schedule(app, config, startup))
+        });
     }
 }
 
 impl GrpcPlugin {
-    async fn schedule(app: Arc<App>, config: GrpcConfig) -> Result<String> {
+    async fn schedule(
+        app: Arc<App>,
+        config: GrpcConfig,
+        startup: StartupGuard,
+    ) -> Result<String> {
         // Get the router in the final schedule step
-        let routes_builder = app.get_component::<RoutesBuilder>();
-
-        let routes = if let Some(routes_builder) = routes_builder {
-            routes_builder.routes()
-        } else {
+        let services = app.get_components::<GrpcService>();
+        if services.is_empty() {
             return Ok(
                 "The grpc plugin does not register any routes, so no scheduling is performed"
                     .to_string(),
             );
-        };
+        }
+        let mut routes_builder = Routes::builder();
+        for GrpcService(add_service) in services {
+            add_service(&mut routes_builder);
+        }
+        let routes = routes_builder.routes();
 
         let mut server = Server::builder()
             .accept_http1(config.accept_http1)
@@ -122,19 +128,28 @@ impl GrpcPlugin {
         server = Self::apply_middleware(server);
 
         let addr = SocketAddr::new(config.binding, config.port);
+        let incoming = TcpIncoming::bind(addr)
+            .with_context(|| format!("bind tcp listener failed:{addr}"))?
+            .with_nodelay(Some(config.tcp_nodelay))
+            .with_keepalive(config.tcp_keepalive);
         tracing::info!("tonic grpc service bind tcp listener: {}", addr);
+        startup.ready();
 
+        let lifecycle = app.lifecycle().clone();
+        let shutdown = async move { lifecycle.shutdown_signal("tonic grpc server").await };
         let router = server.add_routes(routes);
         if config.graceful {
             router
-                .serve_with_shutdown(addr, signal::shutdown_signal("tonic grpc server"))
+                .serve_with_incoming_shutdown(incoming, shutdown)
                 .await
-                .with_context(|| format!("bind tcp listener failed:{addr}"))?;
+                .context("tonic grpc server failed")?;
         } else {
-            router
-                .serve(addr)
-                .await
-                .with_context(|| format!("bind tcp listener failed:{addr}"))?;
+            tokio::select! {
+                result = router.serve_with_incoming(incoming) => {
+                    result.context("tonic grpc server failed")?
+                }
+                _ = shutdown => {}
+            }
         }
         Ok("tonic server schedule finished".to_string())
     }
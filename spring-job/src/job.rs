@@ -6,11 +6,16 @@ use serde::Serialize;
 use spring::app::App;
 use std::{sync::Arc, time::Duration};
 
-#[derive(Clone)]
-enum Trigger {
+/// When a job is triggered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trigger {
+    /// Run once after the delay in seconds
     OneShot(u64),
+    /// Run repeatedly with the delay in seconds between runs
     FixedDelay(u64),
+    /// Run repeatedly at the interval in seconds
     FixedRate(u64),
+    /// Run on the cron schedule
     Cron(String),
 }
 
@@ -18,6 +23,7 @@ enum Trigger {
 pub struct Job {
     trigger: Trigger,
     handler: BoxedHandler,
+    handler_name: &'static str,
     extra: Option<Vec<u8>>,
 }
 
@@ -77,6 +83,16 @@ impl Job {
             data: Some(data),
         }
     }
+    /// When the job is triggered
+    pub fn trigger(&self) -> &Trigger {
+        &self.trigger
+    }
+
+    /// Type name of the job handler, such as `my_app::jobs::cleanup`
+    pub fn handler_name(&self) -> &'static str {
+        self.handler_name
+    }
+
     pub fn build(self, app: Arc<App>) -> tokio_cron_scheduler::Job {
         let handler = self.handler;
         let mut job = match self.trigger {
@@ -132,6 +148,7 @@ impl<T: Serialize> JobBuilder<T> {
         Job {
             trigger: self.trigger,
             handler: BoxedHandler::from_handler(handler),
+            handler_name: std::any::type_name::<H>(),
             extra: self
                 .data
                 .map(|data| serde_json::to_vec(&data).expect("job data to json failed")),
@@ -6,7 +6,6 @@ pub mod extractor;
 pub mod handler;
 pub mod job;
 
-use spring::signal;
 /////////////////job-macros/////////////////////
 /// To use these Procedural Macros, you need to add `spring-job` dependency
 pub use spring_macros::cron;
@@ -18,7 +17,6 @@ use anyhow::Context;
 use job::Job;
 use spring::async_trait;
 use spring::error::Result;
-use spring::plugin::component::ComponentRef;
 use spring::plugin::ComponentRegistry;
 use spring::plugin::MutableComponentRegistry;
 use spring::{
@@ -37,10 +35,6 @@ impl Jobs {
     pub fn new() -> Self {
         Self::default()
     }
-    fn single(job: Job) -> Self {
-        Self(vec![job])
-    }
-
     pub fn add_job(mut self, job: Job) -> Self {
         self.0.push(job);
         self
@@ -52,12 +46,6 @@ impl Jobs {
         }
         self
     }
-
-    fn merge(&mut self, jobs: Jobs) {
-        for job in jobs.0 {
-            self.0.push(job);
-        }
-    }
 }
 
 impl Deref for Jobs {
@@ -78,29 +66,14 @@ pub trait JobConfigurator {
 
 impl JobConfigurator for AppBuilder {
     fn add_job(&mut self, job: Job) -> &mut Self {
-        if let Some(jobs) = self.get_component_ref::<Jobs>() {
-            unsafe {
-                let raw_ptr = ComponentRef::into_raw(jobs);
-                let jobs = &mut *(raw_ptr as *mut Vec<Job>);
-                jobs.push(job);
-            }
-            self
-        } else {
-            self.add_component(Jobs::single(job))
-        }
+        self.append_component(job)
     }
 
-    fn add_jobs(&mut self, new_jobs: Jobs) -> &mut Self {
-        if let Some(jobs) = self.get_component_ref::<Jobs>() {
-            unsafe {
-                let raw_ptr = ComponentRef::into_raw(jobs);
-                let jobs = &mut *(raw_ptr as *mut Jobs);
-                jobs.merge(new_jobs);
-            }
-            self
-        } else {
-            self.add_component(new_jobs)
+    fn add_jobs(&mut self, jobs: Jobs) -> &mut Self {
+        for job in jobs.0 {
+            self.append_component(job);
         }
+        self
     }
 }
 
@@ -159,34 +132,21 @@ impl JobPlugin {
 
     async fn schedule(app: Arc<App>) -> Result<String> {
         let mut sched = app.get_expect_component::<JobScheduler>();
-        let jobs = app.get_component_ref::<Jobs>();
-
-        let jobs = match jobs {
-            None => {
-                let msg = "No tasks are registered, so the task scheduler does not start.";
-                tracing::info!(msg);
-                return Ok(msg.to_string());
-            }
-            Some(jobs) => jobs,
-        };
-
-        for job in jobs.deref().iter() {
+        let jobs = app.get_components::<Job>();
+
+        if jobs.is_empty() {
+            let msg = "No tasks are registered, so the task scheduler does not start.";
+            tracing::info!(msg);
+            return Ok(msg.to_string());
+        }
+
+        for job in jobs {
             sched
-                .add(job.to_owned().build(app.clone()))
+                .add(job.build(app.clone()))
                 .await
                 .context("add job failed")?;
         }
 
-        let mut l = sched.clone();
-        // customize shutdown signal
-        tokio::spawn(async move {
-            let _ = signal::shutdown_signal("job").await;
-
-            if let Err(err) = l.shutdown().await {
-                tracing::error!("{:?}", err);
-            }
-        });
-
         // Add code to be run during/after shutdown
         sched.set_shutdown_handler(Box::new(|| {
             Box::pin(async move {
@@ -197,6 +157,10 @@ impl JobPlugin {
         // Start the scheduler
         sched.start().await.context("job scheduler start failed")?;
 
-        Ok("job schedule started".to_string())
+        // Stop the scheduler when the application shuts down
+        app.lifecycle().shutdown_signal("job").await;
+        sched.shutdown().await.context("job scheduler shutdown failed")?;
+
+        Ok("job schedule finished".to_string())
     }
 }
@@ -1,28 +1,116 @@
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{parse_macro_input, Expr, ExprAssign, ItemFn, Lit, Token};
+use syn::{parse::Parser, Expr, ExprAssign, ItemFn, Lit, Token};
+
+/// Cache operation generated by the macro
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CacheOperation {
+    /// `#[cache]`: return the cached value, or call the function and cache its result
+    Cacheable,
+    /// `#[cache_put]`: always call the function and cache its result
+    Put,
+    /// `#[cache_evict]`: call the function and remove the cached value
+    Evict,
+}
+
+impl CacheOperation {
+    fn syntax(self) -> &'static str {
+        match self {
+            Self::Cacheable => {
+                r#"#[cache("<key_pattern>", name = "<cache_name>", expire = <seconds>, jitter = <seconds>, negative_ttl = <seconds>, sync = "local" | "distributed", serializer = "json" | "msgpack" | "bincode", condition = <bool_expr>, unless = <bool_expr>)]"#
+            }
+            Self::Put => {
+                r#"#[cache_put("<key_pattern>", name = "<cache_name>", expire = <seconds>, jitter = <seconds>, negative_ttl = <seconds>, serializer = "json" | "msgpack" | "bincode", condition = <bool_expr>, unless = <bool_expr>)]"#
+            }
+            Self::Evict => {
+                r#"#[cache_evict("<key_pattern>" | all_entries, name = "<cache_name>", condition = <bool_expr>)]"#
+            }
+        }
+    }
+
+    /// Whether the operation stores the result of the function
+    fn stores(self) -> bool {
+        self != Self::Evict
+    }
+}
+
+/// Key of the cached value, or all the entries of the cache for `#[cache_evict(all_entries)]`
+enum CacheKey {
+    Pattern(String),
+    AllEntries,
+}
 
 /// Cache arguments structure
 struct CacheArgs {
-    key: String,
+    key: CacheKey,
+    name: Option<String>,
     expire: Option<u64>,
+    jitter: Option<u64>,
+    negative_ttl: Option<u64>,
+    sync: Option<TokenStream2>,
+    serializer: TokenStream2,
     condition: Option<Expr>,
     unless: Option<Expr>,
 }
 
-impl syn::parse::Parse for CacheArgs {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        // key_pattern to match: "key:{key_id}"
-        let key = input.parse::<syn::LitStr>().map_err(|mut err| {
+fn parse_seconds(name: &str, right: Expr) -> syn::Result<u64> {
+    match right {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Int(lit_int),
+            ..
+        }) => lit_int.base10_parse(),
+        right => Err(syn::Error::new_spanned(
+            right,
+            format!("{name} must be an integer"),
+        )),
+    }
+}
+
+fn parse_str(name: &str, right: Expr) -> syn::Result<syn::LitStr> {
+    match right {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) => Ok(lit_str),
+        right => Err(syn::Error::new_spanned(
+            right,
+            format!("{name} must be a string"),
+        )),
+    }
+}
+
+impl CacheArgs {
+    fn parse(operation: CacheOperation, input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let invalid_definition = |mut err: syn::Error| {
             err.combine(syn::Error::new(
                 err.span(),
-                r#"invalid cache definition, expected #[cache("<key_pattern>", expire = <seconds>, condition = <bool_expr>, unless = <bool_expr>)]"#,
+                format!("invalid cache definition, expected {}", operation.syntax()),
             ));
-
             err
-        })?.value();
+        };
+
+        // key_pattern to match: "key:{key_id}"
+        let key = if operation == CacheOperation::Evict && input.peek(syn::Ident) {
+            let ident = input.parse::<syn::Ident>()?;
+            if ident != "all_entries" {
+                return Err(invalid_definition(syn::Error::new_spanned(
+                    ident,
+                    "expected a key pattern or all_entries",
+                )));
+            }
+            CacheKey::AllEntries
+        } else {
+            let key = input.parse::<syn::LitStr>().map_err(invalid_definition)?;
+            CacheKey::Pattern(key.value())
+        };
 
+        let mut name = None;
         let mut expire = None;
+        let mut jitter = None;
+        let mut negative_ttl = None;
+        let mut sync = None;
+        let mut serializer = quote! { ::spring::cache::Serializer::Json };
         let mut condition = None;
         let mut unless = None;
         while input.peek(Token![,]) {
@@ -34,22 +122,47 @@ impl syn::parse::Parse for CacheArgs {
             };
 
             match ident.as_deref() {
-                Some("expire") => {
-                    if let Expr::Lit(expr_lit) = *assign.right {
-                        if let Lit::Int(lit_int) = expr_lit.lit {
-                            expire = Some(lit_int.base10_parse()?);
-                        } else {
+                Some("name") => name = Some(parse_str("name", *assign.right)?.value()),
+                Some("expire") if operation.stores() => {
+                    expire = Some(parse_seconds("expire", *assign.right)?);
+                }
+                Some("jitter") if operation.stores() => {
+                    jitter = Some(parse_seconds("jitter", *assign.right)?);
+                }
+                Some("negative_ttl") if operation.stores() => {
+                    negative_ttl = Some(parse_seconds("negative_ttl", *assign.right)?);
+                }
+                Some("sync") if operation == CacheOperation::Cacheable => {
+                    let mode = parse_str("sync", *assign.right)?;
+                    sync = Some(match mode.value().as_str() {
+                        "local" => quote! { ::spring::cache::SyncMode::Local },
+                        "distributed" => quote! { ::spring::cache::SyncMode::Distributed },
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                mode,
+                                r#"sync must be "local" or "distributed""#,
+                            ));
+                        }
+                    });
+                }
+                Some("serializer") if operation.stores() => {
+                    let format = parse_str("serializer", *assign.right)?;
+                    serializer = match format.value().as_str() {
+                        "json" => quote! { ::spring::cache::Serializer::Json },
+                        "msgpack" => quote! { ::spring::cache::Serializer::MessagePack },
+                        "bincode" => quote! { ::spring::cache::Serializer::Bincode },
+                        _ => {
                             return Err(syn::Error::new_spanned(
-                                expr_lit,
-                                "expire must be an integer",
+                                format,
+                                r#"serializer must be "json", "msgpack" or "bincode""#,
                             ));
                         }
-                    }
+                    };
                 }
                 Some("condition") => {
                     condition = Some(*assign.right);
                 }
-                Some("unless") => {
+                Some("unless") if operation.stores() => {
                     unless = Some(*assign.right);
                 }
                 Some(name) => {
@@ -64,9 +177,21 @@ impl syn::parse::Parse for CacheArgs {
             }
         }
 
+        if jitter.is_some() && expire.is_none() && negative_ttl.is_none() {
+            return Err(invalid_definition(syn::Error::new(
+                input.span(),
+                "jitter requires expire or negative_ttl",
+            )));
+        }
+
         Ok(Self {
             key,
+            name,
             expire,
+            jitter,
+            negative_ttl,
+            sync,
+            serializer,
             condition,
             unless,
         })
@@ -74,9 +199,13 @@ impl syn::parse::Parse for CacheArgs {
 }
 
 fn extract_ok_type_from_result(ty: &syn::Type) -> Option<&syn::Type> {
+    extract_first_type_arg(ty, "Result")
+}
+
+fn extract_first_type_arg<'a>(ty: &'a syn::Type, wrapper: &str) -> Option<&'a syn::Type> {
     if let syn::Type::Path(type_path) = ty {
         let segment = type_path.path.segments.last()?;
-        if segment.ident == "Result" {
+        if segment.ident == wrapper {
             if let syn::PathArguments::AngleBracketed(generic_args) = &segment.arguments {
                 if let Some(syn::GenericArgument::Type(ok_ty)) = generic_args.args.first() {
                     return Some(ok_ty);
@@ -87,9 +216,16 @@ fn extract_ok_type_from_result(ty: &syn::Type) -> Option<&syn::Type> {
     None
 }
 
-pub fn cache(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input_fn = parse_macro_input!(item as ItemFn);
-    let args = parse_macro_input!(attr as CacheArgs);
+pub fn cache(operation: CacheOperation, attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input_fn = match syn::parse::<ItemFn>(item) {
+        Ok(input_fn) => input_fn,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let args =
+        match (|input: syn::parse::ParseStream| CacheArgs::parse(operation, input)).parse(attr) {
+            Ok(args) => args,
+            Err(err) => return err.to_compile_error().into(),
+        };
 
     let vis = &input_fn.vis;
     let sig = &input_fn.sig;
@@ -102,8 +238,10 @@ pub fn cache(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attrs = &input_fn.attrs;
     let user_block = &input_fn.block;
 
+    let unit_type: syn::Type = syn::parse_quote!(());
     let ret_type = match &sig.output {
-        syn::ReturnType::Type(_, ty) => ty,
+        syn::ReturnType::Type(_, ty) => &**ty,
+        syn::ReturnType::Default if operation == CacheOperation::Evict => &unit_type,
         syn::ReturnType::Default => {
             return syn::Error::new_spanned(sig, "cached function must return a value")
                 .to_compile_error()
@@ -111,21 +249,42 @@ pub fn cache(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
-    let cache_key_fmt = args.key;
-    let redis_set_stmt = match args.expire {
-        Some(expire_sec) => {
-            quote! {
-                let _: ::std::result::Result<redis::Value, ()> = redis.set_ex(&cache_key, cache_value, #expire_sec).await
-                    .map_err(|err| ::spring::tracing::error!("failed to set cache for key {}: {:?}", cache_key, err));
-            }
+    let cache_name = match args.name {
+        Some(name) => quote! { #name },
+        None => quote! { ::spring::cache::DEFAULT_CACHE },
+    };
+    let cache_key = match &args.key {
+        CacheKey::Pattern(cache_key_fmt) => quote! { let cache_key = format!(#cache_key_fmt); },
+        CacheKey::AllEntries => quote! {},
+    };
+    let is_result = extract_ok_type_from_result(ret_type).is_some();
+    let value_type = extract_ok_type_from_result(ret_type).unwrap_or(ret_type);
+    let mut ttl = match args.expire {
+        Some(expire_sec) => quote! { Some(::std::time::Duration::from_secs(#expire_sec)) },
+        None => quote! { None },
+    };
+    if let Some(negative_sec) = args.negative_ttl {
+        if extract_first_type_arg(value_type, "Option").is_none() {
+            return syn::Error::new_spanned(
+                value_type,
+                "negative_ttl requires the function to return an Option",
+            )
+            .to_compile_error()
+            .into();
         }
-        None => {
-            quote! {
-                let _: ::std::result::Result<redis::Value, ()> = redis.set(&cache_key, cache_value).await
-                    .map_err(|err| ::spring::tracing::error!("failed to set cache for key {}: {:?}", cache_key, err));
+        ttl = quote! {
+            if result.is_none() {
+                Some(::std::time::Duration::from_secs(#negative_sec))
+            } else {
+                #ttl
             }
-        }
-    };
+        };
+    }
+    if let Some(jitter_sec) = args.jitter {
+        ttl =
+            quote! { ::spring::cache::jitter(#ttl, ::std::time::Duration::from_secs(#jitter_sec)) };
+    }
+    let serializer = &args.serializer;
     let condition_check = match &args.condition {
         Some(expr) => quote! {
             if !(#expr) {
@@ -134,106 +293,123 @@ pub fn cache(attr: TokenStream, item: TokenStream) -> TokenStream {
         },
         None => quote! {},
     };
+    let cache_lookup = quote! {
+        let cache = ::spring::cache::cache_manager(&::spring::App::global()).cache(#cache_name);
+        #cache_key
+    };
+    let cache_store = quote! {
+        match #serializer.encode(&result) {
+            Ok(cache_value) => {
+                if let Err(err) = cache.put(&cache_key, cache_value, #ttl).await {
+                    ::spring::tracing::error!("failed to set cache for key {}: {:?}", cache_key, err);
+                }
+            }
+            Err(err) => {
+                ::spring::tracing::error!("cache encode failed for key {}: {:?}", cache_key, err);
+            }
+        }
+    };
+    let cache_evict = match &args.key {
+        CacheKey::Pattern(_) => quote! {
+            if let Err(err) = cache.evict(&cache_key).await {
+                ::spring::tracing::error!("failed to evict cache for key {}: {:?}", cache_key, err);
+            }
+        },
+        CacheKey::AllEntries => quote! {
+            if let Err(err) = cache.clear().await {
+                ::spring::tracing::error!("failed to clear cache {}: {:?}", #cache_name, err);
+            }
+        },
+    };
+    let cached_value = if is_result {
+        quote! { Ok(value) }
+    } else {
+        quote! { value }
+    };
+    let cache_read = quote! {
+        match cache.get(&cache_key).await {
+            Ok(Some(cache_value)) => match #serializer.decode::<#value_type>(&cache_value) {
+                Ok(value) => return #cached_value,
+                Err(e) => {
+                    ::spring::tracing::error!("cache decode error for {}: {:?}", cache_key, e);
+                }
+            },
+            Ok(None) => {}
+            Err(e) => {
+                ::spring::tracing::error!("failed to get cache for key {}: {:?}", cache_key, e);
+            }
+        }
+    };
 
-    let gen_code = match extract_ok_type_from_result(ret_type) {
-        Some(inner_type) => {
-            let unless_check = match &args.unless {
-                Some(expr) => quote! {
-                    if (#expr) {
-                        return Ok(result);
-                    }
-                },
-                None => quote! {},
-            };
-            quote! {
-                #(#attrs)*
-                #vis #asyncness fn #ident #generics(#inputs) #output #where_clause {
-                    #condition_check
-
-                    use spring_redis::redis::{self, AsyncCommands};
-                    use spring::{plugin::ComponentRegistry, tracing, App};
-
-                    let mut redis = App::global()
-                        .get_component::<spring_redis::Redis>()
-                        .expect("redis component not found");
-
-                    let cache_key = format!(#cache_key_fmt);
-
-                    if let Ok(Some(cache_value)) = redis.get::<_, Option<String>>(&cache_key).await {
-                        match ::serde_json::from_str::<#inner_type>(&cache_value) {
-                            Ok(value) => return Ok(value),
-                            Err(e) => {
-                                ::spring::tracing::error!("cache decode error for {}: {:?}", cache_key, e);
-                            }
-                        }
-                    }
+    let (unwrap_result, wrap_result) = if is_result {
+        (
+            quote! { let result: #value_type = result?; },
+            quote! { Ok(result) },
+        )
+    } else {
+        (quote! {}, quote! { result })
+    };
+    let unless_check = match &args.unless {
+        Some(expr) => quote! {
+            if (#expr) {
+                return #wrap_result;
+            }
+        },
+        None => quote! {},
+    };
+
+    // the callers waiting for the lock read the value cached by the first one
+    let sync_lock = match &args.sync {
+        Some(mode) => quote! {
+            let _cache_lock = ::spring::cache::lock(&cache, #cache_name, &cache_key, #mode).await;
+            #cache_read
+        },
+        None => quote! {},
+    };
 
-                    let result: #ret_type = (|| async #user_block)().await;
-                    let result: #inner_type = result?;
+    let body: TokenStream2 = match operation {
+        CacheOperation::Cacheable => quote! {
+            #cache_read
 
-                    #unless_check
+            #sync_lock
 
-                    match ::serde_json::to_string(&result) {
-                        Ok(cache_value) => {
-                            #redis_set_stmt
-                        }
-                        Err(err) => {
-                            ::spring::tracing::error!("cache encode failed for key {}: {:?}", cache_key, err);
-                        }
-                    }
+            let result: #ret_type = (|| async #user_block)().await;
+            #unwrap_result
 
-                    Ok(result)
-                }
-            }
-        }
-        None => {
-            let unless_check = match &args.unless {
-                Some(expr) => quote! {
-                    if (#expr) {
-                        return result;
-                    }
-                },
-                None => quote! {},
-            };
-            quote! {
-                #(#attrs)*
-                #vis #asyncness fn #ident #generics(#inputs) #output #where_clause {
-                    #condition_check
-
-                    use spring_redis::redis::{self, AsyncCommands};
-                    use spring::{plugin::ComponentRegistry, App};
-
-                    let mut redis = App::global()
-                        .get_component::<spring_redis::Redis>()
-                        .expect("redis component not found");
-
-                    let cache_key = format!(#cache_key_fmt);
-
-                    if let Ok(Some(cache_value)) = redis.get::<_, Option<String>>(&cache_key).await {
-                        match ::serde_json::from_str::<#ret_type>(&cache_value) {
-                            Ok(value) => return value,
-                            Err(e) => {
-                                ::spring::tracing::error!("cache decode error for {}: {:?}", cache_key, e);
-                            }
-                        }
-                    }
+            #unless_check
 
-                    let result: #ret_type = (|| async #user_block)().await;
+            #cache_store
 
-                    #unless_check
+            #wrap_result
+        },
+        CacheOperation::Put => quote! {
+            let result: #ret_type = (|| async #user_block)().await;
+            #unwrap_result
 
-                    match ::serde_json::to_string(&result) {
-                        Ok(cache_value) => {
-                            #redis_set_stmt
-                        }
-                        Err(err) => {
-                            ::spring::tracing::error!("cache encode failed for key {}: {:?}", cache_key, err);
-                        }
-                    }
+            #unless_check
 
-                    result
-                }
-            }
+            #cache_store
+
+            #wrap_result
+        },
+        CacheOperation::Evict => quote! {
+            let result: #ret_type = (|| async #user_block)().await;
+            #unwrap_result
+
+            #cache_evict
+
+            #wrap_result
+        },
+    };
+
+    let gen_code = quote! {
+        #(#attrs)*
+        #vis #asyncness fn #ident #generics(#inputs) #output #where_clause {
+            #condition_check
+
+            #cache_lookup
+
+            #body
         }
     };
 
@@ -1,5 +1,6 @@
 //! Attribute parsing for the `#[component]` macro
 
+use crate::condition::Condition;
 use proc_macro2::TokenStream;
 use syn::{parse::Parse, parse2, LitStr, Result};
 
@@ -8,6 +9,10 @@ use syn::{parse::Parse, parse2, LitStr, Result};
 pub struct ComponentAttrs {
     /// Custom Plugin name (optional)
     pub name: Option<String>,
+    /// Profile expression, the component is only registered when it matches the active profiles (optional)
+    pub profile: Option<String>,
+    /// Conditions which must all match to register the component (optional)
+    pub conditions: Vec<Condition>,
 }
 
 impl Parse for ComponentAttrs {
@@ -18,20 +23,25 @@ impl Parse for ComponentAttrs {
             return Ok(attrs);
         }
 
-        // Parse `name = "PluginName"`
-        let lookahead = input.lookahead1();
-        if lookahead.peek(syn::Ident) {
+        // Parse `name = "PluginName", profile = "staging", condition = on_property("key")`
+        while !input.is_empty() {
             let ident: syn::Ident = input.parse()?;
-            if ident == "name" {
-                input.parse::<syn::Token![=]>()?;
-                let lit: LitStr = input.parse()?;
-                attrs.name = Some(lit.value());
+            input.parse::<syn::Token![=]>()?;
+            if ident == "condition" {
+                attrs.conditions.push(input.parse()?);
+            } else if ident == "name" {
+                attrs.name = Some(input.parse::<LitStr>()?.value());
+            } else if ident == "profile" {
+                attrs.profile = Some(input.parse::<LitStr>()?.value());
             } else {
                 return Err(syn::Error::new_spanned(
                     ident,
-                    "unsupported attribute, only `name` is supported",
+                    "unsupported attribute, only `name`, `profile` and `condition` are supported",
                 ));
             }
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
         }
 
         Ok(attrs)
@@ -66,6 +76,23 @@ mod tests {
         assert_eq!(attrs.name, Some("MyPlugin".to_string()));
     }
 
+    #[test]
+    fn test_parse_profile_attr() {
+        let input = quote! { name = "MyPlugin", profile = "!prod" };
+        let attrs = parse_component_attrs(input).unwrap();
+        assert_eq!(attrs.name, Some("MyPlugin".to_string()));
+        assert_eq!(attrs.profile, Some("!prod".to_string()));
+    }
+
+    #[test]
+    fn test_parse_condition_attr() {
+        let input =
+            quote! { condition = on_property("cache.enabled"), condition = on_missing_component };
+        let attrs = parse_component_attrs(input).unwrap();
+        assert_eq!(attrs.conditions.len(), 2);
+        assert!(Condition::is_deferred(&attrs.conditions));
+    }
+
     #[test]
     fn test_parse_invalid_attr() {
         let input = quote! { invalid = "value" };
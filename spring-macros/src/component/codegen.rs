@@ -4,7 +4,13 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::{FnArg, ItemFn};
 
-use super::{attrs::ComponentAttrs, dependency::DependencyInfo, utils};
+use crate::condition::Condition;
+
+use super::{
+    attrs::ComponentAttrs,
+    dependency::DependencyInfo,
+    utils::{self, extract_type_name},
+};
 
 /// Generate Plugin implementation
 pub fn generate_plugin_impl(
@@ -71,6 +77,34 @@ pub fn generate_plugin_impl(
         }
     };
 
+    // Skip the component when the profile expression does not match
+    let profile_check = match &attrs.profile {
+        Some(profile) => quote! {
+            if !app.get_profiles().accepts(#profile) {
+                return;
+            }
+        },
+        None => quote! {},
+    };
+
+    // Skip the component when one of the conditions does not match
+    let conditions = &attrs.conditions;
+    let condition_check = if conditions.is_empty() {
+        quote! {}
+    } else {
+        let component_type = if is_result {
+            utils::extract_generic_type(&return_type).unwrap_or_else(|| return_type.clone())
+        } else {
+            return_type.clone()
+        };
+        quote! {
+            if !::spring::plugin::condition::matches_all::<#component_type>(app, &[#(#conditions),*]) {
+                return;
+            }
+        }
+    };
+    let deferred = Condition::is_deferred(conditions);
+
     quote! {
         struct #plugin_struct_name;
 
@@ -79,7 +113,11 @@ pub fn generate_plugin_impl(
             async fn build(&self, app: &mut ::spring::app::AppBuilder) {
                 use ::spring::config::ConfigRegistry;
                 use ::spring::plugin::{ComponentRegistry, MutableComponentRegistry};
-                
+
+                #profile_check
+
+                #condition_check
+
                 #(#param_extractions)*
 
                 #result_handling
@@ -94,6 +132,10 @@ pub fn generate_plugin_impl(
             fn dependencies(&self) -> Vec<&str> {
                 vec![#(#dependency_names),*]
             }
+
+            fn deferred(&self) -> bool {
+                #deferred
+            }
         }
     }
 }
@@ -124,20 +166,6 @@ fn generate_plugin_struct_name(attrs: &ComponentAttrs, return_type: &syn::Type)
     }
 }
 
-/// Extract type name from Type
-fn extract_type_name(ty: &syn::Type) -> String {
-    match ty {
-        syn::Type::Path(type_path) => {
-            if let Some(segment) = type_path.path.segments.last() {
-                segment.ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        }
-        _ => "Unknown".to_string(),
-    }
-}
-
 /// Check if type is Result<T, E>
 fn is_result_type(ty: &syn::Type) -> bool {
     if let syn::Type::Path(type_path) = ty {
@@ -2,7 +2,7 @@
 
 use syn::{Attribute, FnArg, ItemFn, LitStr, Meta, Type};
 
-use crate::component::utils::extract_generic_type;
+use crate::component::utils::{extract_generic_type, extract_type_name};
 
 /// Information about a component dependency
 #[derive(Debug, Clone)]
@@ -77,20 +77,6 @@ fn infer_plugin_name(component_type: &Type) -> String {
     format!("__Create{}Plugin", type_name)
 }
 
-/// Extract the type name from a Type
-fn extract_type_name(ty: &Type) -> String {
-    match ty {
-        Type::Path(type_path) => {
-            if let Some(segment) = type_path.path.segments.last() {
-                segment.ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        }
-        _ => "Unknown".to_string(),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
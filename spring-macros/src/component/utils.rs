@@ -1,6 +1,6 @@
 //! Utility functions for the component macro
 
-use syn::{GenericArgument, PathArguments, Type};
+use syn::{GenericArgument, PathArguments, Type, TypeParamBound};
 
 /// Extract the generic type T from a wrapper type like Config<T> or Component<T>
 pub fn extract_generic_type(ty: &Type) -> Option<Type> {
@@ -16,6 +16,27 @@ pub fn extract_generic_type(ty: &Type) -> Option<Type> {
     None
 }
 
+/// Extract the type name used in the generated Plugin names.
+/// A trait object like `Arc<dyn UserRepository>` is named after the trait: `DynUserRepository`
+pub fn extract_type_name(ty: &Type) -> String {
+    let Type::Path(type_path) = ty else {
+        return "Unknown".to_string();
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return "Unknown".to_string();
+    };
+    if let Some(Type::TraitObject(trait_object)) = extract_generic_type(ty) {
+        let trait_name = trait_object.bounds.iter().find_map(|bound| match bound {
+            TypeParamBound::Trait(bound) => bound.path.segments.last(),
+            _ => None,
+        });
+        if let Some(trait_name) = trait_name {
+            return format!("Dyn{}", trait_name.ident);
+        }
+    }
+    segment.ident.to_string()
+}
+
 /// Extract the return type from function signature
 pub fn extract_return_type(output: &syn::ReturnType) -> Option<Type> {
     match output {
@@ -44,6 +65,16 @@ mod tests {
         assert_eq!(quote!(#ok_ty).to_string(), "MyComponent");
     }
 
+    #[test]
+    fn test_extract_type_name() {
+        let ty: Type = parse2(quote! { DbConnection }).unwrap();
+        assert_eq!(extract_type_name(&ty), "DbConnection");
+        let ty: Type = parse2(quote! { Arc<dyn UserRepository + Send + Sync> }).unwrap();
+        assert_eq!(extract_type_name(&ty), "DynUserRepository");
+        let ty: Type = parse2(quote! { Arc<Mutex<Cache>> }).unwrap();
+        assert_eq!(extract_type_name(&ty), "Arc");
+    }
+
     #[test]
     fn test_extract_return_type() {
         let output: syn::ReturnType = parse2(quote! { -> MyComponent }).unwrap();
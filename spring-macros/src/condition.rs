@@ -0,0 +1,90 @@
+//! Conditions of `#[component(condition = ...)]` and `#[service(condition = ...)]`
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{parenthesized, parse::Parse, LitStr, Token};
+
+const CONDITION_TIP: &str = "unknown condition, expected on_property(\"key\"), on_property(\"key\", \"value\"), on_profile(\"profiles\") or on_missing_component";
+
+/// A condition of the registration, see `spring::plugin::condition::Condition`
+#[derive(Debug, Clone)]
+pub(crate) enum Condition {
+    Property(LitStr, Option<LitStr>),
+    Profile(LitStr),
+    MissingComponent,
+}
+
+impl Condition {
+    /// The plugin registering the component is deferred, so that the other plugins can register the component first
+    pub(crate) fn is_deferred(conditions: &[Condition]) -> bool {
+        conditions
+            .iter()
+            .any(|c| matches!(c, Self::MissingComponent))
+    }
+}
+
+impl Parse for Condition {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ident: syn::Ident = input.parse()?;
+        if ident == "on_property" {
+            let content;
+            parenthesized!(content in input);
+            let key = content.parse::<LitStr>()?;
+            let value = if content.peek(Token![,]) {
+                content.parse::<Token![,]>()?;
+                Some(content.parse::<LitStr>()?)
+            } else {
+                None
+            };
+            return Ok(Self::Property(key, value));
+        }
+        if ident == "on_profile" {
+            let content;
+            parenthesized!(content in input);
+            return Ok(Self::Profile(content.parse::<LitStr>()?));
+        }
+        if ident == "on_missing_component" {
+            return Ok(Self::MissingComponent);
+        }
+        Err(syn::Error::new_spanned(ident, CONDITION_TIP))
+    }
+}
+
+impl ToTokens for Condition {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Self::Property(key, None) => quote! {
+                ::spring::plugin::condition::Condition::OnProperty { key: #key, value: None }
+            },
+            Self::Property(key, Some(value)) => quote! {
+                ::spring::plugin::condition::Condition::OnProperty { key: #key, value: Some(#value) }
+            },
+            Self::Profile(profiles) => quote! {
+                ::spring::plugin::condition::Condition::OnProfile(#profiles)
+            },
+            Self::MissingComponent => quote! {
+                ::spring::plugin::condition::Condition::OnMissingComponent
+            },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Condition;
+    use quote::quote;
+    use syn::parse2;
+
+    #[test]
+    fn test_parse_condition() {
+        let condition = parse2::<Condition>(quote! { on_property("cache.type", "redis") }).unwrap();
+        assert!(
+            matches!(condition, Condition::Property(key, Some(value)) if key.value() == "cache.type" && value.value() == "redis")
+        );
+        let condition = parse2::<Condition>(quote! { on_profile("!prod") }).unwrap();
+        assert!(matches!(condition, Condition::Profile(profiles) if profiles.value() == "!prod"));
+        let condition = parse2::<Condition>(quote! { on_missing_component }).unwrap();
+        assert!(Condition::is_deferred(&[condition]));
+        assert!(parse2::<Condition>(quote! { on_bean("cache") }).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::input_and_compile_error;
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    punctuated::Punctuated, FnArg, GenericArgument, ItemFn, LitInt, MetaNameValue, PathArguments,
+    Token, Type,
+};
+
+const LISTENER_TIP: &str =
+    "invalid event listener, expected fn listener(event: &Event, Component(c): Component<T>, Config(c): Config<T>)";
+
+#[derive(Default)]
+struct EventListenerArgs {
+    order: Option<LitInt>,
+}
+
+impl syn::parse::Parse for EventListenerArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        for nv in Punctuated::<MetaNameValue, Token![,]>::parse_terminated(input)? {
+            if !nv.path.is_ident("order") {
+                return Err(syn::Error::new_spanned(
+                    nv.path,
+                    "unknown attribute key, expected #[event_listener(order = <i32>)]",
+                ));
+            }
+            match nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(order),
+                    ..
+                }) => args.order = Some(order),
+                value => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "order must be an integer literal",
+                    ))
+                }
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// A parameter of the listener after the event
+enum Param {
+    Component(Type),
+    Config(Type),
+}
+
+impl Param {
+    fn parse(ty: &Type) -> syn::Result<Self> {
+        let Type::Path(type_path) = ty else {
+            return Err(syn::Error::new_spanned(ty, LISTENER_TIP));
+        };
+        let segment = type_path
+            .path
+            .segments
+            .last()
+            .ok_or_else(|| syn::Error::new_spanned(ty, LISTENER_TIP))?;
+        let inner = match &segment.arguments {
+            PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(GenericArgument::Type(inner)) => inner.clone(),
+                _ => return Err(syn::Error::new_spanned(ty, LISTENER_TIP)),
+            },
+            _ => return Err(syn::Error::new_spanned(ty, LISTENER_TIP)),
+        };
+        if segment.ident == "Component" {
+            Ok(Self::Component(inner))
+        } else if segment.ident == "Config" {
+            Ok(Self::Config(inner))
+        } else {
+            Err(syn::Error::new_spanned(ty, LISTENER_TIP))
+        }
+    }
+}
+
+impl ToTokens for Param {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        tokens.extend(match self {
+            Self::Component(ty) => quote! {
+                ::spring::extractor::Component(
+                    ::spring::plugin::ComponentRegistry::get_expect_component::<#ty>(app)
+                )
+            },
+            Self::Config(ty) => quote! {
+                ::spring::extractor::Config(
+                    ::spring::config::ConfigRegistry::get_config::<#ty>(app)
+                        .expect(concat!("Config ", stringify!(#ty), " not found"))
+                )
+            },
+        });
+    }
+}
+
+struct EventListener {
+    name: syn::Ident,
+    order: TokenStream2,
+    event: Type,
+    params: Vec<Param>,
+    ast: ItemFn,
+    doc_attributes: Vec<syn::Attribute>,
+}
+
+impl EventListener {
+    fn new(args: EventListenerArgs, ast: ItemFn) -> syn::Result<Self> {
+        let mut inputs = ast.sig.inputs.iter().map(|arg| match arg {
+            FnArg::Typed(pat_type) => Ok(&*pat_type.ty),
+            FnArg::Receiver(receiver) => Err(syn::Error::new_spanned(receiver, LISTENER_TIP)),
+        });
+        let event = match inputs.next().transpose()? {
+            Some(Type::Reference(reference)) if reference.mutability.is_none() => {
+                (*reference.elem).clone()
+            }
+            Some(ty) => {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "the first parameter of an event listener must be a reference to the event: `event: &MyEvent`",
+                ))
+            }
+            None => return Err(syn::Error::new_spanned(&ast.sig, LISTENER_TIP)),
+        };
+        let params = inputs
+            .map(|ty| ty.and_then(Param::parse))
+            .collect::<syn::Result<_>>()?;
+        let order = match args.order {
+            Some(order) => order.into_token_stream(),
+            None => quote!(0),
+        };
+        let doc_attributes = ast
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"))
+            .cloned()
+            .collect();
+        Ok(Self {
+            name: ast.sig.ident.clone(),
+            order,
+            event,
+            params,
+            ast,
+            doc_attributes,
+        })
+    }
+}
+
+impl ToTokens for EventListener {
+    fn to_tokens(&self, output: &mut TokenStream2) {
+        let Self {
+            name,
+            order,
+            event,
+            params,
+            ast,
+            doc_attributes,
+        } = self;
+        let vis = &ast.vis;
+        let call = format_ident!("__call_{}", name);
+        let invoke = if ast.sig.asyncness.is_some() {
+            quote! { #name(event, #(#params),*).await; }
+        } else {
+            quote! { #name(event, #(#params),*); }
+        };
+
+        output.extend(quote! {
+            #(#doc_attributes)*
+            #[allow(non_camel_case_types, missing_docs)]
+            #vis struct #name;
+
+            impl ::spring::event::EventListenerRegistrar for #name {
+                fn listener(&self) -> ::spring::event::EventListener {
+                    #ast
+
+                    fn #call<'a>(
+                        app: &'a ::spring::App,
+                        event: &'a #event,
+                    ) -> ::spring::event::BoxFuture<'a, ()> {
+                        ::std::boxed::Box::pin(async move { #invoke })
+                    }
+
+                    ::spring::event::EventListener::new::<#event>(
+                        ::std::concat!(::std::module_path!(), "::", ::std::stringify!(#name)),
+                        #order,
+                        #call,
+                    )
+                }
+            }
+
+            ::spring::submit_event_listener!(#name);
+        });
+    }
+}
+
+pub(crate) fn listener(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match syn::parse::<EventListenerArgs>(args) {
+        Ok(args) => args,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+    let ast = match syn::parse::<ItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+    match EventListener::new(args, ast) {
+        Ok(listener) => listener.into_token_stream().into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
@@ -1,3 +1,4 @@
+use crate::condition::Condition;
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{
@@ -17,10 +18,12 @@ enum InjectableType {
     Component {
         optional: bool,
         component_type: syn::Path,
+        name: Option<syn::LitStr>,
     },
     LazyComponent {
         component_type: syn::Path,
     },
+    Components(syn::Path),
     Config(syn::Path),
     ComponentRef(syn::Path),
     ConfigRef(syn::Path),
@@ -34,6 +37,7 @@ impl InjectableType {
             Self::Option => 0,
             Self::Component { .. } => 1,
             Self::LazyComponent { .. } => 1,
+            Self::Components(_) => 1,
             Self::Config(_) => 2,
             Self::ComponentRef(_) => 3,
             Self::ConfigRef(_) => 4,
@@ -45,10 +49,24 @@ impl InjectableType {
     fn is_arg(&self) -> bool {
         matches!(self, Self::PrototypeArg(_))
     }
+
+    /// Type of the component which must be registered before the service is built
+    fn required_component(&self) -> Option<&syn::Path> {
+        match self {
+            Self::Component {
+                optional: false,
+                component_type,
+                ..
+            } => Some(component_type),
+            Self::ComponentRef(component_type) => Some(component_type),
+            _ => None,
+        }
+    }
 }
 
 enum InjectableAttr {
-    Component,
+    Component(Option<syn::LitStr>),
+    Components,
     Config,
     FuncCall(syn::ExprCall),
 }
@@ -85,7 +103,7 @@ impl Injectable {
                 } else {
                     Err(syn::Error::new_spanned(
                 inject_attr,
-                "invalid inject definition, expected #[inject(component|config|func(args))]",
+                "invalid inject definition, expected #[inject(component|component = \"name\"|components|config|func(args))]",
                     ))?;
                 }
             }
@@ -123,7 +141,7 @@ impl Injectable {
             Err(syn::Error::new_spanned(
             field,
             format!(
-                "{field_name} field missing inject definition, expected #[inject(component|config|func(args))]",
+                "{field_name} field missing inject definition, expected #[inject(component|component = \"name\"|components|config|func(args))]",
             )))
         }
     }
@@ -133,7 +151,14 @@ impl syn::parse::Parse for InjectableAttr {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let name = input.parse::<syn::Path>()?;
         if name.is_ident("component") {
-            return Ok(Self::Component);
+            if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                return Ok(Self::Component(Some(input.parse::<syn::LitStr>()?)));
+            }
+            return Ok(Self::Component(None));
+        }
+        if name.is_ident("components") {
+            return Ok(Self::Components);
         }
         if name.is_ident("config") {
             return Ok(Self::Config);
@@ -145,7 +170,7 @@ impl syn::parse::Parse for InjectableAttr {
         }
         Err(syn::Error::new(
             Span::call_site(),
-            "invalid inject definition, expected #[inject(component|config|func(args))]",
+            "invalid inject definition, expected #[inject(component|component = \"name\"|components|config|func(args))]",
         ))
     }
 }
@@ -153,7 +178,7 @@ impl syn::parse::Parse for InjectableAttr {
 impl InjectableAttr {
     fn make_type(self, ty: &syn::Path) -> syn::Result<InjectableType> {
         Ok(match self {
-            Self::Component => {
+            Self::Component(name) => {
                 let last_path_segment = ty.segments.last().ok_or_else(inject_error_tip)?;
 
                 let (optional, component_type) = if last_path_segment.ident == "Option" {
@@ -165,8 +190,19 @@ impl InjectableAttr {
                 InjectableType::Component {
                     optional,
                     component_type,
+                    name,
                 }
             }
+            Self::Components => {
+                let last_path_segment = ty.segments.last().ok_or_else(inject_error_tip)?;
+                if last_path_segment.ident != "Vec" {
+                    return Err(syn::Error::new_spanned(
+                        ty,
+                        "#[inject(components)] expects a Vec<T> field",
+                    ));
+                }
+                InjectableType::Components(get_argument_type(&last_path_segment.arguments)?)
+            }
             Self::Config => InjectableType::Config(ty.clone()),
             Self::FuncCall(func_call) => InjectableType::FuncCall(Box::new(func_call)),
         })
@@ -189,6 +225,27 @@ impl ToTokens for Injectable {
             InjectableType::Component {
                 optional,
                 component_type,
+                name: Some(name),
+            } => {
+                let registry = if *is_prototype {
+                    quote!(::spring::App::global())
+                } else {
+                    quote!(app)
+                };
+                if *optional {
+                    tokens.extend(quote! {
+                        let #field_name = #registry.get_component_named::<#component_type>(#name);
+                    });
+                } else {
+                    tokens.extend(quote! {
+                        let #field_name = #registry.try_get_component_named::<#component_type>(#name)?;
+                    });
+                }
+            }
+            InjectableType::Component {
+                optional,
+                component_type,
+                name: None,
             } =>
             {
                 #[allow(clippy::collapsible_else_if)]
@@ -221,6 +278,17 @@ impl ToTokens for Injectable {
                     let #field_name = ::spring::plugin::LazyComponent::<#component_type>::new();
                 });
             }
+            InjectableType::Components(component_type) => {
+                if *is_prototype {
+                    tokens.extend(quote! {
+                        let #field_name = ::spring::App::global().get_components::<#component_type>();
+                    });
+                } else {
+                    tokens.extend(quote! {
+                        let #field_name = app.get_components::<#component_type>();
+                    });
+                }
+            }
             InjectableType::Config(type_path) => {
                 if *is_prototype {
                     tokens.extend(quote! {
@@ -273,6 +341,9 @@ struct Service {
     generics: syn::Generics,
     ident: proc_macro2::Ident,
     attr: Option<ServiceAttr>,
+    conditions: Vec<Condition>,
+    callbacks: ServiceCallbacks,
+    scope: Option<syn::Ident>,
     fields: Vec<Injectable>,
 }
 
@@ -281,6 +352,21 @@ enum ServiceAttr {
     Prototype(syn::LitStr),
 }
 
+/// Async methods called after the service is injected and when the app shuts down
+#[derive(Default)]
+struct ServiceCallbacks {
+    init: Option<syn::Ident>,
+    destroy: Option<syn::Ident>,
+}
+
+struct ServiceAttrs {
+    attr: Option<ServiceAttr>,
+    conditions: Vec<Condition>,
+    callbacks: ServiceCallbacks,
+    /// `Prototype` or `Request`, singleton services have no scope
+    scope: Option<syn::Ident>,
+}
+
 impl Service {
     fn new(input: syn::DeriveInput) -> syn::Result<Self> {
         let syn::DeriveInput {
@@ -290,10 +376,20 @@ impl Service {
             data,
             ..
         } = input;
-        let service_attr = attrs
-            .iter()
-            .find(|a| a.path().is_ident("service"))
-            .and_then(|attr| attr.parse_args_with(Self::parse_service_attr).ok());
+        let ServiceAttrs {
+            attr: service_attr,
+            conditions,
+            callbacks,
+            scope,
+        } = match attrs.iter().find(|a| a.path().is_ident("service")) {
+            Some(attr) => attr.parse_args_with(Self::parse_service_attr)?,
+            None => ServiceAttrs {
+                attr: None,
+                conditions: vec![],
+                callbacks: ServiceCallbacks::default(),
+                scope: None,
+            },
+        };
 
         let is_prototype = matches!(&service_attr, Some(ServiceAttr::Prototype(_)));
         let mut fields = if let syn::Data::Struct(data) = data {
@@ -311,17 +407,26 @@ impl Service {
             generics,
             ident,
             attr: service_attr,
+            conditions,
+            callbacks,
+            scope,
             fields,
         })
     }
-    fn parse_service_attr(input: syn::parse::ParseStream) -> syn::Result<ServiceAttr> {
+    fn parse_service_attr(input: syn::parse::ParseStream) -> syn::Result<ServiceAttrs> {
         let mut grpc: Option<syn::Path> = None;
         let mut prototype: Option<syn::LitStr> = None;
+        let mut conditions = vec![];
+        let mut callbacks = ServiceCallbacks::default();
+        let mut scope: Option<syn::Ident> = None;
 
         while !input.is_empty() {
             let ident: syn::Ident = input.parse()?;
 
-            if input.peek(syn::Token![=]) {
+            if input.peek(syn::Token![=]) && ident == "condition" {
+                input.parse::<syn::Token![=]>()?;
+                conditions.push(input.parse::<Condition>()?);
+            } else if input.peek(syn::Token![=]) {
                 input.parse::<syn::Token![=]>()?;
                 let value: syn::LitStr = input.parse()?;
 
@@ -344,10 +449,21 @@ impl Service {
                         }
                         prototype = Some(value);
                     }
+                    "init" => callbacks.init = Some(value.parse()?),
+                    "destroy" => callbacks.destroy = Some(value.parse()?),
+                    "scope" => scope = match value.value().as_str() {
+                        "singleton" => None,
+                        "prototype" => Some(syn::Ident::new("Prototype", value.span())),
+                        "request" => Some(syn::Ident::new("Request", value.span())),
+                        _ => return Err(syn::Error::new_spanned(
+                            value,
+                            "unknown scope, expected \"singleton\", \"prototype\" or \"request\"",
+                        )),
+                    },
                     other => {
                         return Err(syn::Error::new_spanned(
                             ident,
-                            format!("Unknown key `{other}` in #[service(...)], expected `grpc` or `prototype`"),
+                            format!("Unknown key `{other}` in #[service(...)], expected `grpc`, `prototype`, `scope`, `condition`, `init` or `destroy`"),
                         ));
                     }
                 }
@@ -385,15 +501,42 @@ impl Service {
             }
         }
 
-        match (grpc, prototype) {
-            (Some(path), None) => Ok(ServiceAttr::Grpc(path)),
-            (None, Some(litstr_opt)) => Ok(ServiceAttr::Prototype(litstr_opt)),
-            (None, None) => Err(syn::Error::new(
+        if scope.is_some()
+            && (grpc.is_some() || callbacks.init.is_some() || callbacks.destroy.is_some())
+        {
+            return Err(syn::Error::new(
                 input.span(),
-                "Expected at least one of `grpc` or `prototype`",
-            )),
-            _ => unreachable!(),
+                "scoped services are created on demand, `grpc`, `init` and `destroy` are only supported by singleton services",
+            ));
         }
+        let registered = !conditions.is_empty()
+            || callbacks.init.is_some()
+            || callbacks.destroy.is_some()
+            || scope.is_some();
+        let attr = match (grpc, prototype) {
+            (Some(path), None) => Some(ServiceAttr::Grpc(path)),
+            (None, Some(_)) if registered => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "prototype services are not registered, `scope`, `condition`, `init` and `destroy` are not supported",
+                ))
+            }
+            (None, Some(litstr_opt)) => Some(ServiceAttr::Prototype(litstr_opt)),
+            (None, None) if registered => None,
+            (None, None) => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "Expected at least one of `grpc`, `prototype`, `scope`, `condition`, `init` or `destroy`",
+                ))
+            }
+            _ => unreachable!(),
+        };
+        Ok(ServiceAttrs {
+            attr,
+            conditions,
+            callbacks,
+            scope,
+        })
     }
 }
 
@@ -403,6 +546,9 @@ impl ToTokens for Service {
             generics,
             ident,
             attr,
+            conditions,
+            callbacks,
+            scope,
             fields,
         } = self;
         let field_names: Vec<&syn::Ident> = fields.iter().map(|f| &f.field_name).collect();
@@ -432,12 +578,55 @@ impl ToTokens for Service {
             _ => {
                 let service_registrar =
                     syn::Ident::new(&format!("__ServiceRegistrarFor_{ident}"), ident.span());
-                let service_installer = match attr {
-                    Some(ServiceAttr::Grpc(server)) => {
+                let dependencies = fields.iter().filter_map(|f| f.ty.required_component());
+                let condition_check = if conditions.is_empty() {
+                    quote! {}
+                } else {
+                    quote! {
+                        if !::spring::plugin::condition::matches_all::<#ident>(app, &[#(#conditions),*]) {
+                            return Ok(());
+                        }
+                    }
+                };
+                let init_hook = callbacks.init.as_ref().map(|init| {
+                    quote! {
+                        let init_service = service.clone();
+                        app.add_init_hook(move || {
+                            Box::new(async move {
+                                init_service.#init().await?;
+                                ::spring::tracing::debug!("{} initialized", stringify!(#ident));
+                                Ok::<_, ::spring::error::AppError>(())
+                            })
+                        });
+                    }
+                });
+                let destroy_hook = callbacks.destroy.as_ref().map(|destroy| {
+                    quote! {
+                        let destroy_service = service.clone();
+                        app.add_shutdown_hook(move |_| {
+                            Box::new(async move {
+                                destroy_service.#destroy().await?;
+                                Ok::<_, ::spring::error::AppError>(format!("{} destroyed", stringify!(#ident)))
+                            })
+                        });
+                    }
+                });
+                let service_installer = match (attr, scope) {
+                    (_, Some(scope)) => {
+                        quote! {
+                            use ::spring::plugin::MutableComponentRegistry;
+                            app.add_scoped_component(::spring::plugin::scope::Scope::#scope, |registry| {
+                                <#ident as ::spring::plugin::service::Service>::build(&registry)
+                            });
+                        }
+                    }
+                    (Some(ServiceAttr::Grpc(server)), None) => {
                         quote! {
                             use ::spring::plugin::MutableComponentRegistry;
                             use ::spring_grpc::GrpcConfigurator;
                             let service = #ident::build(app)?;
+                            #init_hook
+                            #destroy_hook
                             let grpc_server = #server::new(service.clone());
                             app.add_component(service).add_service(grpc_server);
                         }
@@ -445,7 +634,10 @@ impl ToTokens for Service {
                     _ => {
                         quote! {
                             use ::spring::plugin::MutableComponentRegistry;
-                            app.add_component(#ident::build(app)?);
+                            let service = #ident::build(app)?;
+                            #init_hook
+                            #destroy_hook
+                            app.add_component(service);
                         }
                     }
                 };
@@ -463,9 +655,18 @@ impl ToTokens for Service {
                     struct #service_registrar;
                     impl ::spring::plugin::service::ServiceRegistrar for #service_registrar{
                         fn install_service(&self, app: &mut ::spring::app::AppBuilder)->::spring::error::Result<()> {
+                            #condition_check
                             #service_installer
                             Ok(())
                         }
+
+                        fn service_name(&self) -> &'static str {
+                            ::std::any::type_name::<#ident>()
+                        }
+
+                        fn dependencies(&self) -> Vec<&'static str> {
+                            vec![#(::std::any::type_name::<#dependencies>()),*]
+                        }
                     }
                     ::spring::submit_service!(#service_registrar);
                 }
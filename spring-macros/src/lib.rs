@@ -5,12 +5,15 @@
 mod auto;
 mod cache;
 mod component;
+mod condition;
 mod config;
+mod event;
 mod problem_details;
 mod inject;
 mod job;
 mod middlewares;
 mod nest;
+mod redis;
 mod route;
 #[cfg(feature = "socket_io")]
 mod socketioxide;
@@ -307,6 +310,64 @@ pub fn auto_config(args: TokenStream, input: TokenStream) -> TokenStream {
     auto::config(args, input)
 }
 
+/// Registers a listener of the application events published by `ApplicationEventPublisher`.
+///
+/// # Syntax
+/// ```plain
+/// #[event_listener]
+/// #[event_listener(order = 10)]
+/// ```
+///
+/// # Attributes
+/// - `order = <i32>`: the listeners of an event are called by ascending order, `0` by default.
+///
+/// The first parameter is a reference to the event, it can be followed by `Component<T>` and `Config<T>` parameters.
+/// Both sync and async functions are supported.
+///
+/// # Examples
+/// ```ignore
+/// #[event_listener]
+/// async fn send_welcome_mail(event: &UserRegistered, Component(mailer): Component<Mailer>) {
+///     mailer.send_welcome(&event.email).await;
+/// }
+///
+/// #[event_listener(order = -1)]
+/// fn on_ready(_event: &AppReady) {
+///     tracing::info!("ready to serve");
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn event_listener(args: TokenStream, input: TokenStream) -> TokenStream {
+    event::listener(args, input)
+}
+
+/// Subscribes a function to redis channels, it's called with each message published to them.
+///
+/// # Syntax
+/// ```plain
+/// #[redis_listener("<channel>", "<channel2>", pattern = "<pattern>", pattern = "<pattern2>")]
+/// ```
+///
+/// # Attributes
+/// - `"<channel>"`: the channels subscribed with `SUBSCRIBE`.
+/// - `pattern = "<pattern>"`: the patterns of channels subscribed with `PSUBSCRIBE`, e.g. `"user.*"`.
+///
+/// The parameters of the function are extracted from the message:
+/// `Payload<T>`, `Json<T>`, `Channel`, `Pattern`, `Msg`, `Component<T>` and `Config<T>`.
+/// The subscriptions are renewed when the connection is lost.
+///
+/// # Examples
+/// ```ignore
+/// #[redis_listener("user.created", pattern = "order.*")]
+/// async fn on_event(Channel(channel): Channel, Json(event): Json<Event>, Component(db): Component<DbConn>) {
+///     tracing::info!("{channel}: {event:?}");
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn redis_listener(args: TokenStream, input: TokenStream) -> TokenStream {
+    redis::listener(args, input)
+}
+
 /// stream macro
 #[proc_macro_attribute]
 pub fn stream_listener(args: TokenStream, input: TokenStream) -> TokenStream {
@@ -376,15 +437,25 @@ pub fn derive_service(input: TokenStream) -> TokenStream {
 /// # Attributes
 /// - `name = "PluginName"` - **Optional**: Custom Plugin name. If not specified, the name
 ///   is automatically generated as `__Create{TypeName}Plugin`.
+/// - `profile = "staging"` - **Optional**: Only register the component when the profile expression
+///   matches the active profiles, e.g. `"staging,prod"` or `"!prod"`.
+/// - `condition = ...` - **Optional**, can be repeated: Only register the component when the condition matches
+///   - `on_property("key")`: the property is set and is not `false`
+///   - `on_property("key", "value")`: the property is equal to `value`
+///   - `on_profile("staging,prod")`: the profile expression matches the active profiles
+///   - `on_missing_component`: no other plugin registered a component of the same type,
+///     the plugin is built after the others
 ///
 /// # Parameters
 /// - `Config<T>` - Inject configuration of type `T` (must implement `Configurable`)
 /// - `Component<T>` - Inject another component of type `T`
 ///   - Can use `#[inject("PluginName")]` attribute to specify explicit dependency
-///   - Without `#[inject]`, dependency is inferred as `__Create{T}Plugin`
+///   - Without `#[inject]`, dependency is inferred as `__Create{T}Plugin`,
+///     or `__CreateDyn{Trait}Plugin` for a trait object like `Arc<dyn Trait>`
 ///
 /// # Return Type
 /// - Must implement `Clone + Send + Sync + 'static`
+/// - Can be a trait object like `Arc<dyn UserRepository>`, injected as `Component<Arc<dyn UserRepository>>`
 /// - Can return `Result<T, E>` for fallible initialization (will panic on error)
 /// - Each component type can only be registered once
 ///
@@ -493,6 +564,31 @@ pub fn derive_service(input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// ## Profile Specific Component
+///
+/// Register a component only when one of the profiles is active:
+/// ```rust,ignore
+/// #[component(profile = "!prod")]
+/// fn create_mock_mailer() -> MockMailer {
+///     MockMailer::default()
+/// }
+/// ```
+///
+/// ## Conditional Component
+///
+/// Ship a default component which the application can replace, and register an optional one from the configuration:
+/// ```rust,ignore
+/// #[component(condition = on_missing_component)]
+/// fn create_memory_cache() -> Arc<dyn Cache> {
+///     Arc::new(MemoryCache::default())
+/// }
+///
+/// #[component(condition = on_property("metrics.enabled"))]
+/// fn create_metrics(Config(config): Config<MetricsConfig>) -> Metrics {
+///     Metrics::new(config)
+/// }
+/// ```
+///
 /// ## Explicit Dependency
 ///
 /// Use `#[inject("PluginName")]` when the dependency name cannot be inferred:
@@ -527,7 +623,8 @@ pub fn derive_service(input: TokenStream) -> TokenStream {
 /// # Best Practices
 ///
 /// 1. **Keep component functions simple** - They should only create and configure the component
-/// 2. **Use NewType pattern for multiple instances** - Wrap the same type in different structs
+/// 2. **Use NewType pattern for multiple instances** - Wrap the same type in different structs,
+///    or register them as named components in a plugin with `add_named_component`
 /// 3. **Prefer configuration over hardcoding** - Use `Config<T>` for all configurable values
 /// 4. **Use `Arc<T>` for large components** - Reduces clone overhead
 /// 5. **Avoid circular dependencies** - Refactor your design if you encounter them
@@ -535,7 +632,8 @@ pub fn derive_service(input: TokenStream) -> TokenStream {
 ///
 /// # Limitations
 ///
-/// - Each component type can only be registered once (use NewType pattern for multiple instances)
+/// - Each component type can only be registered once (use NewType pattern for multiple instances,
+///   named components are registered with `add_named_component`)
 /// - Circular dependencies are not supported
 /// - Component types must implement `Clone + Send + Sync + 'static`
 /// - Configuration types must implement `Configurable + Deserialize`
@@ -557,9 +655,9 @@ pub fn component(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// to RFC 7807 Problem Details responses.
 ///
 /// Each variant must have a `#[status_code(code)]` attribute.
-/// 
+///
 /// ## Supported Attributes
-/// 
+///
 /// - `#[status_code(code)]` - **Required**: HTTP status code (e.g., 400, 404, 500)
 /// - `#[problem_type("uri")]` - **Optional**: Custom problem type URI
 /// - `#[title("title")]` - **Optional**: Custom problem title
@@ -567,7 +665,7 @@ pub fn component(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// - `#[instance("uri")]` - **Optional**: Problem instance URI
 ///
 /// ## Title Compatibility
-/// 
+///
 /// The `title` field can be automatically derived from the `#[error("...")]` attribute
 /// if no explicit `#[title("...")]` is provided. This provides compatibility with
 /// `thiserror::Error` and reduces duplication.
@@ -619,7 +717,10 @@ pub fn component(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// - `From<T> for ProblemDetails` trait for converting to Problem Details responses
 /// - `IntoResponse` trait for direct use in Axum handlers
 /// - OpenAPI integration for documentation generation
-#[proc_macro_derive(ProblemDetails, attributes(status_code, problem_type, title, detail, instance))]
+#[proc_macro_derive(
+    ProblemDetails,
+    attributes(status_code, problem_type, title, detail, instance)
+)]
 pub fn derive_problem_details(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as DeriveInput);
 
@@ -628,23 +729,43 @@ pub fn derive_problem_details(input: TokenStream) -> TokenStream {
         .into()
 }
 
-/// `#[cache]` - Transparent Redis-based caching for async functions.
+/// `#[cache]` - Transparent caching for async functions.
 ///
 /// This macro wraps an async function to automatically cache its result
-/// in Redis. It checks for a cached value before executing the function.
+/// in the cache returned by `spring::cache::cache_manager`: the `Arc<dyn CacheManager>` component
+/// registered by a plugin such as spring-redis, or an in-memory cache if no plugin registered one.
+/// It checks for a cached value before executing the function.
 /// If a cached result is found, it is deserialized and returned directly.
-/// Otherwise, the function runs normally and its result is stored in Redis.
+/// Otherwise, the function runs normally and its result is stored in the cache.
 ///
 /// # Syntax
 /// ```plain
-/// #[cache("key_pattern", expire = <seconds>, condition = <bool_expr>, unless = <bool_expr>)]
+/// #[cache("key_pattern", name = "<cache_name>", expire = <seconds>, jitter = <seconds>, negative_ttl = <seconds>,
+///     sync = "local" | "distributed", serializer = "json" | "msgpack" | "bincode", condition = <bool_expr>, unless = <bool_expr>)]
 /// ```
 ///
 /// # Attributes
 /// - `"key_pattern"` (**required**):
 ///   A format string used to generate the cache key. Function arguments can be interpolated using standard `format!` syntax.
+/// - `name = "<cache_name>"` (**optional**):
+///   The name of the cache, its backend is configured in `[cache.caches.<cache_name>]`. `"default"` if omitted.
 /// - `expire = <integer>` (**optional**):
-///   The number of seconds before the cached value expires. If omitted, the key will be stored without expiration.
+///   The number of seconds before the cached value expires. If omitted, the ttl configured for the cache is used,
+///   and the key is stored without expiration if there is none.
+/// - `jitter = <integer>` (**optional**):
+///   Up to this number of seconds are randomly added to `expire` and `negative_ttl`,
+///   so that the values cached at the same time don't all expire at the same time.
+/// - `negative_ttl = <integer>` (**optional**):
+///   The number of seconds before a `None` result expires, usually shorter than `expire`.
+///   Requires the function to return an `Option` or a `Result<Option<_>, _>`. Errors are never cached.
+/// - `sync = "local" | "distributed"` (**optional**):
+///   On a miss, only one caller computes the value of a key while the others wait for it:
+///   one caller per process with `"local"`, and one caller across the instances of the application
+///   with `"distributed"` if the cache is shared, e.g. stored in Redis.
+///   The distributed lock is held and waited for 10 seconds at most.
+/// - `serializer = "json" | "msgpack" | "bincode"` (**optional**):
+///   The format of the cached values, `"json"` if omitted.
+///   `"msgpack"` and `"bincode"` require the `cache-msgpack` and `cache-bincode` features of spring.
 /// - `condition = <expression>` (**optional**):
 ///   A boolean expression evaluated **before** executing the function.
 ///   If this evaluates to `false`, caching is completely bypassed — no lookup and no insertion.
@@ -674,7 +795,7 @@ pub fn derive_problem_details(input: TokenStream) -> TokenStream {
 ///
 /// struct MyError;
 ///
-/// #[cache("user:{user_id}", expire = 600, condition = user_id % 2 == 0, unless = result.is_none())]
+/// #[cache("user:{user_id}", name = "users", expire = 600, condition = user_id % 2 == 0, unless = result.is_none())]
 /// async fn get_user(user_id: u64) -> Result<Option<User>, MyError> {
 ///     // Fetch user from database
 ///     unimplemented!("do something")
@@ -682,7 +803,71 @@ pub fn derive_problem_details(input: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro_attribute]
 pub fn cache(args: TokenStream, input: TokenStream) -> TokenStream {
-    cache::cache(args, input)
+    cache::cache(cache::CacheOperation::Cacheable, args, input)
+}
+
+/// `#[cache_put]` - Always run the async function and store its result in the cache.
+///
+/// Unlike [`cache`](macro@cache), the cached value is never read:
+/// use it on the functions updating the data, so that the next reads get the new value.
+///
+/// # Syntax
+/// ```plain
+/// #[cache_put("key_pattern", name = "<cache_name>", expire = <seconds>, jitter = <seconds>, negative_ttl = <seconds>,
+///     serializer = "json" | "msgpack" | "bincode", condition = <bool_expr>, unless = <bool_expr>)]
+/// ```
+///
+/// The attributes are the same as the attributes of [`cache`](macro@cache), except `sync`.
+/// The `serializer` must be the one of the functions reading the value.
+///
+/// # Example
+/// ```rust,ignore
+/// #[cache_put("user:{user_id}", name = "users")]
+/// async fn rename_user(user_id: u64, name: String) -> Result<User, MyError> {
+///     // Update the user in the database
+///     Ok(User { id: user_id, name })
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn cache_put(args: TokenStream, input: TokenStream) -> TokenStream {
+    cache::cache(cache::CacheOperation::Put, args, input)
+}
+
+/// `#[cache_evict]` - Remove a cached value after the async function has run.
+///
+/// If the function returns a `Result`, the value is only removed when it returns `Ok`.
+///
+/// # Syntax
+/// ```plain
+/// #[cache_evict("key_pattern", name = "<cache_name>", condition = <bool_expr>)]
+/// #[cache_evict(all_entries, name = "<cache_name>", condition = <bool_expr>)]
+/// ```
+///
+/// # Attributes
+/// - `"key_pattern"` or `all_entries` (**required**):
+///   The key of the removed value, or `all_entries` to remove all the entries of the cache.
+/// - `name = "<cache_name>"` (**optional**):
+///   The name of the cache, `"default"` if omitted.
+/// - `condition = <expression>` (**optional**):
+///   A boolean expression evaluated **before** executing the function.
+///   If this evaluates to `false`, nothing is removed.
+///
+/// # Example
+/// ```rust,ignore
+/// #[cache_evict("user:{user_id}", name = "users")]
+/// async fn delete_user(user_id: u64) -> Result<(), MyError> {
+///     // Delete the user from the database
+///     Ok(())
+/// }
+///
+/// #[cache_evict(all_entries, name = "users")]
+/// async fn import_users(users: Vec<User>) {
+///     // Replace all the users
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn cache_evict(args: TokenStream, input: TokenStream) -> TokenStream {
+    cache::cache(cache::CacheOperation::Evict, args, input)
 }
 
 #[cfg(feature = "socket_io")]
@@ -0,0 +1,141 @@
+use crate::input_and_compile_error;
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{ItemFn, LitStr, MetaNameValue, Token};
+
+const LISTENER_TIP: &str = r#"invalid redis listener definition, expected #[redis_listener("<channel>", "<channel2>", pattern = "<pattern>")]"#;
+
+#[derive(Default)]
+struct RedisListenerArgs {
+    channels: Vec<LitStr>,
+    patterns: Vec<LitStr>,
+}
+
+impl syn::parse::Parse for RedisListenerArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        while input.peek(LitStr) {
+            args.channels.push(input.parse()?);
+            if !input.peek(Token![,]) {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        for pair in input.parse_terminated(MetaNameValue::parse, Token![,])? {
+            if !pair.path.is_ident("pattern") {
+                return Err(syn::Error::new_spanned(
+                    pair.path,
+                    "Unknown attribute key is specified; allowed: pattern",
+                ));
+            }
+            match pair.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(pattern),
+                    ..
+                }) => args.patterns.push(pattern),
+                value => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "pattern must be string literal",
+                    ))
+                }
+            }
+        }
+        if args.channels.is_empty() && args.patterns.is_empty() {
+            return Err(syn::Error::new(input.span(), LISTENER_TIP));
+        }
+        Ok(args)
+    }
+}
+
+struct RedisListener {
+    /// Name of the handler function being annotated.
+    name: syn::Ident,
+
+    /// Args passed to redis_listener macro.
+    args: RedisListenerArgs,
+
+    /// AST of the handler function being annotated.
+    ast: ItemFn,
+
+    /// The doc comment attributes to copy to generated struct, if any.
+    doc_attributes: Vec<syn::Attribute>,
+}
+
+impl RedisListener {
+    fn new(args: RedisListenerArgs, ast: ItemFn) -> syn::Result<Self> {
+        let name = ast.sig.ident.clone();
+
+        let doc_attributes = ast
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"))
+            .cloned()
+            .collect();
+
+        if ast.sig.asyncness.is_none() {
+            return Err(syn::Error::new_spanned(
+                ast.sig.fn_token,
+                "only support async fn",
+            ));
+        }
+
+        Ok(Self {
+            name,
+            args,
+            ast,
+            doc_attributes,
+        })
+    }
+}
+
+impl ToTokens for RedisListener {
+    fn to_tokens(&self, output: &mut TokenStream2) {
+        let Self {
+            name,
+            args,
+            ast,
+            doc_attributes,
+        } = self;
+        let vis = &ast.vis;
+        let channels = &args.channels;
+        let patterns = &args.patterns;
+
+        output.extend(quote! {
+            #(#doc_attributes)*
+            #[allow(non_camel_case_types, missing_docs)]
+            #vis struct #name;
+
+            impl ::spring_redis::pubsub::ListenerRegistrar for #name {
+                fn listener(&self) -> ::spring_redis::pubsub::Listener {
+                    #ast
+
+                    ::spring_redis::pubsub::Listener::new(
+                        ::std::concat!(::std::module_path!(), "::", ::std::stringify!(#name)),
+                        &[#(#channels),*],
+                        &[#(#patterns),*],
+                        #name,
+                    )
+                }
+            }
+
+            ::spring_redis::submit_listener!(#name);
+        });
+    }
+}
+
+pub(crate) fn listener(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match syn::parse::<RedisListenerArgs>(args) {
+        Ok(args) => args,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+    let ast = match syn::parse::<ItemFn>(input.clone()) {
+        Ok(ast) => ast,
+        Err(err) => return input_and_compile_error(input, err),
+    };
+    match RedisListener::new(args, ast) {
+        Ok(listener) => listener.into_token_stream().into(),
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
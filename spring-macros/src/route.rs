@@ -489,6 +489,7 @@ pub(crate) fn with_method(
     }
 }
 
+#[allow(clippy::result_large_err)]
 pub(crate) fn with_methods(input: TokenStream, openapi: bool) -> TokenStream {
     let mut ast = match syn::parse::<syn::ItemFn>(input.clone()) {
         Ok(ast) => ast,
@@ -1,5 +1,6 @@
 use schemars::JsonSchema;
 use serde::Deserialize;
+use spring::config::secret::Secret;
 use spring::config::Configurable;
 
 spring::submit_config_schema!("mail", MailerConfig);
@@ -40,6 +41,6 @@ pub struct SmtpTransportConfig {
 pub struct MailerAuth {
     /// User
     pub user: String,
-    /// Password
-    pub password: String,
+    /// Password, masked in `Debug` output
+    pub password: Secret<String>,
 }
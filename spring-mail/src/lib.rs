@@ -20,6 +20,7 @@ use lettre::transport::smtp::response::Severity;
 use lettre::{transport::smtp::authentication::Credentials, Tokio1Executor};
 use spring::async_trait;
 use spring::config::ConfigRegistry;
+use spring::health::{Health, HealthConfigurator, HealthIndicator};
 use spring::plugin::MutableComponentRegistry;
 use spring::{app::AppBuilder, error::Result, plugin::Plugin};
 
@@ -93,7 +94,35 @@ impl Plugin for MailPlugin {
             Mailer::Tokio(sender)
         };
 
-        app.add_component(mailer);
+        app.add_health_indicator(MailHealthIndicator(mailer.clone()))
+            .add_component(mailer);
+    }
+}
+
+/// Health indicator of the mailer, sends `NOOP` to the smtp server
+pub struct MailHealthIndicator(Mailer);
+
+#[async_trait]
+impl HealthIndicator for MailHealthIndicator {
+    fn name(&self) -> &str {
+        "mail"
+    }
+
+    async fn check(&self) -> Result<Health> {
+        match &self.0 {
+            Mailer::Tokio(transport) => {
+                let connected = transport
+                    .test_connection()
+                    .await
+                    .context("mail health check failed")?;
+                Ok(if connected {
+                    Health::up()
+                } else {
+                    Health::down().with_detail("error", "unable to connect to the mail server")
+                })
+            }
+            Mailer::Stub(_) => Ok(Health::up().with_detail("transport", "stub")),
+        }
     }
 }
 
@@ -108,7 +137,7 @@ impl MailPlugin {
         };
 
         if let Some(auth) = config.auth.as_ref() {
-            let credentials = Credentials::new(auth.user.clone(), auth.password.clone());
+            let credentials = Credentials::new(auth.user.clone(), auth.password.expose().clone());
             transport_builder = transport_builder.credentials(credentials);
         }
 
@@ -4,11 +4,12 @@
 pub mod config;
 
 use crate::config::*;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use opendal::Operator;
 use spring::app::AppBuilder;
 use spring::async_trait;
 use spring::config::ConfigRegistry;
+use spring::health::{Health, HealthConfigurator, HealthIndicator};
 use spring::plugin::{MutableComponentRegistry, Plugin};
 use std::str::FromStr;
 
@@ -24,7 +25,23 @@ impl Plugin for OpenDALPlugin {
             .expect("OpenDAL plugin config load failed");
 
         let connect = Self::operator(config).expect("OpenDAL operator construct failed");
-        app.add_component(connect);
+        app.add_health_indicator(OpenDALHealthIndicator(connect.clone()))
+            .add_component(connect);
+    }
+}
+
+/// Health indicator of the OpenDAL operator, checks that the storage is accessible
+pub struct OpenDALHealthIndicator(Op);
+
+#[async_trait]
+impl HealthIndicator for OpenDALHealthIndicator {
+    fn name(&self) -> &str {
+        "opendal"
+    }
+
+    async fn check(&self) -> spring::error::Result<Health> {
+        self.0.check().await.context("opendal health check failed")?;
+        Ok(Health::up().with_detail("scheme", self.0.info().scheme().to_string()))
     }
 }
 
@@ -45,7 +45,6 @@ use opentelemetry_semantic_conventions::attribute;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use spring::config::{ConfigRegistry, Configurable};
-use spring::plugin::component::ComponentRef;
 use spring::plugin::{ComponentRegistry, MutableComponentRegistry};
 use spring::{app::AppBuilder, error::Result, plugin::Plugin};
 use tracing_opentelemetry::{MetricsLayer, OpenTelemetryLayer};
@@ -59,7 +58,7 @@ struct OpenTelemetryConfig {
     enable: bool,
 }
 
-/// Routers collection
+/// Resource attributes collection, see [`ResourceConfigurator::opentelemetry_attrs`]
 pub type KeyValues = Vec<KeyValue>;
 
 pub struct OpenTelemetryPlugin;
@@ -210,7 +209,7 @@ impl OpenTelemetryPlugin {
     }
 
     fn build_resource(app: &AppBuilder) -> Resource {
-        let mut key_values = app.get_component::<KeyValues>().unwrap_or_default();
+        let mut key_values: KeyValues = app.get_components();
         key_values.push(KeyValue::new(
             attribute::DEPLOYMENT_ENVIRONMENT_NAME,
             format!("{:?}", app.get_env()),
@@ -257,17 +256,9 @@ impl ResourceConfigurator for AppBuilder {
     where
         KV: IntoIterator<Item = KeyValue>,
     {
-        if let Some(key_values) = self.get_component_ref::<KeyValues>() {
-            unsafe {
-                let raw_ptr = ComponentRef::into_raw(key_values);
-                let key_values = &mut *(raw_ptr as *mut KeyValues);
-                key_values.extend(kvs);
-            }
-            self
-        } else {
-            let mut key_values: KeyValues = vec![];
-            key_values.extend(kvs);
-            self.add_component(key_values)
+        for kv in kvs {
+            self.append_component(kv);
         }
+        self
     }
 }
@@ -5,10 +5,13 @@
 pub mod config;
 pub extern crate tokio_postgres as postgres;
 
+use anyhow::Context;
 use config::PgConfig;
 use spring::app::AppBuilder;
 use spring::async_trait;
 use spring::config::ConfigRegistry;
+use spring::error::Result;
+use spring::health::{Health, HealthConfigurator, HealthIndicator};
 use spring::plugin::{MutableComponentRegistry, Plugin};
 use std::sync::Arc;
 use tokio_postgres::NoTls;
@@ -34,6 +37,26 @@ impl Plugin for PgPlugin {
             }
         });
 
-        app.add_component(Postgres::new(client));
+        let client = Postgres::new(client);
+        app.add_health_indicator(PgHealthIndicator(client.clone()))
+            .add_component(client);
+    }
+}
+
+/// Health indicator of the postgres client, runs `SELECT 1`
+pub struct PgHealthIndicator(Postgres);
+
+#[async_trait]
+impl HealthIndicator for PgHealthIndicator {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    async fn check(&self) -> Result<Health> {
+        self.0
+            .simple_query("SELECT 1")
+            .await
+            .context("postgres health check failed")?;
+        Ok(Health::up())
     }
 }
@@ -0,0 +1,381 @@
+use crate::connection::RedisConnection;
+use anyhow::Context;
+use redis::AsyncCommands;
+use spring::async_trait;
+use spring::cache::{Cache, CacheConfig, CacheManager, CacheType, MemoryCache, DEFAULT_CACHE};
+use spring::error::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Cache stored in Redis, the keys of the cache named `name` are prefixed by `name::`.
+///
+/// The keys of the [`DEFAULT_CACHE`] are not prefixed, they are the keys of `#[cache]`
+/// before the caches had names.
+#[derive(Clone)]
+pub struct RedisCache {
+    redis: RedisConnection,
+    prefix: String,
+    ttl: Option<Duration>,
+}
+
+impl RedisCache {
+    /// Cache named `name` stored in `redis`
+    pub fn new(redis: impl Into<RedisConnection>, name: &str) -> Self {
+        Self {
+            redis: redis.into(),
+            prefix: match name {
+                DEFAULT_CACHE => String::new(),
+                name => format!("{name}::"),
+            },
+            ttl: None,
+        }
+    }
+
+    /// Expire the values stored without ttl after `ttl`
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{key}", self.prefix)
+    }
+
+    fn lock_key(&self, key: &str) -> String {
+        format!("{}{key}::lock", self.prefix)
+    }
+
+    /// Value of `key` with its remaining time to live, `None` if the value doesn't expire
+    async fn get_with_ttl(&self, key: &str) -> Result<Option<(Vec<u8>, Option<Duration>)>> {
+        let key = self.key(key);
+        let (value, pttl): (Option<Vec<u8>>, i64) = redis::pipe()
+            .get(&key)
+            .pttl(&key)
+            .query_async(&mut self.redis.clone())
+            .await
+            .with_context(|| format!("redis get {key} failed"))?;
+        // -1 if the key doesn't expire, -2 if it expired after GET
+        let ttl = match pttl {
+            -1 => None,
+            pttl => Some(Duration::from_millis(pttl.max(0) as u64)),
+        };
+        Ok(value.map(|value| (value, ttl)))
+    }
+}
+
+/// Delete the lock `KEYS[1]` only if it is still held with the token `ARGV[1]`
+const UNLOCK_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+#[async_trait]
+impl Cache for RedisCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let key = self.key(key);
+        Ok(self
+            .redis
+            .clone()
+            .get(&key)
+            .await
+            .with_context(|| format!("redis get {key} failed"))?)
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        let key = self.key(key);
+        let mut redis = self.redis.clone();
+        let result: redis::RedisResult<()> = match ttl.or(self.ttl) {
+            Some(ttl) if ttl.is_zero() => return Ok(()),
+            Some(ttl) => redis.pset_ex(&key, value, ttl.as_millis() as u64).await,
+            None => redis.set(&key, value).await,
+        };
+        Ok(result.with_context(|| format!("redis set {key} failed"))?)
+    }
+
+    async fn evict(&self, key: &str) -> Result<()> {
+        let key = self.key(key);
+        let _: () = self
+            .redis
+            .clone()
+            .del(&key)
+            .await
+            .with_context(|| format!("redis del {key} failed"))?;
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        if self.prefix.is_empty() {
+            // the keys of the default cache can't be told apart from the other keys of the database
+            return Err(anyhow::anyhow!(
+                "the entries of the {DEFAULT_CACHE} cache can't be cleared, name the cache to clear it"
+            )
+            .into());
+        }
+        let pattern = format!("{}*", escape_pattern(&self.prefix));
+        let mut redis = self.redis.clone();
+        if redis.is_cluster() {
+            // SCAN reads a single node of the cluster, KEYS is sent to all the masters
+            let keys: Vec<String> = redis
+                .keys(&pattern)
+                .await
+                .with_context(|| format!("redis keys {pattern} failed"))?;
+            if !keys.is_empty() {
+                let _: () = redis
+                    .del(keys)
+                    .await
+                    .with_context(|| format!("redis del {pattern} failed"))?;
+            }
+            return Ok(());
+        }
+        let mut cursor = 0u64;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(1000)
+                .query_async(&mut redis)
+                .await
+                .with_context(|| format!("redis scan {pattern} failed"))?;
+            if !keys.is_empty() {
+                let _: () = redis
+                    .del(keys)
+                    .await
+                    .with_context(|| format!("redis del {pattern} failed"))?;
+            }
+            if next == 0 {
+                return Ok(());
+            }
+            cursor = next;
+        }
+    }
+
+    async fn try_lock(&self, key: &str, token: &str, ttl: Duration) -> Result<bool> {
+        let key = self.lock_key(key);
+        let locked: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(token)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl.as_millis() as u64)
+            .query_async(&mut self.redis.clone())
+            .await
+            .with_context(|| format!("redis lock {key} failed"))?;
+        Ok(locked.is_some())
+    }
+
+    async fn unlock(&self, key: &str, token: &str) -> Result<()> {
+        let key = self.lock_key(key);
+        let _: i64 = redis::Script::new(UNLOCK_SCRIPT)
+            .key(&key)
+            .arg(token)
+            .invoke_async(&mut self.redis.clone())
+            .await
+            .with_context(|| format!("redis unlock {key} failed"))?;
+        Ok(())
+    }
+}
+
+/// Escape the glob characters of a `SCAN MATCH` pattern
+fn escape_pattern(prefix: &str) -> String {
+    let mut escaped = String::with_capacity(prefix.len());
+    for c in prefix.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// In-process cache in front of a [`RedisCache`].
+///
+/// The local entries are kept `local_ttl` at most, and not after their expiration in Redis:
+/// the changes made by the other instances of the application are seen after this delay.
+pub struct TwoLevelCache {
+    local: MemoryCache,
+    remote: RedisCache,
+    local_ttl: Duration,
+}
+
+impl TwoLevelCache {
+    /// Cache keeping up to `capacity` entries of `remote` in memory during `local_ttl`
+    pub fn new(remote: RedisCache, capacity: usize, local_ttl: Duration) -> Self {
+        Self {
+            local: MemoryCache::new(capacity),
+            remote,
+            local_ttl,
+        }
+    }
+
+    fn local_ttl(&self, ttl: Option<Duration>) -> Duration {
+        ttl.or(self.remote.ttl)
+            .map_or(self.local_ttl, |ttl| ttl.min(self.local_ttl))
+    }
+}
+
+#[async_trait]
+impl Cache for TwoLevelCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        if let Some(value) = self.local.get_value(key) {
+            return Ok(Some(value));
+        }
+        let Some((value, ttl)) = self.remote.get_with_ttl(key).await? else {
+            return Ok(None);
+        };
+        let local_ttl = self.local_ttl(ttl);
+        if !local_ttl.is_zero() {
+            self.local.put_value(key, value.clone(), Some(local_ttl));
+        }
+        Ok(Some(value))
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        self.remote.put(key, value.clone(), ttl).await?;
+        self.local.put_value(key, value, Some(self.local_ttl(ttl)));
+        Ok(())
+    }
+
+    async fn evict(&self, key: &str) -> Result<()> {
+        self.local.evict_value(key);
+        self.remote.evict(key).await
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.local.clear_values();
+        self.remote.clear().await
+    }
+
+    async fn try_lock(&self, key: &str, token: &str, ttl: Duration) -> Result<bool> {
+        self.remote.try_lock(key, token, ttl).await
+    }
+
+    async fn unlock(&self, key: &str, token: &str) -> Result<()> {
+        self.remote.unlock(key, token).await
+    }
+}
+
+/// Cache manager registered by the [`RedisPlugin`](crate::RedisPlugin).
+///
+/// The caches are stored in Redis unless the `[cache]` configuration selects
+/// another backend for them: `memory` or `two-level`.
+pub struct RedisCacheManager {
+    redis: RedisConnection,
+    config: CacheConfig,
+    caches: Mutex<HashMap<String, Arc<dyn Cache>>>,
+}
+
+impl RedisCacheManager {
+    /// Manager creating the caches configured by `config`
+    pub fn new(redis: impl Into<RedisConnection>, config: CacheConfig) -> Self {
+        Self {
+            redis: redis.into(),
+            config,
+            caches: Default::default(),
+        }
+    }
+}
+
+impl CacheManager for RedisCacheManager {
+    fn cache(&self, name: &str) -> Arc<dyn Cache> {
+        let mut caches = self.caches.lock().expect("cache manager lock poisoned");
+        if let Some(cache) = caches.get(name) {
+            return cache.clone();
+        }
+        let capacity = self.config.capacity(name);
+        let ttl = self.config.ttl(name);
+        let remote = RedisCache::new(self.redis.clone(), name).with_ttl(ttl);
+        let cache: Arc<dyn Cache> = match self.config.cache_type(name, CacheType::Redis) {
+            CacheType::Memory => Arc::new(MemoryCache::new(capacity).with_ttl(ttl)),
+            CacheType::Redis => Arc::new(remote),
+            CacheType::TwoLevel => Arc::new(TwoLevelCache::new(
+                remote,
+                capacity,
+                Duration::from_secs(self.config.local_ttl),
+            )),
+        };
+        caches.insert(name.to_string(), cache.clone());
+        cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_pattern, RedisCache, TwoLevelCache};
+    use spring::cache::Cache;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{TcpListener, TcpStream};
+
+    #[test]
+    fn test_escape_pattern() {
+        assert_eq!(escape_pattern("users::"), "users::");
+        assert_eq!(escape_pattern("a*b?[c]\\"), "a\\*b\\?\\[c\\]\\\\");
+    }
+
+    /// Read a command sent by the client
+    async fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim().strip_prefix('*')?.parse().ok()?;
+        let mut args = Vec::with_capacity(len);
+        for _ in 0..len {
+            line.clear();
+            reader.read_line(&mut line).await.ok()?;
+            let arg_len: usize = line.trim().strip_prefix('$')?.parse().ok()?;
+            let mut arg = vec![0; arg_len + 2];
+            reader.read_exact(&mut arg).await.ok()?;
+            arg.truncate(arg_len);
+            args.push(String::from_utf8(arg).ok()?);
+        }
+        Some(args)
+    }
+
+    /// Redis server storing `alice` with a remaining ttl of 50ms, recording the keys read with GET
+    async fn serve(server: TcpListener, reads: Arc<Mutex<Vec<String>>>) {
+        while let Ok((socket, _)) = server.accept().await {
+            let reads = reads.clone();
+            tokio::spawn(async move {
+                let mut reader = BufReader::new(socket);
+                while let Some(command) = read_command(&mut reader).await {
+                    let reply: &[u8] = match command[0].to_ascii_uppercase().as_str() {
+                        "GET" => {
+                            reads.lock().unwrap().push(command[1].clone());
+                            b"$5\r\nalice\r\n"
+                        }
+                        "PTTL" => b":50\r\n",
+                        _ => b"+OK\r\n",
+                    };
+                    reader.get_mut().write_all(reply).await.unwrap();
+                }
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_two_level_cache_remaining_ttl() {
+        let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        let reads = Arc::new(Mutex::new(vec![]));
+        tokio::spawn(serve(server, reads.clone()));
+
+        let client = redis::Client::open(format!("redis://{addr}")).unwrap();
+        let redis = client.get_connection_manager().await.unwrap();
+        let cache =
+            TwoLevelCache::new(RedisCache::new(redis, "users"), 10, Duration::from_secs(60));
+
+        assert_eq!(cache.get("1").await.unwrap().unwrap(), b"alice");
+        assert_eq!(cache.get("1").await.unwrap().unwrap(), b"alice");
+        assert_eq!(*reads.lock().unwrap(), ["users::1"]);
+
+        // the local entry expires with the remote one, not after the local ttl
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(cache.get("1").await.unwrap().unwrap(), b"alice");
+        assert_eq!(*reads.lock().unwrap(), ["users::1", "users::1"]);
+    }
+}
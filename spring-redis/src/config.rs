@@ -1,5 +1,6 @@
 use schemars::JsonSchema;
 use serde::Deserialize;
+use spring::config::secret::Secret;
 use spring::config::Configurable;
 
 spring::submit_config_schema!("redis", RedisConfig);
@@ -9,6 +10,9 @@ spring::submit_config_schema!("redis", RedisConfig);
 pub struct RedisConfig {
     /// The URI for connecting to the Redis server. For example:
     /// <redis://127.0.0.1/>
+    ///
+    /// Leave it empty when `cluster` or `sentinel` is configured.
+    #[serde(default)]
     pub uri: String,
 
     /// The new connection will time out operations after `response_timeout` has passed.
@@ -31,4 +35,68 @@ pub struct RedisConfig {
 
     /// Apply a maximum delay between connection attempts. The delay between attempts won't be longer than max_delay milliseconds.
     pub max_delay: Option<u64>,
+
+    /// Connect to a Redis Cluster instead of `uri`
+    pub cluster: Option<ClusterConfig>,
+
+    /// Connect to the master found by Redis Sentinel instead of `uri`
+    pub sentinel: Option<SentinelConfig>,
+
+    /// Pool of dedicated connections, registered as a [`RedisPool`](crate::RedisPool) component
+    pub pool: Option<PoolConfig>,
+}
+
+/// Configuration of a Redis Cluster in `[redis.cluster]`
+#[derive(Debug, Clone, JsonSchema, Deserialize)]
+pub struct ClusterConfig {
+    /// The URIs of the initial nodes of the cluster, the other nodes are discovered
+    pub nodes: Vec<String>,
+
+    /// Send the read-only commands to the replicas
+    #[serde(default)]
+    pub read_from_replicas: bool,
+}
+
+/// Configuration of Redis Sentinel in `[redis.sentinel]`
+#[derive(Debug, Clone, JsonSchema, Deserialize)]
+pub struct SentinelConfig {
+    /// The name of the master monitored by the sentinels
+    pub master: String,
+
+    /// The URIs of the sentinels
+    pub nodes: Vec<String>,
+
+    /// The database number of the master
+    #[serde(default)]
+    pub db: i64,
+
+    /// The username for the master
+    pub username: Option<String>,
+
+    /// The password for the master
+    pub password: Option<Secret<String>>,
+}
+
+/// Configuration of the pool of dedicated connections in `[redis.pool]`
+#[derive(Debug, Clone, JsonSchema, Deserialize)]
+pub struct PoolConfig {
+    /// Minimum number of idle connections of the pool
+    pub min_connections: Option<u32>,
+
+    /// Maximum number of connections of the pool
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u32,
+
+    /// Set the timeout for acquiring a connection, in milliseconds
+    pub acquire_timeout: Option<u64>,
+
+    /// Close the connections idle for longer than `idle_timeout` milliseconds
+    pub idle_timeout: Option<u64>,
+
+    /// Close the connections open for longer than `max_lifetime` milliseconds
+    pub max_lifetime: Option<u64>,
+}
+
+fn default_max_connections() -> u32 {
+    10
 }
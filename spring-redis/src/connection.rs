@@ -0,0 +1,267 @@
+use crate::{Redis, RedisCluster};
+use redis::aio::{
+    ConnectionLike, ConnectionManager, ConnectionManagerConfig, MultiplexedConnection,
+};
+use redis::sentinel::SentinelClient;
+use redis::{Client, Cmd, ErrorKind, Pipeline, RedisError, RedisFuture, RedisResult, Role, Value};
+use std::sync::{Arc, RwLock};
+use tokio::sync::Mutex;
+
+/// Connection to Redis whatever its deployment, used by the caches and the health indicator.
+///
+/// It is registered as a component next to the [`Redis`] or [`RedisCluster`] connection,
+/// for the code supporting both deployments.
+#[derive(Clone)]
+pub enum RedisConnection {
+    /// Connection to a standalone server
+    Standalone(Redis),
+    /// Connection to the master found by the sentinels
+    Sentinel(RedisSentinel),
+    /// Connection to a Redis Cluster
+    Cluster(RedisCluster),
+}
+
+impl RedisConnection {
+    /// Whether the keys are spread on the nodes of a cluster
+    pub fn is_cluster(&self) -> bool {
+        matches!(self, Self::Cluster(_))
+    }
+}
+
+impl From<Redis> for RedisConnection {
+    fn from(redis: Redis) -> Self {
+        Self::Standalone(redis)
+    }
+}
+
+impl From<RedisSentinel> for RedisConnection {
+    fn from(sentinel: RedisSentinel) -> Self {
+        Self::Sentinel(sentinel)
+    }
+}
+
+impl From<RedisCluster> for RedisConnection {
+    fn from(cluster: RedisCluster) -> Self {
+        Self::Cluster(cluster)
+    }
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            Self::Standalone(redis) => redis.req_packed_command(cmd),
+            Self::Sentinel(sentinel) => sentinel.req_packed_command(cmd),
+            Self::Cluster(cluster) => cluster.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            Self::Standalone(redis) => redis.req_packed_commands(cmd, offset, count),
+            Self::Sentinel(sentinel) => sentinel.req_packed_commands(cmd, offset, count),
+            Self::Cluster(cluster) => cluster.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            Self::Standalone(redis) => redis.get_db(),
+            Self::Sentinel(sentinel) => sentinel.get_db(),
+            Self::Cluster(cluster) => cluster.get_db(),
+        }
+    }
+}
+
+/// Opens the connections to the server configured by `uri`,
+/// or to the master found by the sentinels, looked up again for each connection
+/// so that the new connections follow the failovers.
+#[derive(Clone)]
+pub enum RedisConnector {
+    /// Server configured by `uri`, or node of a cluster
+    Server(Client),
+    /// Master monitored by the sentinels
+    Sentinel(Arc<Mutex<SentinelClient>>),
+}
+
+impl RedisConnector {
+    /// Client of the server, or of the current master
+    pub async fn client(&self) -> RedisResult<Client> {
+        match self {
+            Self::Server(client) => Ok(client.clone()),
+            Self::Sentinel(sentinel) => sentinel.lock().await.async_get_client().await,
+        }
+    }
+}
+
+impl bb8::ManageConnection for RedisConnector {
+    type Connection = MultiplexedConnection;
+    type Error = RedisError;
+
+    async fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        self.client()
+            .await?
+            .get_multiplexed_async_connection()
+            .await
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        match self {
+            Self::Server(_) => {
+                let _: String = redis::cmd("PING").query_async(conn).await?;
+            }
+            // the former master is demoted to a replica by a failover
+            Self::Sentinel(_) => {
+                let role: Role = redis::cmd("ROLE").query_async(conn).await?;
+                if !matches!(role, Role::Primary { .. }) {
+                    return Err((ErrorKind::ReadOnly, "the server is no longer the master").into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn has_broken(&self, _conn: &mut Self::Connection) -> bool {
+        false
+    }
+}
+
+/// Connection to the master found by Redis Sentinel, registered instead of [`Redis`]
+/// when `[redis.sentinel]` is configured.
+///
+/// It reconnects like [`Redis`], and when a command fails because the connection is lost
+/// or the server is no longer the master, the master is looked up again:
+/// the next commands are sent to the master elected by the failover.
+#[derive(Clone)]
+pub struct RedisSentinel(Arc<SentinelState>);
+
+struct SentinelState {
+    connector: RedisConnector,
+    config: ConnectionManagerConfig,
+    db: i64,
+    /// connection to the current master, with the number of failovers followed so far
+    master: RwLock<(u64, ConnectionManager)>,
+    failover: Mutex<()>,
+}
+
+impl RedisSentinel {
+    /// Connect to the master found by the sentinels of `connector`
+    pub(crate) async fn connect(
+        connector: RedisConnector,
+        config: ConnectionManagerConfig,
+    ) -> RedisResult<Self> {
+        let client = connector.client().await?;
+        let db = client.get_connection_info().redis.db;
+        let manager = client
+            .get_connection_manager_with_config(config.clone())
+            .await?;
+        Ok(Self(Arc::new(SentinelState {
+            connector,
+            config,
+            db,
+            master: RwLock::new((0, manager)),
+            failover: Mutex::new(()),
+        })))
+    }
+
+    fn master(&self) -> (u64, ConnectionManager) {
+        self.0
+            .master
+            .read()
+            .expect("redis master lock failed")
+            .clone()
+    }
+
+    /// Whether the error shows that the master may have changed
+    fn lost_master(e: &RedisError) -> bool {
+        e.kind() == ErrorKind::ReadOnly
+            || e.is_io_error()
+            || e.is_connection_dropped()
+            || e.is_connection_refusal()
+            || e.is_timeout()
+    }
+
+    /// Connect to the master found by the sentinels,
+    /// unless another command already did since the connection `generation` failed
+    async fn follow_failover(&self, generation: u64) {
+        let _failover = self.0.failover.lock().await;
+        if self.master().0 != generation {
+            return;
+        }
+        let manager = match self.0.connector.client().await {
+            Ok(client) => {
+                let addr = client.get_connection_info().addr.clone();
+                client
+                    .get_connection_manager_with_config(self.0.config.clone())
+                    .await
+                    .map(|manager| (addr, manager))
+            }
+            Err(e) => Err(e),
+        };
+        match manager {
+            Ok((addr, manager)) => {
+                tracing::info!("redis sentinel master connected: {addr}");
+                *self.0.master.write().expect("redis master lock failed") =
+                    (generation + 1, manager);
+            }
+            Err(e) => tracing::warn!("redis sentinel master lookup failed: {e}"),
+        }
+    }
+}
+
+impl ConnectionLike for RedisSentinel {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        let (generation, mut master) = self.master();
+        Box::pin(async move {
+            let result = master.req_packed_command(cmd).await;
+            if matches!(&result, Err(e) if Self::lost_master(e)) {
+                self.follow_failover(generation).await;
+            }
+            result
+        })
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        let (generation, mut master) = self.master();
+        Box::pin(async move {
+            let result = master.req_packed_commands(cmd, offset, count).await;
+            if matches!(&result, Err(e) if Self::lost_master(e)) {
+                self.follow_failover(generation).await;
+            }
+            result
+        })
+    }
+
+    fn get_db(&self) -> i64 {
+        self.0.db
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RedisSentinel;
+    use redis::{ErrorKind, RedisError};
+    use std::io;
+
+    #[test]
+    fn test_lost_master() {
+        let demoted = RedisError::from((ErrorKind::ReadOnly, "READONLY"));
+        assert!(RedisSentinel::lost_master(&demoted));
+        let dropped = RedisError::from(io::Error::from(io::ErrorKind::ConnectionReset));
+        assert!(RedisSentinel::lost_master(&dropped));
+        let refused = RedisError::from(io::Error::from(io::ErrorKind::ConnectionRefused));
+        assert!(RedisSentinel::lost_master(&refused));
+
+        let wrong_type = RedisError::from((ErrorKind::TypeError, "WRONGTYPE"));
+        assert!(!RedisSentinel::lost_master(&wrong_type));
+    }
+}
@@ -2,22 +2,45 @@
 #![doc(html_favicon_url = "https://spring-rs.github.io/favicon.ico")]
 #![doc(html_logo_url = "https://spring-rs.github.io/logo.svg")]
 
+pub mod cache;
 pub mod config;
+pub mod connection;
+pub mod pubsub;
 
 pub use redis;
-pub use spring_macros::cache;
+pub use spring_macros::{cache, cache_evict, cache_put, redis_listener};
 
 use anyhow::Context;
-use config::RedisConfig;
-use redis::{aio::ConnectionManagerConfig, Client};
+use cache::RedisCacheManager;
+use config::{ClusterConfig, PoolConfig, RedisConfig, SentinelConfig};
+use connection::{RedisConnection, RedisConnector, RedisSentinel};
+use pubsub::{Backoff, RedisPublisher, Subscriber};
+use redis::cluster::ClusterClientBuilder;
+use redis::sentinel::{SentinelClient, SentinelNodeConnectionInfo, SentinelServerType};
+use redis::{aio::ConnectionManagerConfig, Client, RedisConnectionInfo};
+use spring::app::{App, AppBuilder};
 use spring::async_trait;
+use spring::cache::{CacheConfig, CacheManager};
 use spring::config::ConfigRegistry;
+use spring::health::{Health, HealthConfigurator, HealthIndicator};
 use spring::plugin::MutableComponentRegistry;
-use spring::{app::AppBuilder, error::Result, plugin::Plugin};
+use spring::{error::Result, plugin::Plugin};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Mutex;
 
 pub type Redis = redis::aio::ConnectionManager;
 
+/// Connection to a Redis Cluster, registered instead of [`Redis`] when `[redis.cluster]` is configured
+pub type RedisCluster = redis::cluster_async::ClusterConnection;
+
+/// Pool of dedicated connections, registered when `[redis.pool]` is configured.
+///
+/// Unlike the multiplexed [`Redis`] connection, a pooled connection can run blocking commands
+/// and transactions without affecting the other users.
+/// With `[redis.sentinel]`, each new connection is opened to the current master.
+pub type RedisPool = bb8::Pool<RedisConnector>;
+
 pub struct RedisPlugin;
 
 #[async_trait]
@@ -27,17 +50,133 @@ impl Plugin for RedisPlugin {
             .get_config::<RedisConfig>()
             .expect("redis plugin config load failed");
 
-        let connect: Redis = Self::connect(config).await.expect("redis connect failed");
-        app.add_component(connect);
+        let cache_config = app
+            .get_config::<CacheConfig>()
+            .expect("cache config load failed");
+
+        let (connection, connectors): (RedisConnection, _) = match &config.cluster {
+            Some(cluster_config) => {
+                let cluster: RedisCluster = Self::connect_cluster(&config, cluster_config)
+                    .await
+                    .expect("redis cluster connect failed");
+                app.add_component(cluster.clone());
+                // the messages published in a cluster are received by the subscribers of every node
+                let connectors = cluster_config
+                    .nodes
+                    .iter()
+                    .map(|node| Client::open(node.as_str()).map(RedisConnector::Server))
+                    .collect::<redis::RedisResult<Vec<_>>>()
+                    .expect("redis cluster node uri is invalid");
+                (cluster.into(), connectors)
+            }
+            None => {
+                let connector = Self::connector(&config).expect("redis client create failed");
+                if let Some(pool) = &config.pool {
+                    let pool: RedisPool = Self::create_pool(connector.clone(), pool)
+                        .await
+                        .expect("redis pool create failed");
+                    app.add_component(pool);
+                }
+                let connection = match &connector {
+                    RedisConnector::Server(client) => {
+                        let connect: Redis = Self::connect(client.clone(), &config)
+                            .await
+                            .expect("redis connect failed");
+                        app.add_component(connect.clone());
+                        connect.into()
+                    }
+                    RedisConnector::Sentinel(_) => {
+                        let sentinel = Self::connect_sentinel(connector.clone(), &config)
+                            .await
+                            .expect("redis sentinel connect failed");
+                        app.add_component(sentinel.clone());
+                        sentinel.into()
+                    }
+                };
+                (connection, vec![connector])
+            }
+        };
+
+        let backoff = Backoff::new(&config);
+        for listener in pubsub::listeners() {
+            let subscriber = Subscriber {
+                listener,
+                connectors: connectors.clone(),
+                backoff,
+            };
+            app.add_scheduler(move |app: Arc<App>| {
+                let startup = app.lifecycle().startup_task("redis listener");
+                Box::new(subscriber.schedule(app, startup))
+            });
+        }
+
+        let cache_manager: Arc<dyn CacheManager> =
+            Arc::new(RedisCacheManager::new(connection.clone(), cache_config));
+        app.add_health_indicator(RedisHealthIndicator(connection.clone()))
+            .add_component(cache_manager)
+            .add_component(RedisPublisher::new(connection.clone()))
+            .add_component(connection);
+    }
+}
+
+/// Health indicator of the redis connection, sends `PING`
+pub struct RedisHealthIndicator(RedisConnection);
+
+#[async_trait]
+impl HealthIndicator for RedisHealthIndicator {
+    fn name(&self) -> &str {
+        "redis"
+    }
+
+    async fn check(&self) -> Result<Health> {
+        let mut conn = self.0.clone();
+        let pong: String = redis::cmd("PING")
+            .query_async(&mut conn)
+            .await
+            .context("redis health check failed")?;
+        Ok(Health::up().with_detail("ping", pong))
     }
 }
 
 impl RedisPlugin {
-    async fn connect(config: RedisConfig) -> Result<Redis> {
-        let url = config.uri;
-        let client = Client::open(url.clone())
-            .with_context(|| format!("redis connect failed:{}", url.clone()))?;
+    /// Connector of the server configured by `uri`, or of the master found by the sentinels
+    fn connector(config: &RedisConfig) -> Result<RedisConnector> {
+        match &config.sentinel {
+            Some(_) if !config.uri.is_empty() => {
+                Err(anyhow::anyhow!("redis uri and sentinel are mutually exclusive").into())
+            }
+            Some(sentinel) => Self::sentinel(sentinel),
+            None => {
+                let url = &config.uri;
+                let client = Client::open(url.clone())
+                    .with_context(|| format!("redis connect failed:{url}"))?;
+                Ok(RedisConnector::Server(client))
+            }
+        }
+    }
+
+    fn sentinel(config: &SentinelConfig) -> Result<RedisConnector> {
+        let master = &config.master;
+        let node_connection_info = SentinelNodeConnectionInfo {
+            tls_mode: None,
+            redis_connection_info: Some(RedisConnectionInfo {
+                db: config.db,
+                username: config.username.clone(),
+                password: config.password.as_ref().map(|p| p.expose().clone()),
+                ..Default::default()
+            }),
+        };
+        let sentinel = SentinelClient::build(
+            config.nodes.clone(),
+            master.clone(),
+            Some(node_connection_info),
+            SentinelServerType::Master,
+        )
+        .with_context(|| format!("redis sentinel create failed:{master}"))?;
+        Ok(RedisConnector::Sentinel(Arc::new(Mutex::new(sentinel))))
+    }
 
+    fn connection_manager_config(config: &RedisConfig) -> ConnectionManagerConfig {
         let mut conn_config = ConnectionManagerConfig::new();
 
         if let Some(exponent_base) = config.exponent_base {
@@ -59,10 +198,92 @@ impl RedisPlugin {
             conn_config =
                 conn_config.set_connection_timeout(Duration::from_millis(connection_timeout));
         }
+        conn_config
+    }
 
+    async fn connect(client: Client, config: &RedisConfig) -> Result<Redis> {
+        let addr = client.get_connection_info().addr.clone();
         Ok(client
-            .get_connection_manager_with_config(conn_config)
+            .get_connection_manager_with_config(Self::connection_manager_config(config))
+            .await
+            .with_context(|| format!("redis connect failed:{addr}"))?)
+    }
+
+    async fn connect_sentinel(
+        connector: RedisConnector,
+        config: &RedisConfig,
+    ) -> Result<RedisSentinel> {
+        let master = config.sentinel.as_ref().map_or("", |s| s.master.as_str());
+        Ok(
+            RedisSentinel::connect(connector, Self::connection_manager_config(config))
+                .await
+                .with_context(|| format!("redis sentinel master lookup failed:{master}"))?,
+        )
+    }
+
+    async fn connect_cluster(
+        config: &RedisConfig,
+        cluster: &ClusterConfig,
+    ) -> Result<RedisCluster> {
+        if !config.uri.is_empty() || config.sentinel.is_some() {
+            return Err(anyhow::anyhow!(
+                "redis cluster is mutually exclusive with uri and sentinel"
+            )
+            .into());
+        }
+        if config.pool.is_some() {
+            return Err(anyhow::anyhow!("redis pool is not supported with a cluster").into());
+        }
+
+        let mut builder = ClusterClientBuilder::new(cluster.nodes.clone());
+        if cluster.read_from_replicas {
+            builder = builder.read_from_replicas();
+        }
+        if config.exponent_base.is_some() || config.factor.is_some() {
+            builder = builder.retry_wait_formula(
+                config.factor.unwrap_or(100),
+                config.exponent_base.unwrap_or(2),
+            );
+        }
+        if let Some(number_of_retries) = config.number_of_retries {
+            builder = builder.retries(number_of_retries as u32);
+        }
+        if let Some(max_delay) = config.max_delay {
+            builder = builder.max_retry_wait(max_delay);
+        }
+        if let Some(response_timeout) = config.response_timeout {
+            builder = builder.response_timeout(Duration::from_millis(response_timeout));
+        }
+        if let Some(connection_timeout) = config.connection_timeout {
+            builder = builder.connection_timeout(Duration::from_millis(connection_timeout));
+        }
+
+        let nodes = cluster.nodes.join(",");
+        let client = builder
+            .build()
+            .with_context(|| format!("redis cluster create failed:{nodes}"))?;
+        Ok(client
+            .get_async_connection()
+            .await
+            .with_context(|| format!("redis cluster connect failed:{nodes}"))?)
+    }
+
+    async fn create_pool(connector: RedisConnector, config: &PoolConfig) -> Result<RedisPool> {
+        let mut builder = bb8::Pool::builder()
+            .max_size(config.max_connections)
+            .min_idle(config.min_connections);
+        if let Some(acquire_timeout) = config.acquire_timeout {
+            builder = builder.connection_timeout(Duration::from_millis(acquire_timeout));
+        }
+        if let Some(idle_timeout) = config.idle_timeout {
+            builder = builder.idle_timeout(Some(Duration::from_millis(idle_timeout)));
+        }
+        if let Some(max_lifetime) = config.max_lifetime {
+            builder = builder.max_lifetime(Some(Duration::from_millis(max_lifetime)));
+        }
+        Ok(builder
+            .build(connector)
             .await
-            .with_context(|| format!("redis connect failed:{}", url.clone()))?)
+            .context("redis pool create failed")?)
     }
 }
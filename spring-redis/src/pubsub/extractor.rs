@@ -0,0 +1,105 @@
+pub use redis::Msg;
+
+use redis::FromRedisValue;
+use spring::app::App;
+use spring::config::ConfigRegistry;
+use spring::config::Configurable;
+use spring::extractor::Component;
+use spring::extractor::Config;
+use spring::plugin::ComponentRegistry;
+
+pub trait FromMsg {
+    fn from_msg(msg: &Msg, app: &App) -> Self;
+}
+
+impl FromMsg for Msg {
+    fn from_msg(msg: &Msg, _app: &App) -> Self {
+        msg.clone()
+    }
+}
+
+/// Name of the channel the message was published to
+pub struct Channel(pub String);
+
+impl FromMsg for Channel {
+    fn from_msg(msg: &Msg, _app: &App) -> Self {
+        Channel(msg.get_channel_name().to_string())
+    }
+}
+
+/// Pattern matching the channel, `None` if the channel itself is subscribed
+pub struct Pattern(pub Option<String>);
+
+impl FromMsg for Pattern {
+    fn from_msg(msg: &Msg, _app: &App) -> Self {
+        let pattern = msg.from_pattern().then(|| {
+            msg.get_pattern()
+                .expect("redis message pattern is not a string")
+        });
+        Pattern(pattern)
+    }
+}
+
+/// Payload of the message, `Payload<String>`, `Payload<i64>`... or the raw bytes by default
+pub struct Payload<T = Vec<u8>>(pub T);
+
+impl<T> FromMsg for Payload<T>
+where
+    T: FromRedisValue,
+{
+    fn from_msg(msg: &Msg, _app: &App) -> Self {
+        match msg.get_payload() {
+            Ok(payload) => Payload(payload),
+            Err(e) => panic!(
+                "redis message payload parse as {} failed: {}",
+                std::any::type_name::<T>(),
+                e
+            ),
+        }
+    }
+}
+
+/// Payload of the message parsed as json
+pub struct Json<T>(pub T);
+
+impl<T> FromMsg for Json<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    fn from_msg(msg: &Msg, _app: &App) -> Self {
+        let value = serde_json::from_slice(msg.get_payload_bytes())
+            .expect("redis message parse as json failed");
+        Json(value)
+    }
+}
+
+impl<T> FromMsg for Component<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn from_msg(_msg: &Msg, app: &App) -> Self {
+        match app.get_component_ref::<T>() {
+            Some(component) => Component(T::clone(&component)),
+            None => panic!(
+                "There is no component of `{}` type",
+                std::any::type_name::<T>()
+            ),
+        }
+    }
+}
+
+impl<T> FromMsg for Config<T>
+where
+    T: serde::de::DeserializeOwned + Configurable,
+{
+    fn from_msg(_msg: &Msg, app: &App) -> Self {
+        match app.get_config::<T>() {
+            Ok(config) => Config(config),
+            Err(e) => panic!(
+                "get config failed for typeof {}: {}",
+                std::any::type_name::<T>(),
+                e
+            ),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::pubsub::extractor::FromMsg;
+use futures_util::future::BoxFuture;
+use redis::Msg;
+use spring::app::App;
+use std::{future::Future, pin::Pin, sync::Arc};
+
+pub trait Handler<T>: Clone + Send + Sync + Sized + 'static {
+    /// The type of future calling this handler returns.
+    type Future: Future<Output = ()> + Send + 'static;
+
+    /// Call the handler with the given message.
+    fn call(self, msg: Msg, app: Arc<App>) -> Self::Future;
+}
+
+/// no args handler impl
+impl<F, Fut> Handler<()> for F
+where
+    F: FnOnce() -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn call(self, _msg: Msg, _app: Arc<App>) -> Self::Future {
+        Box::pin(self())
+    }
+}
+
+/// 1~15 args handler impl
+#[rustfmt::skip]
+macro_rules! all_the_tuples {
+    ($name:ident) => {
+        $name!([T1]);
+        $name!([T1, T2]);
+        $name!([T1, T2, T3]);
+        $name!([T1, T2, T3, T4]);
+        $name!([T1, T2, T3, T4, T5]);
+        $name!([T1, T2, T3, T4, T5, T6]);
+        $name!([T1, T2, T3, T4, T5, T6, T7]);
+        $name!([T1, T2, T3, T4, T5, T6, T7, T8]);
+        $name!([T1, T2, T3, T4, T5, T6, T7, T8, T9]);
+        $name!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10]);
+        $name!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11]);
+        $name!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12]);
+        $name!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13]);
+        $name!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14]);
+        $name!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15]);
+    };
+}
+
+macro_rules! impl_handler {
+    (
+        [$($ty:ident),*]
+    ) => {
+        #[allow(non_snake_case, unused_mut)]
+        impl<F, Fut, $($ty,)*> Handler<($($ty,)*)> for F
+        where
+            F: FnOnce($($ty,)*) -> Fut + Clone + Send + Sync + 'static,
+            Fut: Future<Output = ()> + Send + 'static,
+            $( $ty: FromMsg + Send, )*
+        {
+            type Future = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+            fn call(self, msg: Msg, app: Arc<App>) -> Self::Future {
+                $(
+                    let $ty = $ty::from_msg(&msg, &app);
+                )*
+                Box::pin(self($($ty,)*))
+            }
+        }
+    };
+}
+
+all_the_tuples!(impl_handler);
+
+/// Type-erased [`Handler`]
+pub(crate) type BoxedHandler = Arc<dyn Fn(Msg, Arc<App>) -> BoxFuture<'static, ()> + Send + Sync>;
+
+pub(crate) fn boxed<H, T>(handler: H) -> BoxedHandler
+where
+    H: Handler<T>,
+    T: 'static,
+{
+    Arc::new(move |msg, app| Box::pin(handler.clone().call(msg, app)))
+}
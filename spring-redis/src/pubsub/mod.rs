@@ -0,0 +1,493 @@
+//! Listeners of the redis channels, declared with [`redis_listener`](crate::redis_listener)
+//!
+//! ```rust,ignore
+//! #[redis_listener("user.created", pattern = "order.*")]
+//! async fn on_message(Channel(channel): Channel, Json(event): Json<Event>) {
+//!     tracing::info!("{channel}: {event:?}");
+//! }
+//! ```
+
+pub mod extractor;
+pub mod handler;
+pub mod publisher;
+
+pub use inventory::submit;
+pub use publisher::RedisPublisher;
+
+use crate::config::RedisConfig;
+use crate::connection::RedisConnector;
+use anyhow::Context;
+use futures_util::StreamExt;
+use handler::{BoxedHandler, Handler};
+use redis::{aio::PubSub, Msg};
+use spring::app::App;
+use spring::error::Result;
+use spring::lifecycle::StartupGuard;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Channels and patterns subscribed by a handler
+#[derive(Clone)]
+pub struct Listener {
+    name: &'static str,
+    channels: &'static [&'static str],
+    patterns: &'static [&'static str],
+    handler: BoxedHandler,
+}
+
+impl Listener {
+    /// Listener named `name` calling `handler` with the messages of `channels` and of the channels matching `patterns`
+    pub fn new<H, A>(
+        name: &'static str,
+        channels: &'static [&'static str],
+        patterns: &'static [&'static str],
+        handler: H,
+    ) -> Self
+    where
+        H: Handler<A>,
+        A: 'static,
+    {
+        Self {
+            name,
+            channels,
+            patterns,
+            handler: handler::boxed(handler),
+        }
+    }
+
+    /// Name of the listener, the path of its function for [`redis_listener`](crate::redis_listener)
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Channels subscribed with `SUBSCRIBE`
+    pub fn channels(&self) -> &'static [&'static str] {
+        self.channels
+    }
+
+    /// Patterns subscribed with `PSUBSCRIBE`
+    pub fn patterns(&self) -> &'static [&'static str] {
+        self.patterns
+    }
+
+    async fn subscribe(&self, connector: &RedisConnector) -> Result<PubSub> {
+        let client = connector
+            .client()
+            .await
+            .context("redis sentinel master lookup failed")?;
+        let mut pubsub = client
+            .get_async_pubsub()
+            .await
+            .context("redis pubsub connect failed")?;
+        for channel in self.channels {
+            pubsub
+                .subscribe(*channel)
+                .await
+                .with_context(|| format!("redis subscribe {channel} failed"))?;
+        }
+        for pattern in self.patterns {
+            pubsub
+                .psubscribe(*pattern)
+                .await
+                .with_context(|| format!("redis psubscribe {pattern} failed"))?;
+        }
+        Ok(pubsub)
+    }
+
+    async fn handle(&self, msg: Msg, app: Arc<App>) {
+        let handler = self.handler.clone();
+        // a panic of an extractor or of the handler doesn't stop the subscription
+        if let Err(e) = tokio::spawn(async move { handler(msg, app).await }).await {
+            tracing::error!("redis listener {} failed: {e}", self.name);
+        }
+    }
+}
+
+/// Registers the [`Listener`] generated by [`redis_listener`](crate::redis_listener)
+pub trait ListenerRegistrar: Send + Sync + 'static {
+    fn listener(&self) -> Listener;
+}
+
+inventory::collect!(&'static dyn ListenerRegistrar);
+
+#[macro_export]
+macro_rules! submit_listener {
+    ($ty:ident) => {
+        ::spring_redis::pubsub::submit! {
+            &$ty as &dyn ::spring_redis::pubsub::ListenerRegistrar
+        }
+    };
+}
+
+/// All the listeners declared with [`redis_listener`](crate::redis_listener)
+pub fn listeners() -> Vec<Listener> {
+    inventory::iter::<&dyn ListenerRegistrar>
+        .into_iter()
+        .map(|registrar| registrar.listener())
+        .collect()
+}
+
+/// Delay between the reconnections of the subscribers, configured like the connection retries.
+///
+/// A subscriber that still can't subscribe after `retries` retries lets the application start without it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Backoff {
+    exponent_base: u64,
+    factor: u64,
+    max_delay: u64,
+    retries: u32,
+}
+
+impl Backoff {
+    pub(crate) fn new(config: &RedisConfig) -> Self {
+        Self {
+            exponent_base: config.exponent_base.unwrap_or(2),
+            factor: config.factor.unwrap_or(100),
+            max_delay: config.max_delay.unwrap_or(60_000),
+            retries: config.number_of_retries.unwrap_or(6) as u32,
+        }
+    }
+
+    fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .exponent_base
+            .saturating_pow(attempt)
+            .saturating_mul(self.factor);
+        Duration::from_millis(delay.min(self.max_delay))
+    }
+}
+
+/// Keeps a [`Listener`] subscribed, on each of `connectors` in turn when the connection is lost.
+/// With Redis Sentinel the master is looked up again before each subscription.
+pub(crate) struct Subscriber {
+    pub(crate) listener: Listener,
+    pub(crate) connectors: Vec<RedisConnector>,
+    pub(crate) backoff: Backoff,
+}
+
+impl Subscriber {
+    pub(crate) async fn schedule(self, app: Arc<App>, startup: StartupGuard) -> Result<String> {
+        let Self {
+            listener,
+            connectors,
+            backoff,
+        } = self;
+        let lifecycle = app.lifecycle().clone();
+        let mut startup = Some(startup);
+        let mut attempt = 0u32;
+        for connector in connectors.iter().cycle() {
+            match listener.subscribe(connector).await {
+                Ok(pubsub) => {
+                    tracing::info!(
+                        "redis listener {} subscribed to {:?} {:?}",
+                        listener.name,
+                        listener.channels,
+                        listener.patterns
+                    );
+                    drop(startup.take());
+                    attempt = 0;
+                    let mut messages = pubsub.into_on_message();
+                    loop {
+                        let msg = tokio::select! {
+                            msg = messages.next() => msg,
+                            _ = lifecycle.shutdown_signal("redis listener") => {
+                                return Ok("redis listener finished".to_string());
+                            }
+                        };
+                        match msg {
+                            Some(msg) => listener.handle(msg, app.clone()).await,
+                            None => break,
+                        }
+                    }
+                    tracing::warn!("redis listener {} disconnected", listener.name);
+                }
+                Err(e) => tracing::error!("redis listener {} failed: {e:?}", listener.name),
+            }
+            let delay = backoff.delay(attempt);
+            attempt = attempt.saturating_add(1);
+            if attempt > backoff.retries {
+                if let Some(startup) = startup.take() {
+                    tracing::error!(
+                        "redis listener {} failed to subscribe {attempt} times, the application starts without it",
+                        listener.name
+                    );
+                    drop(startup);
+                }
+            }
+            tracing::info!("redis listener {} resubscribes in {delay:?}", listener.name);
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = lifecycle.shutdown_signal("redis listener") => {
+                    return Ok("redis listener finished".to_string());
+                }
+            }
+        }
+        Err(anyhow::anyhow!("no redis node to subscribe to").into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extractor::{Channel, Json, Pattern, Payload};
+    use super::{Backoff, Listener, Subscriber};
+    use crate::connection::RedisConnector;
+    use redis::{Client, Msg, Value};
+    use serde::Deserialize;
+    use spring::app::{App, AppBuilder};
+    use spring::extractor::Component;
+    use spring::plugin::MutableComponentRegistry;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{TcpListener, TcpStream};
+
+    #[test]
+    fn test_backoff() {
+        let backoff = Backoff {
+            exponent_base: 2,
+            factor: 100,
+            max_delay: 1000,
+            retries: 6,
+        };
+        assert_eq!(backoff.delay(0), Duration::from_millis(100));
+        assert_eq!(backoff.delay(2), Duration::from_millis(400));
+        assert_eq!(backoff.delay(4), Duration::from_millis(1000));
+        assert_eq!(backoff.delay(100), Duration::from_millis(1000));
+    }
+
+    /// Messages received by the handlers
+    #[derive(Clone, Default)]
+    struct Received(Arc<Mutex<Vec<String>>>);
+
+    impl Received {
+        fn push(&self, message: String) {
+            self.0.lock().unwrap().push(message);
+        }
+
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut *self.0.lock().unwrap())
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Order {
+        id: u64,
+    }
+
+    async fn on_user(
+        Channel(channel): Channel,
+        Pattern(pattern): Pattern,
+        Payload(name): Payload<String>,
+        Component(received): Component<Received>,
+    ) {
+        received.push(format!("{channel} {pattern:?} {name}"));
+    }
+
+    async fn on_order(Json(order): Json<Order>, Component(received): Component<Received>) {
+        received.push(format!("order {}", order.id));
+    }
+
+    fn bulk(value: &str) -> Value {
+        Value::BulkString(value.as_bytes().to_vec())
+    }
+
+    fn message(channel: &str, payload: &str) -> Msg {
+        let value = Value::Array(vec![bulk("message"), bulk(channel), bulk(payload)]);
+        Msg::from_owned_value(value).unwrap()
+    }
+
+    fn pattern_message(pattern: &str, channel: &str, payload: &str) -> Msg {
+        let value = Value::Array(vec![
+            bulk("pmessage"),
+            bulk(pattern),
+            bulk(channel),
+            bulk(payload),
+        ]);
+        Msg::from_owned_value(value).unwrap()
+    }
+
+    async fn app(received: &Received) -> Arc<App> {
+        AppBuilder::from_config_str("")
+            .add_component(received.clone())
+            .isolate_global(true)
+            .build()
+            .await
+            .expect("app build failed")
+    }
+
+    #[tokio::test]
+    async fn test_handler_extractors() {
+        let received = Received::default();
+        let app = app(&received).await;
+
+        let users = Listener::new("on_user", &["user.created"], &["user.*"], on_user);
+        users
+            .handle(message("user.created", "alice"), app.clone())
+            .await;
+        users
+            .handle(
+                pattern_message("user.*", "user.deleted", "bob"),
+                app.clone(),
+            )
+            .await;
+        assert_eq!(
+            received.take(),
+            [
+                "user.created None alice",
+                "user.deleted Some(\"user.*\") bob"
+            ]
+        );
+
+        let orders = Listener::new("on_order", &["order.paid"], &[], on_order);
+        orders
+            .handle(message("order.paid", r#"{"id":42}"#), app.clone())
+            .await;
+        assert_eq!(received.take(), ["order 42"]);
+
+        // a message the extractors can't parse is dropped, the next ones are handled
+        orders
+            .handle(message("order.paid", "not json"), app.clone())
+            .await;
+        orders
+            .handle(message("order.paid", r#"{"id":43}"#), app.clone())
+            .await;
+        assert_eq!(received.take(), ["order 43"]);
+    }
+
+    /// Read a command sent by the client
+    async fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim().strip_prefix('*')?.parse().ok()?;
+        let mut args = Vec::with_capacity(len);
+        for _ in 0..len {
+            line.clear();
+            reader.read_line(&mut line).await.ok()?;
+            let arg_len: usize = line.trim().strip_prefix('$')?.parse().ok()?;
+            let mut arg = vec![0; arg_len + 2];
+            reader.read_exact(&mut arg).await.ok()?;
+            arg.truncate(arg_len);
+            args.push(String::from_utf8(arg).ok()?);
+        }
+        Some(args)
+    }
+
+    fn resp(parts: &[&str]) -> Vec<u8> {
+        let mut out = format!("*{}\r\n", parts.len());
+        for part in parts {
+            out.push_str(&format!("${}\r\n{part}\r\n", part.len()));
+        }
+        out.into_bytes()
+    }
+
+    /// Redis server accepting a subscription, publishing `payload` and closing the connection
+    /// if `disconnect`, otherwise keeping it open until the client closes it
+    async fn serve_subscription(socket: TcpStream, payload: &str, disconnect: bool) {
+        let mut reader = BufReader::new(socket);
+        while let Some(command) = read_command(&mut reader).await {
+            let socket = reader.get_mut();
+            if command[0].eq_ignore_ascii_case("SUBSCRIBE") {
+                let channel = command[1].as_str();
+                let reply = format!(
+                    "*3\r\n$9\r\nsubscribe\r\n${}\r\n{channel}\r\n:1\r\n",
+                    channel.len()
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+                socket
+                    .write_all(&resp(&["message", channel, payload]))
+                    .await
+                    .unwrap();
+                if disconnect {
+                    return;
+                }
+            } else {
+                socket.write_all(b"+OK\r\n").await.unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resubscribe_after_lost_connection() {
+        let received = Received::default();
+        let app = app(&received).await;
+
+        let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = server.accept().await.unwrap();
+            serve_subscription(socket, "alice", true).await;
+            let (socket, _) = server.accept().await.unwrap();
+            serve_subscription(socket, "bob", false).await;
+        });
+
+        let client = Client::open(format!("redis://{addr}")).unwrap();
+        let subscriber = Subscriber {
+            listener: Listener::new("on_user", &["user.created"], &[], on_user),
+            connectors: vec![RedisConnector::Server(client)],
+            backoff: Backoff {
+                exponent_base: 2,
+                factor: 10,
+                max_delay: 100,
+                retries: 6,
+            },
+        };
+        let startup = app.lifecycle().startup_task("redis listener");
+        let schedule = tokio::spawn(subscriber.schedule(app.clone(), startup));
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while received.0.lock().unwrap().len() < 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the listener didn't resubscribe");
+        assert_eq!(
+            received.take(),
+            ["user.created None alice", "user.created None bob"]
+        );
+
+        app.lifecycle().shutdown();
+        let result = schedule.await.unwrap();
+        assert_eq!(result.unwrap(), "redis listener finished");
+    }
+
+    #[tokio::test]
+    async fn test_startup_without_redis() {
+        // nothing listens on the port once the listener is dropped
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let client = Client::open(format!("redis://{addr}")).unwrap();
+        let subscriber = Subscriber {
+            listener: Listener::new("on_user", &["user.created"], &[], on_user),
+            connectors: vec![RedisConnector::Server(client)],
+            backoff: Backoff {
+                exponent_base: 2,
+                factor: 10,
+                max_delay: 100,
+                retries: 2,
+            },
+        };
+
+        let mut builder = AppBuilder::from_config_str("");
+        builder
+            .add_component(Received::default())
+            .isolate_global(true)
+            .add_scheduler(move |app: Arc<App>| {
+                let startup = app.lifecycle().startup_task("redis listener");
+                Box::new(subscriber.schedule(app, startup))
+            })
+            .add_startup_hook(|app: Arc<App>| {
+                Box::new(async move {
+                    app.lifecycle().shutdown();
+                    Ok("started".to_string())
+                })
+            });
+        tokio::time::timeout(Duration::from_secs(10), builder.try_run())
+            .await
+            .expect("the listener blocked the startup")
+            .expect("app run failed");
+    }
+}
@@ -0,0 +1,35 @@
+use crate::connection::RedisConnection;
+use anyhow::Context;
+use redis::{AsyncCommands, ToRedisArgs};
+use serde::Serialize;
+use spring::error::Result;
+
+/// Publishes messages to the channels, registered as a component by the [`RedisPlugin`](crate::RedisPlugin)
+#[derive(Clone)]
+pub struct RedisPublisher(RedisConnection);
+
+impl RedisPublisher {
+    /// Publisher sending the messages through `redis`
+    pub fn new(redis: impl Into<RedisConnection>) -> Self {
+        Self(redis.into())
+    }
+
+    /// Publish `payload` to `channel`, returns the number of subscribers that received it
+    pub async fn publish<P>(&self, channel: &str, payload: P) -> Result<usize>
+    where
+        P: ToRedisArgs + Send + Sync,
+    {
+        Ok(self
+            .0
+            .clone()
+            .publish(channel, payload)
+            .await
+            .with_context(|| format!("redis publish to {channel} failed"))?)
+    }
+
+    /// Publish `payload` serialized in json to `channel`, read by the [`Json`](crate::pubsub::extractor::Json) extractor
+    pub async fn publish_json<T: Serialize>(&self, channel: &str, payload: &T) -> Result<usize> {
+        let json = serde_json::to_vec(payload).context("json serialize failed")?;
+        self.publish(channel, json).await
+    }
+}
@@ -10,6 +10,9 @@ fn test_redis_config_creation() {
         max_delay: Some(5000),
         response_timeout: Some(3000),
         connection_timeout: Some(2000),
+        cluster: None,
+        sentinel: None,
+        pool: None,
     };
     
     assert_eq!(config.uri, "redis://localhost:6379");
@@ -28,6 +31,9 @@ fn test_redis_config_default_values() {
         max_delay: None,
         response_timeout: None,
         connection_timeout: None,
+        cluster: None,
+        sentinel: None,
+        pool: None,
     };
     
     assert_eq!(config.uri, "redis://localhost:6379");
@@ -75,6 +81,9 @@ fn test_redis_config_with_auth() {
         max_delay: None,
         response_timeout: None,
         connection_timeout: None,
+        cluster: None,
+        sentinel: None,
+        pool: None,
     };
     
     assert!(config.uri.contains("password"));
@@ -90,6 +99,9 @@ fn test_redis_config_with_db_number() {
         max_delay: None,
         response_timeout: None,
         connection_timeout: None,
+        cluster: None,
+        sentinel: None,
+        pool: None,
     };
     
     assert!(config.uri.ends_with("/2"));
@@ -105,6 +117,9 @@ fn test_redis_config_clone() {
         max_delay: Some(5000),
         response_timeout: Some(3000),
         connection_timeout: Some(2000),
+        cluster: None,
+        sentinel: None,
+        pool: None,
     };
     
     let cloned = config.clone();
@@ -122,6 +137,9 @@ fn test_redis_config_timeouts() {
         max_delay: Some(5000),
         response_timeout: Some(3000),
         connection_timeout: Some(2000),
+        cluster: None,
+        sentinel: None,
+        pool: None,
     };
     
     assert!(config.response_timeout.unwrap() > 0);
@@ -139,6 +157,9 @@ fn test_redis_config_retry_settings() {
         max_delay: None,
         response_timeout: None,
         connection_timeout: None,
+        cluster: None,
+        sentinel: None,
+        pool: None,
     };
     
     assert_eq!(config.exponent_base, Some(2));
@@ -146,3 +167,41 @@ fn test_redis_config_retry_settings() {
     assert_eq!(config.number_of_retries, Some(5));
 }
 
+
+#[test]
+fn test_redis_config_cluster_toml() {
+    let toml_str = r#"
+        number_of_retries = 3
+        [cluster]
+        nodes = ["redis://10.0.0.1:6379", "redis://10.0.0.2:6379"]
+        read_from_replicas = true
+    "#;
+
+    let config: RedisConfig = toml::from_str(toml_str).unwrap();
+    assert!(config.uri.is_empty());
+    let cluster = config.cluster.unwrap();
+    assert_eq!(cluster.nodes.len(), 2);
+    assert!(cluster.read_from_replicas);
+    assert!(config.sentinel.is_none());
+}
+
+#[test]
+fn test_redis_config_sentinel_and_pool_toml() {
+    let toml_str = r#"
+        [sentinel]
+        master = "mymaster"
+        nodes = ["redis://10.0.0.1:26379"]
+        password = "secret"
+        [pool]
+        min_connections = 2
+    "#;
+
+    let config: RedisConfig = toml::from_str(toml_str).unwrap();
+    let sentinel = config.sentinel.unwrap();
+    assert_eq!(sentinel.master, "mymaster");
+    assert_eq!(sentinel.db, 0);
+    assert_eq!(sentinel.password.unwrap().expose(), "secret");
+    let pool = config.pool.unwrap();
+    assert_eq!(pool.min_connections, Some(2));
+    assert_eq!(pool.max_connections, 10);
+}
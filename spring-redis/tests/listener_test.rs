@@ -0,0 +1,26 @@
+use spring_redis::pubsub::extractor::{Channel, Json, Payload};
+use spring_redis::redis_listener;
+
+#[redis_listener("user.created", "user.deleted", pattern = "order.*")]
+async fn on_user_event(Channel(_channel): Channel, Payload(_payload): Payload<String>) {}
+
+#[redis_listener(pattern = "audit.*")]
+async fn on_audit_event(Json(_event): Json<serde_json::Value>) {}
+
+#[test]
+fn test_redis_listener_registration() {
+    let listeners = spring_redis::pubsub::listeners();
+    let user = listeners
+        .iter()
+        .find(|l| l.name().ends_with("::on_user_event"))
+        .expect("on_user_event is registered");
+    assert_eq!(user.channels(), ["user.created", "user.deleted"]);
+    assert_eq!(user.patterns(), ["order.*"]);
+
+    let audit = listeners
+        .iter()
+        .find(|l| l.name().ends_with("::on_audit_event"))
+        .expect("on_audit_event is registered");
+    assert!(audit.channels().is_empty());
+    assert_eq!(audit.patterns(), ["audit.*"]);
+}
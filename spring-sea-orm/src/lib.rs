@@ -4,15 +4,18 @@
 
 pub mod config;
 pub mod pagination;
+/// Read/write splitting between the primary datasource and the read replicas
+pub mod routing;
 
 use anyhow::Context;
-use config::SeaOrmConfig;
-use sea_orm::{ConnectOptions, Database};
+use config::{DataSourceConfig, SeaOrmConfig};
+use routing::RoutingConnection;
+use sea_orm::{ConnectOptions, ConnectionTrait, Database};
+use spring::async_trait;
 use spring::config::ConfigRegistry;
-use spring::plugin::{ComponentRegistry, MutableComponentRegistry};
+use spring::health::{Health, HealthConfigurator, HealthIndicator};
+use spring::plugin::MutableComponentRegistry;
 use spring::{app::AppBuilder, error::Result, plugin::Plugin};
-use spring::{async_trait, App};
-use std::sync::Arc;
 use std::time::Duration;
 
 pub type DbConn = sea_orm::DbConn;
@@ -26,16 +29,54 @@ impl Plugin for SeaOrmPlugin {
             .get_config::<SeaOrmConfig>()
             .expect("sea-orm plugin config load failed");
 
-        let conn = Self::connect(&config)
+        Self::add_datasources(app, &config)
             .await
             .expect("sea-orm plugin load failed");
-        app.add_component(conn)
-            .add_shutdown_hook(|app| Box::new(Self::close_db_connection(app)));
+    }
+}
+
+/// Health indicator of the sea-orm connection, pings the database
+pub struct SeaOrmHealthIndicator {
+    name: String,
+    conn: DbConn,
+}
+
+impl SeaOrmHealthIndicator {
+    /// Health indicator of `conn` reported as `name`
+    pub fn new<S: Into<String>>(name: S, conn: DbConn) -> Self {
+        Self {
+            name: name.into(),
+            conn,
+        }
+    }
+}
+
+#[async_trait]
+impl HealthIndicator for SeaOrmHealthIndicator {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> Result<Health> {
+        self.conn
+            .ping()
+            .await
+            .context("sea-orm health check failed")?;
+        Ok(Health::up().with_detail("backend", format!("{:?}", self.conn.get_database_backend())))
     }
 }
 
 impl SeaOrmPlugin {
+    /// Connect to the datasource configured by `uri`
     pub async fn connect(config: &config::SeaOrmConfig) -> Result<DbConn> {
+        let datasource = config
+            .default_datasource()
+            .context("sea-orm.uri is not configured")?;
+        Self::connect_datasource(&datasource).await
+    }
+
+    /// Connect to a named datasource
+    pub async fn connect_datasource(config: &DataSourceConfig) -> Result<DbConn> {
         let mut opt = ConnectOptions::new(&config.uri);
         opt.max_connections(config.max_connections)
             .min_connections(config.min_connections)
@@ -56,12 +97,71 @@ impl SeaOrmPlugin {
             .with_context(|| format!("sea-orm connection failed:{}", &config.uri))?)
     }
 
-    async fn close_db_connection(app: Arc<App>) -> Result<String> {
-        app.get_component::<DbConn>()
-            .expect("sea-orm db connection not exists")
-            .close()
-            .await
-            .context("sea-orm db connection close failed")?;
+    /// Register the connection of `uri` as the default [`DbConn`], the connections of the named datasources
+    /// as named components, and the [`RoutingConnection`] if there are read replicas
+    async fn add_datasources(app: &mut AppBuilder, config: &SeaOrmConfig) -> Result<()> {
+        if config.uri.is_empty() && config.datasources.is_empty() {
+            return Err(
+                anyhow::anyhow!("sea-orm.uri or sea-orm.datasources must be configured").into(),
+            );
+        }
+        let mut conns = vec![];
+        let mut primary = None;
+        let mut replicas = vec![];
+        if let Some(datasource) = config.default_datasource() {
+            let conn = Self::connect_datasource(&datasource).await?;
+            app.add_health_indicator(SeaOrmHealthIndicator::new("sea_orm", conn.clone()));
+            primary = Some(conn.clone());
+            conns.push(conn);
+        }
+        for (name, datasource) in &config.datasources {
+            if datasource.primary && datasource.read_only {
+                return Err(anyhow::anyhow!(
+                    "sea-orm datasource {name} can't be both primary and read_only"
+                )
+                .into());
+            }
+            if datasource.primary && primary.is_some() {
+                return Err(anyhow::anyhow!("sea-orm datasource {name} is primary, but a primary datasource is already configured").into());
+            }
+            let conn = Self::connect_datasource(datasource)
+                .await
+                .with_context(|| format!("sea-orm datasource {name} load failed"))?;
+            tracing::info!("sea-orm datasource {name} connection success");
+            app.add_health_indicator(SeaOrmHealthIndicator::new(
+                format!("sea_orm.{name}"),
+                conn.clone(),
+            ))
+            .add_named_component(name, conn.clone());
+            if datasource.primary {
+                primary = Some(conn.clone());
+            }
+            if datasource.read_only {
+                replicas.push(conn.clone());
+            }
+            conns.push(conn);
+        }
+        if let Some(primary) = primary {
+            if !replicas.is_empty() {
+                app.add_component(RoutingConnection::new(primary.clone(), replicas));
+            }
+            app.add_component(primary);
+        } else if !replicas.is_empty() {
+            return Err(anyhow::anyhow!(
+                "sea-orm read_only datasources require a primary datasource"
+            )
+            .into());
+        }
+        app.add_shutdown_hook(move |_| Box::new(Self::close_db_connection(conns)));
+        Ok(())
+    }
+
+    async fn close_db_connection(conns: Vec<DbConn>) -> Result<String> {
+        for conn in conns {
+            conn.close()
+                .await
+                .context("sea-orm db connection close failed")?;
+        }
         Ok("sea-orm db connection close successful!".into())
     }
 }
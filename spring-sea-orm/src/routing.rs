@@ -0,0 +1,144 @@
+use crate::DbConn;
+use sea_orm::{
+    AccessMode, ConnectionTrait, DatabaseTransaction, DbBackend, DbErr, ExecResult, IsolationLevel,
+    QueryResult, Statement, TransactionError, TransactionTrait,
+};
+use spring::async_trait;
+pub use spring::sql::is_read_only;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Connection routing the statements between the primary datasource and its read replicas.
+///
+/// The read-only queries are sent to a replica, the other statements and the transactions
+/// to the primary datasource. The [`SeaOrmPlugin`](crate::SeaOrmPlugin) registers it as a
+/// component when a datasource is `read_only`.
+///
+/// ```rust,ignore
+/// async fn list_todos(Component(db): Component<RoutingConnection>) -> Result<Json<Vec<todo::Model>>> {
+///     // sent to a replica
+///     let todos = Todo::find().all(&db).await?;
+///     Ok(Json(todos))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RoutingConnection {
+    writer: DbConn,
+    readers: Arc<[DbConn]>,
+    next: Arc<AtomicUsize>,
+}
+
+impl RoutingConnection {
+    /// Route the writes to `writer` and the reads to `readers`
+    pub fn new(writer: DbConn, readers: Vec<DbConn>) -> Self {
+        Self {
+            writer,
+            readers: readers.into(),
+            next: Default::default(),
+        }
+    }
+
+    /// Connection of the primary datasource
+    pub fn writer(&self) -> &DbConn {
+        &self.writer
+    }
+
+    /// Connection of a read replica chosen round robin, the primary connection if there is no replica
+    pub fn reader(&self) -> &DbConn {
+        if self.readers.is_empty() {
+            return &self.writer;
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.readers.len();
+        &self.readers[index]
+    }
+
+    /// The [reader](Self::reader) if `sql` [is read-only](is_read_only), otherwise the [writer](Self::writer)
+    pub fn route(&self, sql: &str) -> &DbConn {
+        if is_read_only(sql) {
+            self.reader()
+        } else {
+            self.writer()
+        }
+    }
+}
+
+#[async_trait]
+impl ConnectionTrait for RoutingConnection {
+    fn get_database_backend(&self) -> DbBackend {
+        self.writer.get_database_backend()
+    }
+
+    async fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        self.writer.execute(stmt).await
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        self.writer.execute_unprepared(sql).await
+    }
+
+    async fn query_one(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        self.route(&stmt.sql).query_one(stmt).await
+    }
+
+    async fn query_all(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        self.route(&stmt.sql).query_all(stmt).await
+    }
+
+    fn support_returning(&self) -> bool {
+        self.writer.support_returning()
+    }
+
+    fn is_mock_connection(&self) -> bool {
+        self.writer.is_mock_connection()
+    }
+}
+
+#[async_trait]
+impl TransactionTrait for RoutingConnection {
+    async fn begin(&self) -> Result<DatabaseTransaction, DbErr> {
+        self.writer.begin().await
+    }
+
+    async fn begin_with_config(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<DatabaseTransaction, DbErr> {
+        self.writer
+            .begin_with_config(isolation_level, access_mode)
+            .await
+    }
+
+    async fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        self.writer.transaction(callback).await
+    }
+
+    async fn transaction_with_config<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::fmt::Display + std::fmt::Debug + Send,
+    {
+        self.writer
+            .transaction_with_config(callback, isolation_level, access_mode)
+            .await
+    }
+}
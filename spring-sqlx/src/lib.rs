@@ -3,16 +3,19 @@
 #![doc(html_logo_url = "https://spring-rs.github.io/logo.svg")]
 
 pub mod config;
+/// Read/write splitting between the primary datasource and the read replicas
+pub mod routing;
 pub extern crate sqlx;
 use anyhow::Context;
-use config::SqlxConfig;
+use config::{DataSourceConfig, SqlxConfig};
+use routing::RoutingPool;
 use spring::app::AppBuilder;
+use spring::async_trait;
 use spring::config::ConfigRegistry;
 use spring::error::Result;
-use spring::plugin::{ComponentRegistry, MutableComponentRegistry, Plugin};
-use spring::{async_trait, App};
+use spring::health::{Health, HealthConfigurator, HealthIndicator};
+use spring::plugin::{MutableComponentRegistry, Plugin};
 use sqlx::{Database, Pool};
-use std::sync::Arc;
 use std::time::Duration;
 
 #[cfg(not(feature = "postgres"))]
@@ -30,37 +33,133 @@ impl Plugin for SqlxPlugin {
             .get_config::<SqlxConfig>()
             .expect("sqlx plugin config load failed");
 
-        let connect_pool = Self::connect(&config)
+        Self::add_datasources(app, &config)
             .await
             .expect("sqlx plugin load failed");
 
         tracing::info!("sqlx connection success");
+    }
+}
+
+/// Health indicator of the sqlx connection pool, runs `SELECT 1`
+pub struct SqlxHealthIndicator {
+    name: String,
+    pool: ConnectPool,
+}
+
+impl SqlxHealthIndicator {
+    /// Health indicator of `pool` reported as `name`
+    pub fn new<S: Into<String>>(name: S, pool: ConnectPool) -> Self {
+        Self {
+            name: name.into(),
+            pool,
+        }
+    }
+}
+
+#[async_trait]
+impl HealthIndicator for SqlxHealthIndicator {
+    fn name(&self) -> &str {
+        &self.name
+    }
 
-        app.add_component(connect_pool)
-            .add_shutdown_hook(|app| Box::new(Self::close_db_connection(app)));
+    async fn check(&self) -> Result<Health> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .context("sqlx health check failed")?;
+        Ok(Health::up()
+            .with_detail("size", self.pool.size())
+            .with_detail("idle", self.pool.num_idle()))
     }
 }
 
 impl SqlxPlugin {
-    #[cfg(not(feature = "postgres"))]
+    /// Connect to the datasource configured by `uri`
     pub async fn connect(config: &SqlxConfig) -> Result<ConnectPool> {
+        let datasource = config
+            .default_datasource()
+            .context("sqlx.uri is not configured")?;
+        Self::connect_datasource(&datasource).await
+    }
+
+    /// Connect to a named datasource
+    #[cfg(not(feature = "postgres"))]
+    pub async fn connect_datasource(config: &DataSourceConfig) -> Result<ConnectPool> {
         use sqlx::any::AnyPoolOptions;
 
         let opt = Self::configure_pool(AnyPoolOptions::new(), config);
         Self::establish_connection(opt, &config.uri).await
     }
 
+    /// Connect to a named datasource
     #[cfg(feature = "postgres")]
-    pub async fn connect(config: &SqlxConfig) -> Result<ConnectPool> {
+    pub async fn connect_datasource(config: &DataSourceConfig) -> Result<ConnectPool> {
         use sqlx::postgres::PgPoolOptions;
 
         let opt = Self::configure_pool(PgPoolOptions::new(), config);
         Self::establish_connection(opt, &config.uri).await
     }
 
+    /// Register the pool of `uri` as the default [`ConnectPool`], the pools of the named datasources
+    /// as named components, and the [`RoutingPool`] if there are read replicas
+    async fn add_datasources(app: &mut AppBuilder, config: &SqlxConfig) -> Result<()> {
+        if config.uri.is_empty() && config.datasources.is_empty() {
+            return Err(anyhow::anyhow!("sqlx.uri or sqlx.datasources must be configured").into());
+        }
+        let mut pools = vec![];
+        let mut primary = None;
+        let mut replicas = vec![];
+        if let Some(datasource) = config.default_datasource() {
+            let pool = Self::connect_datasource(&datasource).await?;
+            app.add_health_indicator(SqlxHealthIndicator::new("sqlx", pool.clone()));
+            primary = Some(pool.clone());
+            pools.push(pool);
+        }
+        for (name, datasource) in &config.datasources {
+            if datasource.primary && datasource.read_only {
+                return Err(anyhow::anyhow!(
+                    "sqlx datasource {name} can't be both primary and read_only"
+                )
+                .into());
+            }
+            if datasource.primary && primary.is_some() {
+                return Err(anyhow::anyhow!("sqlx datasource {name} is primary, but a primary datasource is already configured").into());
+            }
+            let pool = Self::connect_datasource(datasource)
+                .await
+                .with_context(|| format!("sqlx datasource {name} load failed"))?;
+            tracing::info!("sqlx datasource {name} connection success");
+            app.add_health_indicator(SqlxHealthIndicator::new(
+                format!("sqlx.{name}"),
+                pool.clone(),
+            ))
+            .add_named_component(name, pool.clone());
+            if datasource.primary {
+                primary = Some(pool.clone());
+            }
+            if datasource.read_only {
+                replicas.push(pool.clone());
+            }
+            pools.push(pool);
+        }
+        if let Some(primary) = primary {
+            if !replicas.is_empty() {
+                app.add_component(RoutingPool::new(primary.clone(), replicas));
+            }
+            app.add_component(primary);
+        } else if !replicas.is_empty() {
+            return Err(
+                anyhow::anyhow!("sqlx read_only datasources require a primary datasource").into(),
+            );
+        }
+        app.add_shutdown_hook(move |_| Box::new(Self::close_db_connection(pools)));
+        Ok(())
+    }
+
     fn configure_pool<T>(
         mut opt: sqlx::pool::PoolOptions<T>,
-        config: &SqlxConfig,
+        config: &DataSourceConfig,
     ) -> sqlx::pool::PoolOptions<T>
     where
         T: Database,
@@ -92,11 +191,10 @@ impl SqlxPlugin {
             .with_context(|| format!("Failed to connect to database: {uri}"))?)
     }
 
-    async fn close_db_connection(app: Arc<App>) -> Result<String> {
-        app.get_component::<ConnectPool>()
-            .expect("sqlx connect pool not exists")
-            .close()
-            .await;
+    async fn close_db_connection(pools: Vec<ConnectPool>) -> Result<String> {
+        for pool in pools {
+            pool.close().await;
+        }
         Ok("sqlx connection pool close successful".into())
     }
 }
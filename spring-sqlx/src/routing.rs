@@ -0,0 +1,57 @@
+use crate::ConnectPool;
+pub use spring::sql::is_read_only;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Routes the statements between the primary datasource and its read replicas.
+///
+/// The [`SqlxPlugin`](crate::SqlxPlugin) registers it as a component when a datasource is `read_only`.
+///
+/// ```rust,ignore
+/// async fn list_users(Component(db): Component<RoutingPool>) -> Result<Vec<User>> {
+///     let sql = "SELECT * FROM users";
+///     // sent to a replica
+///     let users = sqlx::query_as(sql).fetch_all(db.route(sql)).await?;
+///     Ok(users)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RoutingPool {
+    writer: ConnectPool,
+    readers: Arc<[ConnectPool]>,
+    next: Arc<AtomicUsize>,
+}
+
+impl RoutingPool {
+    /// Route the writes to `writer` and the reads to `readers`
+    pub fn new(writer: ConnectPool, readers: Vec<ConnectPool>) -> Self {
+        Self {
+            writer,
+            readers: readers.into(),
+            next: Default::default(),
+        }
+    }
+
+    /// Pool of the primary datasource
+    pub fn writer(&self) -> &ConnectPool {
+        &self.writer
+    }
+
+    /// Pool of a read replica chosen round robin, the primary pool if there is no replica
+    pub fn reader(&self) -> &ConnectPool {
+        if self.readers.is_empty() {
+            return &self.writer;
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.readers.len();
+        &self.readers[index]
+    }
+
+    /// The [reader](Self::reader) if `sql` [is read-only](is_read_only), otherwise the [writer](Self::writer)
+    pub fn route(&self, sql: &str) -> &ConnectPool {
+        if is_read_only(sql) {
+            self.reader()
+        } else {
+            self.writer()
+        }
+    }
+}
@@ -28,12 +28,6 @@ impl Consumers {
         self.0.push(consumer);
         self
     }
-
-    pub(crate) fn merge(&mut self, consumers: Self) {
-        for consumer in consumers.0 {
-            self.0.push(consumer);
-        }
-    }
 }
 
 impl Deref for Consumers {
@@ -134,8 +128,14 @@ pub(crate) struct ConsumerInstance {
 impl ConsumerInstance {
     pub async fn schedule(self, app: Arc<App>) -> Result<String> {
         let ConsumerInstance { consumer, handler } = self;
+        let lifecycle = app.lifecycle().clone();
         loop {
-            let message = consumer.next().await.context("consumer poll msg failed")?;
+            let message = tokio::select! {
+                message = consumer.next() => message.context("consumer poll msg failed")?,
+                _ = lifecycle.shutdown_signal("stream consumer") => {
+                    return Ok("stream consumer finished".to_string());
+                }
+            };
             BoxedHandler::call(handler.clone(), message, app.clone()).await;
         }
     }
@@ -22,6 +22,7 @@ pub use spring_macros::stream_listener;
 
 use anyhow::Context;
 use config::StreamConfig;
+use consumer::Consumer;
 use sea_streamer::{
     Buffer, MessageHeader, Producer as _, SeaConsumer, SeaProducer, SeaStreamer, StreamKey,
     Streamer as _, StreamerUri,
@@ -31,13 +32,11 @@ use serde::Serialize;
 use spring::async_trait;
 use spring::config::ConfigRegistry;
 use spring::error::Result;
-use spring::plugin::component::ComponentRef;
 use spring::plugin::{ComponentRegistry, MutableComponentRegistry};
 use spring::{
     app::{App, AppBuilder},
     plugin::Plugin,
 };
-use std::ops::Deref;
 use std::{str::FromStr, sync::Arc};
 
 pub trait StreamConfigurator {
@@ -46,16 +45,10 @@ pub trait StreamConfigurator {
 
 impl StreamConfigurator for AppBuilder {
     fn add_consumer(&mut self, new_consumers: Consumers) -> &mut Self {
-        if let Some(consumers) = self.get_component_ref::<Consumers>() {
-            unsafe {
-                let raw_ptr = ComponentRef::into_raw(consumers);
-                let consumers = &mut *(raw_ptr as *mut Consumers);
-                consumers.merge(new_consumers);
-            }
-            self
-        } else {
-            self.add_component(new_consumers)
+        for consumer in new_consumers.iter() {
+            self.append_component(consumer.clone());
         }
+        self
     }
 }
 
@@ -70,21 +63,21 @@ impl Plugin for StreamPlugin {
 
         let streamer = Streamer::new(config).await.expect("create streamer failed");
 
-        if let Some(consumers) = app.get_component_ref::<Consumers>() {
-            for consumer in consumers.deref().iter() {
-                let consumer_instance = consumer
-                    .new_instance(&streamer)
-                    .await
-                    .expect("create customer instance failed");
-                app.add_scheduler(|app: Arc<App>| Box::new(consumer_instance.schedule(app)));
-                tracing::info!(
-                    "register scheduler for \"{:?}\" stream consumer",
-                    consumer.stream_keys
-                );
-            }
-        } else {
+        let consumers = app.get_components::<Consumer>();
+        if consumers.is_empty() {
             tracing::info!("not consumer be registry");
         }
+        for consumer in consumers {
+            let consumer_instance = consumer
+                .new_instance(&streamer)
+                .await
+                .expect("create customer instance failed");
+            app.add_scheduler(|app: Arc<App>| Box::new(consumer_instance.schedule(app)));
+            tracing::info!(
+                "register scheduler for \"{:?}\" stream consumer",
+                consumer.stream_keys
+            );
+        }
         let producer = streamer
             .create_generic_producer()
             .await
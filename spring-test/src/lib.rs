@@ -0,0 +1,28 @@
+//! [![spring-rs](https://img.shields.io/github/stars/spring-rs/spring-rs)](https://spring-rs.github.io/docs/plugins/spring-test)
+#![doc = include_str!("../README.md")]
+#![doc(html_favicon_url = "https://spring-rs.github.io/favicon.ico")]
+#![doc(html_logo_url = "https://spring-rs.github.io/logo.svg")]
+
+/// In-process client for the routes of spring-web
+#[cfg(feature = "web")]
+pub mod web;
+
+#[cfg(feature = "web")]
+pub use web::{TestClient, TestResponse};
+
+use spring::app::AppBuilder;
+
+/// Create an [`AppBuilder`] for a test, configured by the inline toml `config`.
+///
+/// * `./config/app.toml`, the profile files and the `.env` file are not read,
+///   the `SPRING_*` environment variables and the command line arguments are ignored,
+///   see [`AppBuilder::from_config_str`]
+/// * the built app is the [global app](spring::App::global) of the current thread only,
+///   so tests running in parallel don't share it
+///
+/// Components can be replaced with mocks by [`AppBuilder::override_component`] before the plugins are built.
+pub fn test_app(config: &str) -> AppBuilder {
+    let mut app = AppBuilder::from_config_str(config);
+    app.isolate_global(true);
+    app
+}
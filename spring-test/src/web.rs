@@ -0,0 +1,201 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use spring::error::Result;
+use spring::App;
+use spring_web::axum::body::{Body, Bytes};
+use spring_web::axum::extract::connect_info::MockConnectInfo;
+use spring_web::axum::http::{header, HeaderMap, Method, Request, StatusCode};
+use spring_web::axum::Router;
+use spring_web::WebPlugin;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Calls the router of the [`WebPlugin`] in process, without binding a tcp port.
+///
+/// The router is the one served by the web server, with the router layers,
+/// the management endpoints and the [`AppState`](spring_web::AppState) extension.
+///
+/// ```rust,ignore
+/// let app = spring_test::test_app("[web]").add_plugin(WebPlugin).build().await?;
+/// let client = TestClient::new(app)?;
+/// let response = client.get("/hello").await;
+/// assert_eq!(response.status(), StatusCode::OK);
+/// assert_eq!(response.text(), "hello");
+/// ```
+#[derive(Clone)]
+pub struct TestClient {
+    router: Router,
+}
+
+impl TestClient {
+    /// Create a client for the app, the app must be built with the [`WebPlugin`]
+    pub fn new(app: Arc<App>) -> Result<Self> {
+        let router = WebPlugin::router(app)?
+            // the handlers extracting `ConnectInfo` see a client on localhost
+            .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+        Ok(Self { router })
+    }
+
+    /// Send a `GET` request
+    pub async fn get(&self, uri: &str) -> TestResponse {
+        self.send(Self::request(Method::GET, uri, Body::empty()))
+            .await
+    }
+
+    /// Send a `DELETE` request
+    pub async fn delete(&self, uri: &str) -> TestResponse {
+        self.send(Self::request(Method::DELETE, uri, Body::empty()))
+            .await
+    }
+
+    /// Send a `POST` request with a json body
+    pub async fn post_json<T: Serialize>(&self, uri: &str, body: &T) -> TestResponse {
+        self.send(Self::json_request(Method::POST, uri, body)).await
+    }
+
+    /// Send a `PUT` request with a json body
+    pub async fn put_json<T: Serialize>(&self, uri: &str, body: &T) -> TestResponse {
+        self.send(Self::json_request(Method::PUT, uri, body)).await
+    }
+
+    /// Send any request
+    pub async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = match self.router.clone().oneshot(request).await {
+            Ok(response) => response,
+            Err(infallible) => match infallible {},
+        };
+        let (parts, body) = response.into_parts();
+        let body = spring_web::axum::body::to_bytes(body, usize::MAX)
+            .await
+            .expect("read response body failed");
+        TestResponse {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+        }
+    }
+
+    fn request(method: Method, uri: &str, body: Body) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(body)
+            .expect("invalid request")
+    }
+
+    fn json_request<T: Serialize>(method: Method, uri: &str, body: &T) -> Request<Body> {
+        let body = serde_json::to_vec(body).expect("serialize request body failed");
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .expect("invalid request")
+    }
+}
+
+/// Response returned by the [`TestClient`], the body is fully read
+#[derive(Debug, Clone)]
+pub struct TestResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl TestResponse {
+    /// Status code of the response
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Headers of the response
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Raw body of the response
+    pub fn bytes(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Body of the response as text
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Deserialize the json body of the response
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body).unwrap_or_else(|e| {
+            panic!(
+                "response body is not the expected json: {e}\n{}",
+                self.text()
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TestClient;
+    use serde::{Deserialize, Serialize};
+    use spring::error::Result;
+    use spring::plugin::MutableComponentRegistry;
+    use spring_web::axum::http::StatusCode;
+    use spring_web::axum::routing::{get, post};
+    use spring_web::axum::Json;
+    use spring_web::extractor::Component;
+    use spring_web::{Router, WebConfigurator, WebPlugin};
+
+    #[derive(Clone)]
+    struct Greeter(&'static str);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Greeting {
+        name: String,
+    }
+
+    async fn hello(Component(greeter): Component<Greeter>) -> String {
+        format!("{}, world", greeter.0)
+    }
+
+    async fn greet(Json(greeting): Json<Greeting>) -> Json<Greeting> {
+        Json(Greeting {
+            name: greeting.name.to_uppercase(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_client() -> Result<()> {
+        let config = r#"
+            [web]
+            global_prefix = "/api"
+            [web.openapi]
+        "#;
+        let app = crate::test_app(config)
+            .override_component(Greeter("mocked hello"))
+            .add_component(Greeter("hello"))
+            .add_router(
+                Router::new()
+                    .route("/hello", get(hello))
+                    .route("/greet", post(greet)),
+            )
+            .add_plugin(WebPlugin)
+            .build()
+            .await?;
+        let client = TestClient::new(app)?;
+
+        let response = client.get("/api/hello").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text(), "mocked hello, world");
+
+        let name = "spring".to_string();
+        let response = client.post_json("/api/greet", &Greeting { name }).await;
+        let greeting: Greeting = response.json();
+        assert_eq!(greeting.name, "SPRING");
+
+        let response = client.get("/hello").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        Ok(())
+    }
+}
@@ -0,0 +1,456 @@
+//! Management endpoints to inspect a running application, similar to Spring Boot Actuator.
+//!
+//! The endpoints are enabled in the `[web.management]` configuration:
+//!
+//! ```toml
+//! [web.management]
+//! enable = true
+//! port = 8081                      # optional, serve the endpoints on a separate port
+//! prefix = "/actuator"             # default
+//! endpoints = ["health", "info"]   # default, "env", "components", "loggers" and "scheduledtasks" are opt-in
+//! ```
+//!
+//! The endpoints are not authenticated: expose the sensitive ones only on a port that is not public.
+use crate::config::{ManagementConfig, ManagementEndpoint};
+use crate::extractor::AppRef;
+use crate::AppState;
+use anyhow::Context;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use spring::error::Result;
+use spring::health::HealthStatus;
+use spring::lifecycle::{LifecycleState, StartupGuard};
+use spring::plugin::ComponentRegistry;
+use spring::App;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Build information of the application, returned by the `info` endpoint.
+///
+/// Register it with the [`build_info!`](crate::build_info) macro,
+/// which reads the package name and version of the application crate:
+///
+/// ```rust,ignore
+/// App::new()
+///     .add_component(spring_web::build_info!())
+///     .add_plugin(WebPlugin)
+///     .run()
+///     .await
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    /// package name
+    pub name: &'static str,
+    /// package version
+    pub version: &'static str,
+}
+
+/// Create the [`BuildInfo`] of the calling crate
+#[macro_export]
+macro_rules! build_info {
+    () => {
+        $crate::actuator::BuildInfo {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        }
+    };
+}
+
+/// Status reported by the `health` endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Status {
+    /// The application is working
+    Up,
+    /// The application is not working
+    Down,
+    /// The application is starting or shutting down
+    OutOfService,
+}
+
+impl Status {
+    fn status_code(self) -> StatusCode {
+        match self {
+            Self::Up => StatusCode::OK,
+            _ => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// Router of the exposed management endpoints
+pub(crate) fn router(config: &ManagementConfig) -> axum::Router {
+    let mut router = axum::Router::new();
+    if config.is_exposed(ManagementEndpoint::Health) {
+        router = router
+            .route("/health", get(health))
+            .route("/health/liveness", get(liveness))
+            .route("/health/readiness", get(readiness));
+    }
+    if config.is_exposed(ManagementEndpoint::Info) {
+        let extra = config.info.clone();
+        router = router.route("/info", get(move |app: AppRef| info(app, extra)));
+    }
+    if config.is_exposed(ManagementEndpoint::Env) {
+        router = router.route("/env", get(env));
+    }
+    if config.is_exposed(ManagementEndpoint::Components) {
+        router = router.route("/components", get(components));
+    }
+    if config.is_exposed(ManagementEndpoint::Loggers) {
+        router = router
+            .route("/loggers", get(loggers))
+            .route("/loggers/{target}", post(set_logger_level));
+    }
+    #[cfg(feature = "job")]
+    if config.is_exposed(ManagementEndpoint::ScheduledTasks) {
+        router = router.route("/scheduledtasks", get(scheduled_tasks));
+    }
+    match config.prefix.trim_end_matches('/') {
+        "" => router,
+        prefix => axum::Router::new().nest(prefix, router),
+    }
+}
+
+/// Serve the management endpoints on a separate port
+pub(crate) async fn serve(
+    app: Arc<App>,
+    binding: IpAddr,
+    config: ManagementConfig,
+    startup: StartupGuard,
+) -> Result<String> {
+    let port = config.port.expect("management port is not configured");
+    let addr = SocketAddr::new(binding, port);
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("bind management tcp listener failed:{addr}"))?;
+    tracing::info!("bind management tcp listener: {addr}");
+    startup.ready();
+
+    let lifecycle = app.lifecycle().clone();
+    let router = router(&config).layer(Extension(AppState { app }));
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async move { lifecycle.shutdown_signal("management server").await })
+        .await
+        .context("start management server failed")?;
+    Ok("management server finished".to_string())
+}
+
+fn lifecycle_status(state: LifecycleState) -> Status {
+    match state {
+        LifecycleState::Ready => Status::Up,
+        LifecycleState::Starting | LifecycleState::Draining => Status::OutOfService,
+        LifecycleState::Stopped => Status::Down,
+    }
+}
+
+fn status_response(status: Status, body: Value) -> (StatusCode, Json<Value>) {
+    (status.status_code(), Json(body))
+}
+
+async fn health(AppRef(app): AppRef) -> (StatusCode, Json<Value>) {
+    let state = app.lifecycle().state();
+    let report = app.check_health().await;
+    let status = combined_status(lifecycle_status(state), report.status);
+    status_response(
+        status,
+        json!({
+            "status": status,
+            "lifecycle": state.to_string(),
+            "components": report.components,
+        }),
+    )
+}
+
+async fn liveness(AppRef(app): AppRef) -> (StatusCode, Json<Value>) {
+    let status = if app.lifecycle().is_alive() {
+        Status::Up
+    } else {
+        Status::Down
+    };
+    status_response(status, json!({ "status": status }))
+}
+
+async fn readiness(AppRef(app): AppRef) -> (StatusCode, Json<Value>) {
+    let status = lifecycle_status(app.lifecycle().state());
+    let status = if status == Status::Up {
+        combined_status(status, app.check_health().await.status)
+    } else {
+        status
+    };
+    status_response(status, json!({ "status": status }))
+}
+
+/// A started application with a component down is down
+fn combined_status(lifecycle: Status, health: HealthStatus) -> Status {
+    match (lifecycle, health) {
+        (Status::Up, HealthStatus::Down) => Status::Down,
+        (status, _) => status,
+    }
+}
+
+async fn info(AppRef(app): AppRef, extra: serde_json::Map<String, Value>) -> Json<Value> {
+    let mut info = extra;
+    if let Some(build) = app.get_component_ref::<BuildInfo>() {
+        info.insert("build".to_string(), json!(*build));
+    }
+    let profiles: Vec<&str> = app.get_profiles().iter().collect();
+    info.insert("profiles".to_string(), json!(profiles));
+    Json(Value::Object(info))
+}
+
+async fn env(AppRef(app): AppRef) -> Json<Value> {
+    let config = app.config_registry();
+    let profiles: Vec<&str> = app.get_profiles().iter().collect();
+    Json(json!({
+        "profiles": profiles,
+        "property_sources": config.property_sources(),
+        "properties": config.properties(),
+    }))
+}
+
+async fn components(AppRef(app): AppRef) -> Json<Value> {
+    let named_components: Vec<Value> = app
+        .named_component_names()
+        .into_iter()
+        .map(|(type_name, name)| json!({ "type": type_name, "name": name }))
+        .collect();
+    Json(json!({
+        "components": app.component_names(),
+        "named_components": named_components,
+    }))
+}
+
+async fn loggers(AppRef(app): AppRef) -> (StatusCode, Json<Value>) {
+    match app.logger() {
+        Some(logger) => (StatusCode::OK, Json(json!(logger.levels()))),
+        None => logger_unavailable(),
+    }
+}
+
+/// Request body of `POST /loggers/{target}`
+#[derive(Debug, Deserialize)]
+struct LoggerLevel {
+    /// new level of the target, `null` resets it to the configured level
+    level: Option<String>,
+    /// reset the level after the given number of seconds
+    revert_after: Option<u64>,
+}
+
+async fn set_logger_level(
+    AppRef(app): AppRef,
+    Path(target): Path<String>,
+    Json(body): Json<LoggerLevel>,
+) -> (StatusCode, Json<Value>) {
+    let Some(logger) = app.logger() else {
+        return logger_unavailable();
+    };
+    let result = match body.level {
+        Some(level) => {
+            logger.set_level(&target, &level, body.revert_after.map(Duration::from_secs))
+        }
+        None => logger.reset(&target),
+    };
+    match result {
+        Ok(()) => (StatusCode::OK, Json(json!(logger.levels()))),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+fn logger_unavailable() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(
+            json!({ "error": "the log levels can't be changed, the logger is not installed by spring" }),
+        ),
+    )
+}
+
+#[cfg(feature = "job")]
+async fn scheduled_tasks(AppRef(app): AppRef) -> Json<Value> {
+    use spring_job::job::Trigger;
+
+    let tasks: Vec<Value> = app
+        .get_components::<spring_job::job::Job>()
+        .iter()
+        .map(|job| {
+            let (trigger, schedule) = match job.trigger() {
+                Trigger::OneShot(seconds) => ("one_shot", json!({ "delay": seconds })),
+                Trigger::FixedDelay(seconds) => ("fixed_delay", json!({ "interval": seconds })),
+                Trigger::FixedRate(seconds) => ("fixed_rate", json!({ "interval": seconds })),
+                Trigger::Cron(cron) => ("cron", json!({ "expression": cron })),
+            };
+            json!({
+                "handler": job.handler_name(),
+                "trigger": trigger,
+                "schedule": schedule,
+            })
+        })
+        .collect();
+    Json(json!({ "tasks": tasks }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::router;
+    use crate::config::ManagementConfig;
+    use crate::AppState;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::Extension;
+    use serde_json::Value;
+    use spring::app::AppBuilder;
+    use spring::health::{Health, HealthConfigurator, HealthIndicator};
+    use spring::plugin::MutableComponentRegistry;
+    use tower::ServiceExt;
+
+    async fn get(router: &axum::Router, uri: &str) -> (StatusCode, Value) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_management_endpoints() {
+        let app = AppBuilder::from_config_str("[db]\nuser = \"admin\"\npassword = \"p@ssw0rd\"")
+            .add_component(crate::build_info!())
+            .add_named_component("replica", 42u32)
+            .isolate_global(true)
+            .build()
+            .await
+            .expect("app build failed");
+        let config: ManagementConfig = toml::from_str(
+            "enable = true\nendpoints = [\"health\", \"info\", \"env\", \"components\"]",
+        )
+        .unwrap();
+        let router = router(&config).layer(Extension(AppState { app }));
+
+        let (status, health) = get(&router, "/actuator/health").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(health["status"], "UP");
+
+        let (_, info) = get(&router, "/actuator/info").await;
+        assert_eq!(info["build"]["name"], "spring-web");
+
+        let (_, env) = get(&router, "/actuator/env").await;
+        let properties = env["properties"].as_array().unwrap();
+        let password = properties
+            .iter()
+            .find(|p| p["key"] == "db.password")
+            .unwrap();
+        assert_eq!(password["value"], "******");
+
+        let (_, components) = get(&router, "/actuator/components").await;
+        assert!(components["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|c| c == "spring_web::actuator::BuildInfo"));
+        assert_eq!(
+            components["named_components"][0],
+            serde_json::json!({ "type": "u32", "name": "replica" })
+        );
+
+        let request = Request::builder()
+            .uri("/actuator/loggers")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    struct DatabaseIndicator;
+
+    #[spring::async_trait]
+    impl HealthIndicator for DatabaseIndicator {
+        fn name(&self) -> &str {
+            "db"
+        }
+
+        async fn check(&self) -> spring::error::Result<Health> {
+            Ok(Health::down().with_detail("error", "connection refused"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_indicators() {
+        let app = AppBuilder::from_config_str("")
+            .add_health_indicator(DatabaseIndicator)
+            .isolate_global(true)
+            .build()
+            .await
+            .expect("app build failed");
+        let config: ManagementConfig = toml::from_str("enable = true").unwrap();
+        let router = router(&config).layer(Extension(AppState { app }));
+
+        // only health and info are exposed by default
+        let request = Request::builder()
+            .uri("/actuator/env")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let (status, health) = get(&router, "/actuator/health").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(health["status"], "DOWN");
+        assert_eq!(health["components"]["db"]["status"], "DOWN");
+        assert_eq!(
+            health["components"]["db"]["details"]["error"],
+            "connection refused"
+        );
+
+        let (status, _) = get(&router, "/actuator/health/readiness").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        let (status, _) = get(&router, "/actuator/health/liveness").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_loggers() {
+        let app = AppBuilder::from_config_str("")
+            .isolate_global(true)
+            .build()
+            .await
+            .expect("app build failed");
+        let config: ManagementConfig =
+            toml::from_str("enable = true\nendpoints = [\"loggers\"]").unwrap();
+        let router = router(&config).layer(Extension(AppState { app }));
+
+        let post = |uri: &str, body: &str| {
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let request = post("/actuator/loggers/hyper", r#"{"level":"debug"}"#);
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let (_, loggers) = get(&router, "/actuator/loggers").await;
+        assert_eq!(loggers["overrides"]["hyper"], "debug");
+
+        let request = post("/actuator/loggers/hyper", r#"{"level":"loud"}"#);
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let request = post("/actuator/loggers/hyper", r#"{"level":null}"#);
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let (_, loggers) = get(&router, "/actuator/loggers").await;
+        assert!(loggers["overrides"].get("hyper").is_none());
+    }
+}
@@ -18,6 +18,7 @@ pub struct WebConfig {
     #[cfg(feature = "openapi")]
     pub(crate) openapi: OpenApiConfig,
     pub(crate) middlewares: Option<Middlewares>,
+    pub(crate) management: Option<ManagementConfig>,
 }
 
 #[derive(Debug, Clone, JsonSchema, Deserialize)]
@@ -43,6 +44,58 @@ pub struct OpenApiConfig {
     pub(crate) info: aide::openapi::Info,
 }
 
+/// Management endpoints configuration
+#[derive(Debug, Clone, JsonSchema, Deserialize)]
+pub struct ManagementConfig {
+    /// toggle enable
+    #[serde(default)]
+    pub(crate) enable: bool,
+    /// Serve the endpoints on a separate port, by default they are served by the web server
+    pub(crate) port: Option<u16>,
+    /// Path prefix of the endpoints
+    #[serde(default = "default_management_prefix")]
+    pub(crate) prefix: String,
+    /// Exposed endpoints, only `health` and `info` are exposed by default,
+    /// the other endpoints reveal the configuration or change the application and must be listed explicitly
+    #[serde(default = "default_management_endpoints")]
+    pub(crate) endpoints: Vec<ManagementEndpoint>,
+    /// Additional properties returned by the `info` endpoint
+    #[serde(default)]
+    pub(crate) info: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ManagementConfig {
+    pub(crate) fn is_exposed(&self, endpoint: ManagementEndpoint) -> bool {
+        self.endpoints.contains(&endpoint)
+    }
+}
+
+/// Management endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, JsonSchema, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManagementEndpoint {
+    /// `/health`: status of the application
+    Health,
+    /// `/info`: build information
+    Info,
+    /// `/env`: effective configuration, secrets are masked
+    Env,
+    /// `/components`: registered components
+    Components,
+    /// `/loggers`: logger configuration
+    Loggers,
+    /// `/scheduledtasks`: jobs of spring-job, requires the `job` feature
+    ScheduledTasks,
+}
+
+fn default_management_prefix() -> String {
+    "/actuator".into()
+}
+
+fn default_management_endpoints() -> Vec<ManagementEndpoint> {
+    vec![ManagementEndpoint::Health, ManagementEndpoint::Info]
+}
+
 fn default_binding() -> IpAddr {
     IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0))
 }
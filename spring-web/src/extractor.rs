@@ -4,9 +4,12 @@ use crate::error::{Result, WebError};
 use crate::AppState;
 use anyhow::Context;
 use axum::http::request::Parts;
+use axum::http::Extensions;
 use spring::config::{ConfigRegistry, Configurable};
+use spring::plugin::scope::{RequestComponents, RequestRegistry, Scope};
 use spring::plugin::ComponentRegistry;
 use spring::App;
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::result::Result as StdResult;
 use std::sync::Arc;
@@ -19,6 +22,9 @@ pub trait RequestPartsExt {
     /// get Component
     fn get_component<T: Clone + Send + Sync + 'static>(&self) -> Result<T>;
 
+    /// get the Component registered with a name
+    fn get_component_named<T: Clone + Send + Sync + 'static>(&self, name: &str) -> Result<T>;
+
     /// get Config
     fn get_config<T: serde::de::DeserializeOwned + Configurable>(&self) -> Result<T>;
 }
@@ -38,6 +44,14 @@ impl RequestPartsExt for Parts {
             .context("get_component failed")?)
     }
 
+    fn get_component_named<T: Clone + Send + Sync + 'static>(&self, name: &str) -> Result<T> {
+        Ok(self
+            .get_app_state()
+            .app
+            .try_get_component_named(name)
+            .context("get_component_named failed")?)
+    }
+
     fn get_config<T: serde::de::DeserializeOwned + Configurable>(&self) -> Result<T> {
         self.get_app_state()
             .app
@@ -46,7 +60,54 @@ impl RequestPartsExt for Parts {
     }
 }
 
-/// Extract the components registered by the plugin from AppState
+/// Components of [request scope](Scope::Request) of the current request.
+///
+/// It is stored in the extensions of the request, the components are dropped with the request.
+/// A middleware can put a component in it, the [`Component`] extractor of the handler then finds it:
+///
+/// ```rust,ignore
+/// async fn tenant_middleware(mut request: Request, next: Next) -> Response {
+///     let tenant = TenantContext::from_headers(request.headers());
+///     RequestScope::of(request.extensions_mut()).insert(tenant);
+///     next.run(request).await
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct RequestScope(RequestComponents);
+
+impl RequestScope {
+    /// The scope of the request, created by the first call
+    pub fn of(extensions: &mut Extensions) -> Self {
+        match extensions.get::<Self>() {
+            Some(scope) => scope.clone(),
+            None => {
+                let scope = Self::default();
+                extensions.insert(scope.clone());
+                scope
+            }
+        }
+    }
+
+    /// Put the component in the scope, it replaces the component of the same type
+    pub fn insert<T: Clone + Send + Sync + 'static>(&self, component: T) {
+        self.0.insert(component);
+    }
+
+    /// The component of type `T` of the scope
+    pub fn get<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.0.get::<T>().map(|c| T::clone(&c))
+    }
+
+    /// Registry of the request, the components of request scope are created in this scope
+    pub fn registry(&self, app: Arc<App>) -> RequestRegistry {
+        RequestRegistry::new(app, self.0.clone())
+    }
+}
+
+/// Extract the components registered by the plugin from AppState.
+///
+/// A component of [request scope](Scope::Request) is created once per request
+/// and shared by the extractors of the request, see [`RequestScope`].
 pub struct Component<T: Clone>(pub T);
 
 impl<T, S> FromRequestParts<S> for Component<T>
@@ -57,6 +118,21 @@ where
     type Rejection = WebError;
 
     async fn from_request_parts(parts: &mut Parts, _s: &S) -> StdResult<Self, Self::Rejection> {
+        let app = parts.get_app_state().app.clone();
+        let scoped = parts.extensions.get::<RequestScope>().is_some()
+            || app
+                .component_factory::<T>()
+                .is_some_and(|f| f.scope() == Scope::Request);
+        if scoped {
+            let component = RequestScope::of(&mut parts.extensions)
+                .registry(app)
+                .get_scoped_component::<T>()
+                .await
+                .context("create request scoped component failed")?;
+            if let Some(component) = component {
+                return Ok(Component(T::clone(&component)));
+            }
+        }
         parts.get_component::<T>().map(|c| Component(c))
     }
 }
@@ -78,6 +154,69 @@ impl<T: Clone> DerefMut for Component<T> {
     }
 }
 
+/// Name of a component registered by
+/// [`add_named_component`](spring::plugin::MutableComponentRegistry::add_named_component),
+/// used by the [`NamedComponent`] extractor. Define it with the [`qualifier!`](crate::qualifier) macro.
+pub trait Qualifier: Send + Sync + 'static {
+    /// name of the component
+    const NAME: &'static str;
+}
+
+/// Define a [`Qualifier`] type
+///
+/// ```rust,ignore
+/// qualifier!(pub Replica = "replica");
+///
+/// async fn handler(NamedComponent(db, _): NamedComponent<DbConn, Replica>) {}
+/// ```
+#[macro_export]
+macro_rules! qualifier {
+    ($(#[$meta:meta])* $vis:vis $ident:ident = $name:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default)]
+        $vis struct $ident;
+
+        impl $crate::extractor::Qualifier for $ident {
+            const NAME: &'static str = $name;
+        }
+    };
+}
+
+/// Extract the component registered with the name of the [`Qualifier`] `Q` from AppState
+pub struct NamedComponent<T: Clone, Q: Qualifier>(pub T, pub PhantomData<Q>);
+
+impl<T, Q, S> FromRequestParts<S> for NamedComponent<T, Q>
+where
+    T: Clone + Send + Sync + 'static,
+    Q: Qualifier,
+    S: Sync,
+{
+    type Rejection = WebError;
+
+    async fn from_request_parts(parts: &mut Parts, _s: &S) -> StdResult<Self, Self::Rejection> {
+        parts
+            .get_component_named::<T>(Q::NAME)
+            .map(|c| NamedComponent(c, PhantomData))
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl<T: Clone, Q: Qualifier> aide::OperationInput for NamedComponent<T, Q> {}
+
+impl<T: Clone, Q: Qualifier> Deref for NamedComponent<T, Q> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Clone, Q: Qualifier> DerefMut for NamedComponent<T, Q> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 pub struct Config<T>(pub T)
 where
     T: serde::de::DeserializeOwned + Configurable;
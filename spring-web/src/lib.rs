@@ -3,6 +3,8 @@
 #![doc(html_favicon_url = "https://spring-rs.github.io/favicon.ico")]
 #![doc(html_logo_url = "https://spring-rs.github.io/logo.svg")]
 
+/// management endpoints
+pub mod actuator;
 /// spring-web config
 pub mod config;
 /// spring-web defined error
@@ -24,7 +26,6 @@ pub use { socketioxide, rmpv };
 
 pub use axum;
 pub use spring::async_trait;
-use spring::signal;
 /////////////////web-macros/////////////////////
 /// To use these Procedural Macros, you need to add `spring-web` dependency
 pub use spring_macros::middlewares;
@@ -97,18 +98,20 @@ use aide::transform::TransformOpenApi;
 
 use anyhow::Context;
 use axum::Extension;
+use config::ManagementConfig;
 use config::ServerConfig;
 use config::WebConfig;
-use spring::plugin::component::ComponentRef;
 use spring::plugin::ComponentRegistry;
 use spring::plugin::MutableComponentRegistry;
 use spring::{
     app::{App, AppBuilder},
     config::ConfigRegistry,
     error::Result,
+    lifecycle::StartupGuard,
     plugin::Plugin,
 };
-use std::{net::SocketAddr, ops::Deref, sync::Arc};
+use std::future::IntoFuture;
+use std::{net::SocketAddr, sync::Arc};
 
 #[cfg(feature = "socket_io")]
 use config::SocketIOConfig;
@@ -118,8 +121,17 @@ use crate::config::OpenApiConfig;
 
 /// Routers collection
 #[cfg(feature = "openapi")]
+#[deprecated(
+    since = "0.4.18",
+    note = "the routers are appended components, use `app.get_components::<Router>()`"
+)]
 pub type Routers = Vec<aide::axum::ApiRouter>;
+/// Routers collection
 #[cfg(not(feature = "openapi"))]
+#[deprecated(
+    since = "0.4.18",
+    note = "the routers are appended components, use `app.get_components::<Router>()`"
+)]
 pub type Routers = Vec<axum::Router>;
 
 /// Router layer function type
@@ -140,6 +152,10 @@ pub type Routers = Vec<axum::Router>;
 pub type RouterLayer = Arc<dyn Fn(Router) -> Router + Send + Sync>;
 
 /// Collection of router layers
+#[deprecated(
+    since = "0.4.18",
+    note = "the router layers are appended components, use `app.get_components::<RouterLayer>()`"
+)]
 pub type RouterLayers = Vec<RouterLayer>;
 
 /// Trait for adding layers to the web router
@@ -167,17 +183,7 @@ impl LayerConfigurator for AppBuilder {
     where
         F: Fn(Router) -> Router + Send + Sync + 'static,
     {
-        if let Some(layers) = self.get_component_ref::<RouterLayers>() {
-            unsafe {
-                let raw_ptr = ComponentRef::into_raw(layers);
-                let layers = &mut *(raw_ptr as *mut RouterLayers);
-                layers.push(Arc::new(layer));
-            }
-            self
-        } else {
-            let layers: RouterLayers = vec![Arc::new(layer)];
-            self.add_component(layers)
-        }
+        self.append_component::<RouterLayer>(Arc::new(layer))
     }
 }
 
@@ -201,16 +207,7 @@ pub trait WebConfigurator {
 
 impl WebConfigurator for AppBuilder {
     fn add_router(&mut self, router: Router) -> &mut Self {
-        if let Some(routers) = self.get_component_ref::<Routers>() {
-            unsafe {
-                let raw_ptr = ComponentRef::into_raw(routers);
-                let routers = &mut *(raw_ptr as *mut Routers);
-                routers.push(router);
-            }
-            self
-        } else {
-            self.add_component(vec![router])
-        }
+        self.append_component(router)
     }
 
     /// Initialize OpenAPI Documents
@@ -225,6 +222,11 @@ impl WebConfigurator for AppBuilder {
     }
 }
 
+/// Router merged from the routers added by [`WebConfigurator::add_router`],
+/// registered apart from them so that `app.get_components::<Router>()` only lists the added routers
+#[derive(Clone)]
+struct MergedRouter(Router);
+
 /// State of App
 #[derive(Clone)]
 pub struct AppState {
@@ -246,17 +248,10 @@ impl Plugin for WebPlugin {
         let socketio_config = app.get_config::<SocketIOConfig>().ok();
 
         // 1. collect router
-        let routers = app.get_component_ref::<Routers>();
-        let mut router: Router = match routers {
-            Some(rs) => {
-                let mut router = Router::new();
-                for r in rs.deref().iter() {
-                    router = router.merge(r.to_owned());
-                }
-                router
-            }
-            None => Router::new(),
-        };
+        let mut router = Router::new();
+        for r in app.get_components::<Router>() {
+            router = router.merge(r);
+        }
         if let Some(middlewares) = config.middlewares {
             router = crate::middleware::apply_middleware(router, middlewares);
         }
@@ -266,7 +261,7 @@ impl Plugin for WebPlugin {
             router =  enable_socketio(socketio_config, app, router);
         }
 
-        app.add_component(router);
+        app.add_component(MergedRouter(router));
 
         let server_conf = config.server;
         #[cfg(feature = "openapi")]
@@ -275,44 +270,100 @@ impl Plugin for WebPlugin {
             app.add_component(openapi_conf.clone());
         }
 
-        app.add_scheduler(move |app: Arc<App>| Box::new(Self::schedule(app, server_conf)));
+        // management endpoints are served by the web server unless they have their own port
+        let management = config.management.filter(|m| m.enable);
+        let embedded_management = match management {
+            Some(management) if management.port.is_some() => {
+                let binding = server_conf.binding;
+                app.add_scheduler(move |app: Arc<App>| {
+                    let startup = app.lifecycle().startup_task("management server");
+                    Box::new(actuator::serve(app, binding, management, startup))
+                });
+                None
+            }
+            management => management,
+        };
+
+        app.add_scheduler(move |app: Arc<App>| {
+            let startup = app.lifecycle().startup_task("axum web server");
+            Box::new(Self::schedule(
+                app,
+                server_conf,
+                embedded_management,
+                startup,
+            ))
+        });
     }
 }
 
 impl WebPlugin {
-    async fn schedule(app: Arc<App>, config: ServerConfig) -> Result<String> {
-        let mut router = app.get_expect_component::<Router>();
+    /// Build the router served by the web server: the registered routers with their layers,
+    /// the OpenAPI documents, the embedded management endpoints and the [`AppState`] extension.
+    ///
+    /// The app must be built with the [`WebPlugin`]. This allows to call the routes without binding a port,
+    /// e.g. in tests.
+    pub fn router(app: Arc<App>) -> Result<axum::Router> {
+        let config = app.get_config::<WebConfig>()?;
+        let MergedRouter(router) = app.try_get_component()?;
+        let management = config.management.filter(|m| m.enable && m.port.is_none());
+        Ok(Self::finish_router(app, router, &config.server, management))
+    }
 
+    fn finish_router(
+        app: Arc<App>,
+        mut router: Router,
+        config: &ServerConfig,
+        management: Option<ManagementConfig>,
+    ) -> axum::Router {
         // Apply custom router layers registered by plugins
-        // This is done in schedule() after all plugins have built,
+        // This is done after all plugins have built,
         // ensuring plugins that depend on other plugins can still register layers
-        if let Some(layers) = app.get_component_ref::<RouterLayers>() {
-            for layer_fn in layers.deref().iter() {
-                router = layer_fn(router);
-            }
+        for layer_fn in app.get_components::<RouterLayer>() {
+            router = layer_fn(router);
         }
 
-        // 2. bind tcp listener
-        let addr = SocketAddr::from((config.binding, config.port));
-        let listener = tokio::net::TcpListener::bind(addr)
-            .await
-            .with_context(|| format!("bind tcp listener failed:{addr}"))?;
-        tracing::info!("bind tcp listener: {addr}");
-
-        // 3. openapi
+        // openapi
         #[cfg(feature = "openapi")]
         let router = {
             let openapi_conf = app.get_expect_component::<OpenApiConfig>();
             finish_openapi(&app, router, openapi_conf)
         };
 
-        // 4. axum server
-        let mut router = router.layer(Extension(AppState { app }));
+        // management endpoints
+        let router = match management {
+            Some(management) => router.merge(actuator::router(&management)),
+            None => router,
+        };
 
+        let mut router = router.layer(Extension(AppState { app }));
         if !config.global_prefix.is_empty() {
             router = axum::Router::new().nest(&config.global_prefix, router)
         };
+        router
+    }
 
+    async fn schedule(
+        app: Arc<App>,
+        config: ServerConfig,
+        management: Option<ManagementConfig>,
+        startup: StartupGuard,
+    ) -> Result<String> {
+        let MergedRouter(router) = app.get_expect_component();
+
+        // 2. bind tcp listener
+        let addr = SocketAddr::from((config.binding, config.port));
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("bind tcp listener failed:{addr}"))?;
+        tracing::info!("bind tcp listener: {addr}");
+        startup.ready();
+
+        // 3. router layers, openapi and management endpoints
+        let lifecycle = app.lifecycle().clone();
+        let router = Self::finish_router(app, router, &config, management);
+
+        // 4. axum server
+        let shutdown = async move { lifecycle.shutdown_signal("axum web server").await };
 
         tracing::info!("axum server started");
         if config.connect_info {
@@ -320,21 +371,23 @@ impl WebPlugin {
             let service = router.into_make_service_with_connect_info::<SocketAddr>();
             let server = axum::serve(listener, service);
             if config.graceful {
-                server
-                    .with_graceful_shutdown(signal::shutdown_signal("axum web server"))
-                    .await
+                server.with_graceful_shutdown(shutdown).await
             } else {
-                server.await
+                tokio::select! {
+                    result = server.into_future() => result,
+                    _ = shutdown => Ok(()),
+                }
             }
         } else {
             let service = router.into_make_service();
             let server = axum::serve(listener, service);
             if config.graceful {
-                server
-                    .with_graceful_shutdown(signal::shutdown_signal("axum web server"))
-                    .await
+                server.with_graceful_shutdown(shutdown).await
             } else {
-                server.await
+                tokio::select! {
+                    result = server.into_future() => result,
+                    _ = shutdown => Ok(()),
+                }
             }
         }
         .context("start axum server failed")?;
@@ -11,7 +11,7 @@ async fn test_router_registration() {
     app.add_router(router);
     
     // Verify router component is registered
-    assert!(app.has_component::<spring_web::Routers>());
+    assert_eq!(app.get_components::<Router>().len(), 1);
 }
 
 #[tokio::test]
@@ -24,9 +24,43 @@ async fn test_multiple_routers() {
     app.add_router(router1);
     app.add_router(router2);
     
-    let routers = app.get_component::<spring_web::Routers>();
-    assert!(routers.is_some());
-    assert_eq!(routers.unwrap().len(), 2);
+    let routers = app.get_components::<Router>();
+    assert_eq!(routers.len(), 2);
+}
+
+#[tokio::test]
+async fn test_routers_of_parallel_plugins() {
+    use spring::plugin::Plugin;
+    use spring_web::{LayerConfigurator, WebPlugin};
+
+    // plugins are registered by type, `ID` distinguishes them
+    struct RoutesPlugin<const ID: u8>(&'static str);
+
+    #[spring::async_trait]
+    impl<const ID: u8> Plugin for RoutesPlugin<ID> {
+        async fn build(&self, app: &mut AppBuilder) {
+            app.add_router(Router::new())
+                .add_router_layer(|router| router);
+        }
+
+        fn name(&self) -> &str {
+            self.0
+        }
+    }
+
+    // both plugins have no dependency, they are built in the same layer
+    let app = AppBuilder::from_config_str("")
+        .add_plugin(RoutesPlugin::<1>("users"))
+        .add_plugin(RoutesPlugin::<2>("orders"))
+        .add_plugin(WebPlugin)
+        .isolate_global(true)
+        .build()
+        .await
+        .expect("app build failed");
+    // the router merged by the web plugin isn't listed with the added routers
+    assert_eq!(app.get_components::<Router>().len(), 2);
+    assert_eq!(app.get_components::<spring_web::RouterLayer>().len(), 2);
+    assert!(WebPlugin::router(app).is_ok());
 }
 
 // Test basic axum functionality
@@ -146,6 +180,105 @@ async fn test_with_extension() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_named_component_extractor() {
+    use spring::plugin::MutableComponentRegistry;
+    use spring_web::axum::http::{Request, StatusCode};
+    use spring_web::axum::routing::get;
+    use spring_web::axum::Extension;
+    use spring_web::extractor::NamedComponent;
+    use spring_web::AppState;
+    use tower::ServiceExt;
+
+    spring_web::qualifier!(Replica = "replica");
+    spring_web::qualifier!(Reporting = "reporting");
+
+    async fn replica(NamedComponent(db, _): NamedComponent<String, Replica>) -> String {
+        db
+    }
+
+    async fn reporting(db: NamedComponent<String, Reporting>) -> String {
+        db.to_string()
+    }
+
+    let app = spring::App::new()
+        .add_named_component("primary", "primary-db".to_string())
+        .add_named_component("replica", "replica-db".to_string())
+        .build()
+        .await
+        .expect("app build failed");
+    let router = spring_web::axum::Router::new()
+        .route("/replica", get(replica))
+        .route("/reporting", get(reporting))
+        .layer(Extension(AppState { app }));
+
+    let request = Request::builder()
+        .uri("/replica")
+        .body(String::new())
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = spring_web::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "replica-db");
+
+    let request = Request::builder()
+        .uri("/reporting")
+        .body(String::new())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn test_trait_object_component_extractor() {
+    use spring::plugin::MutableComponentRegistry;
+    use spring_web::axum::http::{Request, StatusCode};
+    use spring_web::axum::routing::get;
+    use spring_web::axum::Extension;
+    use spring_web::extractor::Component;
+    use spring_web::AppState;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    struct MockGreeter;
+
+    impl Greeter for MockGreeter {
+        fn greet(&self) -> String {
+            "hello from mock".to_string()
+        }
+    }
+
+    async fn hello(Component(greeter): Component<Arc<dyn Greeter>>) -> String {
+        greeter.greet()
+    }
+
+    let app = spring::App::new()
+        .add_component::<Arc<dyn Greeter>>(Arc::new(MockGreeter))
+        .build()
+        .await
+        .expect("app build failed");
+    let router = spring_web::axum::Router::new()
+        .route("/hello", get(hello))
+        .layer(Extension(AppState { app }));
+
+    let request = Request::builder()
+        .uri("/hello")
+        .body(String::new())
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = spring_web::axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "hello from mock");
+}
+
 #[tokio::test]
 async fn test_path_extractor() {
     use spring_web::axum::extract::Path;
@@ -229,7 +362,7 @@ fn test_web_configurator() {
     let router = Router::new();
     app.add_router(router);
     
-    assert!(app.has_component::<spring_web::Routers>());
+    assert!(!app.get_components::<Router>().is_empty());
 }
 
 #[cfg(test)]
@@ -318,3 +451,103 @@ mod test_problem_details_macro {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 }
+
+#[tokio::test]
+async fn test_request_scoped_component() {
+    use spring::plugin::scope::Scope;
+    use spring::plugin::MutableComponentRegistry;
+    use spring_web::axum::extract::Request as AxumRequest;
+    use spring_web::axum::http::{Request, StatusCode};
+    use spring_web::axum::middleware::{self, Next};
+    use spring_web::axum::response::Response;
+    use spring_web::axum::routing::get;
+    use spring_web::axum::Extension;
+    use spring_web::extractor::{Component, RequestScope};
+    use spring_web::AppState;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    #[derive(Clone)]
+    struct RequestId(usize);
+
+    #[derive(Clone)]
+    struct Tenant(String);
+
+    // depends on the components of request scope of the same request
+    #[derive(Clone)]
+    struct Audit(usize);
+
+    #[derive(Clone)]
+    struct Transaction {
+        request: usize,
+        tenant: String,
+    }
+
+    async fn tenant(mut request: AxumRequest, next: Next) -> Response {
+        let tenant = request.headers()["x-tenant"].to_str().unwrap().to_string();
+        RequestScope::of(request.extensions_mut()).insert(Tenant(tenant));
+        next.run(request).await
+    }
+
+    async fn handler(
+        Component(first): Component<RequestId>,
+        Component(second): Component<RequestId>,
+        Component(tenant): Component<Tenant>,
+        Component(audit): Component<Audit>,
+        Component(tx): Component<Transaction>,
+    ) -> String {
+        assert_eq!(first.0, second.0);
+        assert_eq!(audit.0, first.0);
+        assert_eq!(
+            (tx.request, tx.tenant.as_str()),
+            (first.0, tenant.0.as_str())
+        );
+        format!("{}:{}", tenant.0, first.0)
+    }
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let ids = counter.clone();
+    let app = spring::App::new()
+        .add_scoped_component(Scope::Request, move |_| {
+            Ok(RequestId(ids.fetch_add(1, Ordering::SeqCst)))
+        })
+        .add_scoped_component(Scope::Request, |registry| {
+            Ok(Audit(registry.try_get_component::<RequestId>()?.0))
+        })
+        .add_async_request_component(|registry| async move {
+            tokio::task::yield_now().await;
+            let request = registry.try_get_component::<RequestId>()?.0;
+            let tenant = registry.get_scoped_component::<Tenant>().await?.unwrap();
+            Ok(Transaction {
+                request,
+                tenant: tenant.0.clone(),
+            })
+        })
+        .isolate_global(true)
+        .build()
+        .await
+        .expect("app build failed");
+    assert!(app.get_component::<RequestId>().is_none());
+    assert!(app.get_component::<Transaction>().is_none());
+
+    let router = spring_web::axum::Router::new()
+        .route("/", get(handler))
+        .layer(middleware::from_fn(tenant))
+        .layer(Extension(AppState { app }));
+
+    for (tenant, expected) in [("acme", "acme:0"), ("globex", "globex:1")] {
+        let request = Request::builder()
+            .uri("/")
+            .header("x-tenant", tenant)
+            .body(spring_web::axum::body::Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = spring_web::axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, expected);
+    }
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
@@ -1,20 +1,35 @@
 use crate::banner;
-use crate::config::env::Env;
-use crate::config::toml::TomlConfigRegistry;
-use crate::config::ConfigRegistry;
-use crate::log::{BoxLayer, LogPlugin};
+use crate::config::cli::ConfigCommand;
+use crate::config::env::{Env, Profiles};
+use crate::config::secret::{default_secret_resolvers, SecretResolver};
+use crate::config::source::{
+    CommandLineSource, ConfigFileSource, EnvSource, PropertySource, TomlSource,
+};
+use crate::config::toml::{ConfigWatch, TomlConfigRegistry};
+use crate::config::{ConfigRegistry, Configurable};
+use crate::event::{AppReady, AppStarted, ApplicationEventPublisher, ShutdownStarted};
+use crate::health::{self, HealthReport, HealthStatus};
+use crate::lifecycle::{Lifecycle, LifecycleState};
+use crate::log::{BoxLayer, LogPlugin, LoggerHandle};
 use crate::plugin::component::ComponentRef;
+use crate::plugin::graph::{DependencyGraph, GraphFormat, NodeKind};
+use crate::plugin::scope::{ComponentFactory, RegistryRef, RequestRegistry, Scope};
+use crate::plugin::service::ServiceRegistrar;
 use crate::plugin::{service, ComponentRegistry, MutableComponentRegistry, Plugin};
+use crate::signal;
 use crate::{
-    error::Result,
+    error::{AppError, Result},
     plugin::{component::DynComponentRef, PluginRef},
 };
 use dashmap::DashMap;
 use std::sync::LazyLock;
 use std::any::{Any, TypeId};
-use std::str::FromStr;
+use std::cell::RefCell;
 use std::sync::RwLock;
-use std::{collections::HashSet, future::Future, path::Path, sync::Arc};
+use std::time::{Duration, Instant};
+use std::collections::{HashMap, HashSet};
+use std::{future::Future, sync::Arc};
+use tokio::task::JoinSet;
 use tracing_subscriber::Layer;
 
 /// Wrapper for dynamically registered plugins (from inventory)
@@ -41,18 +56,34 @@ impl Plugin for DynPluginWrapper {
     fn immediately(&self) -> bool {
         self.0.immediately()
     }
+
+    fn deferred(&self) -> bool {
+        self.0.deferred()
+    }
 }
 
 type Registry<T> = DashMap<TypeId, T>;
+type NamedRegistry<T> = DashMap<(TypeId, String), T>;
 type Scheduler<T> = dyn FnOnce(Arc<App>) -> Box<dyn Future<Output = Result<T>> + Send>;
+type InitHook = dyn FnOnce() -> Box<dyn Future<Output = Result<()>> + Send>;
 
 /// Running Applications
 #[derive(Default)]
 pub struct App {
     env: Env,
+    profiles: Profiles,
     /// Component
     components: Registry<DynComponentRef>,
-    config: TomlConfigRegistry,
+    /// Component registered with a name
+    named_components: NamedRegistry<DynComponentRef>,
+    /// Components appended to the list of their type
+    component_lists: Registry<Vec<DynComponentRef>>,
+    /// Factories of the components of prototype or request scope
+    factories: Registry<ComponentFactory>,
+    config: Arc<TomlConfigRegistry>,
+    lifecycle: Arc<Lifecycle>,
+    event_publisher: ApplicationEventPublisher,
+    dependency_graph: DependencyGraph,
 }
 
 /// AppBuilder: Application under construction
@@ -62,6 +93,7 @@ pub struct App {
 /// - Configuration management
 pub struct AppBuilder {
     pub(crate) env: Env,
+    pub(crate) profiles: Profiles,
     /// Tracing Layer
     pub(crate) layers: Vec<BoxLayer>,
     /// Plugin
@@ -70,10 +102,50 @@ pub struct AppBuilder {
     dynamic_plugins: Vec<&'static dyn Plugin>,
     /// Component
     components: Registry<DynComponentRef>,
+    /// Component registered with a name
+    named_components: NamedRegistry<DynComponentRef>,
+    /// Components appended to the list of their type
+    component_lists: Registry<Vec<DynComponentRef>>,
+    /// Factories of the components of prototype or request scope
+    factories: Registry<ComponentFactory>,
     /// Configuration read from `config_path`
-    config: TomlConfigRegistry,
+    config: Arc<TomlConfigRegistry>,
+    /// Main configuration source, the file or string set by `use_config_*`
+    config_source: Arc<dyn PropertySource>,
+    /// Property sources added by the application
+    property_sources: Vec<Arc<dyn PropertySource>>,
+    /// Secret resolvers added by the application
+    secret_resolvers: Vec<Arc<dyn SecretResolver>>,
+    /// Read the environment variables and the command line arguments
+    system_sources: bool,
+    /// Interval for checking configuration files for changes
+    config_reload_interval: Option<Duration>,
+    /// Validate the configuration before building the plugins
+    validate_config: bool,
+    /// Lifecycle shared with the built [`App`]
+    lifecycle: Arc<Lifecycle>,
+    /// Publisher shared with the built [`App`], also registered as a component
+    event_publisher: ApplicationEventPublisher,
+    /// Grace period for the schedulers to finish after shutdown is requested
+    shutdown_timeout: Duration,
+    /// Config command requested on the command line, see [`AppBuilder::cli`]
+    config_command: Option<ConfigCommand>,
+    /// Format of the dependency graph requested on the command line, see [`AppBuilder::cli`]
+    graph_format: Option<GraphFormat>,
+    /// Plugins built so far and their dependencies
+    plugin_graph: DependencyGraph,
+    /// Name of the plugin being built on this builder
+    building_plugin: Option<String>,
+    /// Type names of the components and the plugins that registered them
+    component_plugins: HashMap<&'static str, String>,
+    /// Components registered by [`AppBuilder::override_component`]
+    overridden_components: HashSet<TypeId>,
+    /// Set the built app as the global app of the current thread only
+    isolate_global: bool,
     /// task
     schedulers: Vec<Box<Scheduler<String>>>,
+    init_hooks: Vec<Box<InitHook>>,
+    startup_hooks: Vec<Box<Scheduler<String>>>,
     shutdown_hooks: Vec<Box<Scheduler<String>>>,
 }
 
@@ -90,26 +162,127 @@ impl App {
         self.env
     }
 
+    /// Currently active profiles
+    /// * [Profiles]
+    pub fn get_profiles(&self) -> &Profiles {
+        &self.profiles
+    }
+
     /// Returns an instance of the currently configured global [`App`].
     ///
     /// **NOTE**: This global App is initialized after the application is built,
     /// please use it when the app is running, don't use it during the build process,
     /// such as during the plug-in build process.
     pub fn global() -> Arc<App> {
+        if let Some(app) = THREAD_GLOBAL_APP.with(|app| app.borrow().clone()) {
+            return app;
+        }
         GLOBAL_APP
             .read()
             .expect("GLOBAL_APP RwLock poisoned")
             .clone()
     }
 
+    /// Subscribe to configuration changes of `T`'s prefix.
+    ///
+    /// Changes are only published when hot reload is enabled through
+    /// [`AppBuilder::hot_reload_config`] or when [`Self::reload_config`] is called.
+    pub fn watch_config<T>(&self) -> ConfigWatch<T>
+    where
+        T: serde::de::DeserializeOwned + Configurable,
+    {
+        self.config.watch::<T>()
+    }
+
+    /// Re-read the configuration files and notify the watchers if the configuration has changed.
+    pub fn reload_config(&self) -> Result<bool> {
+        self.config.reload()
+    }
+
+    /// Get the configuration registry, e.g. to inspect the effective [properties][TomlConfigRegistry::properties]
+    pub fn config_registry(&self) -> &TomlConfigRegistry {
+        &self.config
+    }
+
+    /// Type names of all registered components, sorted by name
+    pub fn component_names(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self
+            .components
+            .iter()
+            .map(|c| c.value().type_name())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Type names and names of all [named components](MutableComponentRegistry::add_named_component),
+    /// sorted by type name and name
+    pub fn named_component_names(&self) -> Vec<(&'static str, String)> {
+        let mut names: Vec<_> = self
+            .named_components
+            .iter()
+            .map(|c| (c.value().type_name(), c.key().1.clone()))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Factory of the component of type `T` if it has the [prototype](Scope::Prototype)
+    /// or [request](Scope::Request) scope
+    pub fn component_factory<T>(&self) -> Option<ComponentFactory>
+    where
+        T: Any + Send + Sync,
+    {
+        self.factories
+            .get(&TypeId::of::<T>())
+            .map(|f| f.value().clone())
+    }
+
+    /// Dependency graph of the plugins, components and services of the application
+    pub fn dependency_graph(&self) -> &DependencyGraph {
+        &self.dependency_graph
+    }
+
+    /// Lifecycle of the application, use it to observe the readiness and liveness states
+    /// or to request a graceful shutdown.
+    pub fn lifecycle(&self) -> &Arc<Lifecycle> {
+        &self.lifecycle
+    }
+
+    /// Publisher of the application events, see [`ApplicationEventPublisher`]
+    pub fn event_publisher(&self) -> &ApplicationEventPublisher {
+        &self.event_publisher
+    }
+
+    /// Handle to change the log levels at runtime.
+    ///
+    /// Returns `None` if a global tracing subscriber was installed before the [`LogPlugin`] was built.
+    pub fn logger(&self) -> Option<&'static LoggerHandle> {
+        crate::log::logger()
+    }
+
+    /// Run all registered [health indicators](crate::health::HealthIndicator) concurrently
+    pub async fn check_health(&self) -> HealthReport {
+        health::check_health(self).await
+    }
+
     fn set_global(app: Arc<App>) {
         let mut global_app = GLOBAL_APP.write().expect("GLOBAL_APP RwLock poisoned");
         *global_app = app;
     }
+
+    fn set_thread_global(app: Option<Arc<App>>) {
+        THREAD_GLOBAL_APP.with(|global_app| *global_app.borrow_mut() = app);
+    }
 }
 
 static GLOBAL_APP: LazyLock<RwLock<Arc<App>>> = LazyLock::new(|| RwLock::new(Arc::new(App::default())));
 
+thread_local! {
+    /// Global app of the current thread, see [`AppBuilder::isolate_global`]
+    static THREAD_GLOBAL_APP: RefCell<Option<Arc<App>>> = const { RefCell::new(None) };
+}
+
 unsafe impl Send for AppBuilder {}
 unsafe impl Sync for AppBuilder {}
 
@@ -121,6 +294,13 @@ impl AppBuilder {
         self.env
     }
 
+    /// Currently active profiles
+    /// * [Profiles]
+    #[inline]
+    pub fn get_profiles(&self) -> &Profiles {
+        &self.profiles
+    }
+
     /// add plugin
     pub fn add_plugin<T: Plugin>(&mut self, plugin: T) -> &mut Self {
         log::debug!("added plugin: {}", plugin.name());
@@ -154,8 +334,18 @@ impl AppBuilder {
             
             // Check if already added by name
             let plugin_name = plugin.name();
-            if self.dynamic_plugins.iter().any(|p| p.name() == plugin_name) {
-                panic!("Error adding plugin {plugin_name}: plugin was already added in application")
+            if let Some(index) = self.dynamic_plugins.iter().position(|p| p.name() == plugin_name) {
+                // a deferred plugin provides a default, which gives way to the other plugin
+                match (self.dynamic_plugins[index].deferred(), plugin.deferred()) {
+                    (false, true) => continue,
+                    (true, false) => {
+                        self.dynamic_plugins[index] = *plugin;
+                        continue;
+                    }
+                    _ => panic!(
+                        "Error adding plugin {plugin_name}: plugin was already added in application"
+                    ),
+                }
             }
             
             if plugin.immediately() {
@@ -166,6 +356,20 @@ impl AppBuilder {
         }
     }
 
+    /// Add the plugin only if the profile expression matches the active profiles,
+    /// e.g. `"staging,prod"` or `"!prod"`. See [`Profiles::accepts`].
+    pub fn add_plugin_if_profile<T: Plugin>(&mut self, profiles: &str, plugin: T) -> &mut Self {
+        if self.profiles.accepts(profiles) {
+            self.add_plugin(plugin)
+        } else {
+            log::debug!(
+                "plugin {} skipped, the profile {profiles} is not active",
+                plugin.name()
+            );
+            self
+        }
+    }
+
     /// Returns `true` if the [`Plugin`] has already been added.
     #[inline]
     pub fn is_plugin_added<T: Plugin>(&self) -> bool {
@@ -173,16 +377,19 @@ impl AppBuilder {
     }
 
     /// The path of the configuration file, default is `./config/app.toml`.
-    /// The application automatically reads the environment configuration file
-    /// in the same directory according to the `SPRING_ENV` environment variable,
+    /// The application automatically reads the profile configuration files
+    /// in the same directory according to the `SPRING_PROFILES_ACTIVE` or `SPRING_ENV` environment variable,
     /// such as `./config/app-dev.toml`.
-    /// The environment configuration file has a higher priority and will
+    /// The profile configuration files have a higher priority and will
     /// overwrite the configuration items of the main configuration file.
     ///
-    /// For specific supported environments, see the [`Env`] enum.
+    /// For details of the active profiles, see [`Profiles`].
     pub fn use_config_file(&mut self, config_path: &str) -> &mut Self {
-        self.config = TomlConfigRegistry::new(Path::new(config_path), self.env)
-            .expect("config file load failed");
+        self.config_source = Arc::new(ConfigFileSource::with_profiles(
+            config_path,
+            self.profiles.clone(),
+        ));
+        self.config = self.load_config().expect("config file load failed");
         self
     }
 
@@ -191,11 +398,135 @@ impl AppBuilder {
     ///
     /// **Note**: This configuration method only supports one configuration content and does not support multiple environments.
     pub fn use_config_str(&mut self, toml_content: &str) -> &mut Self {
-        self.config =
-            TomlConfigRegistry::from_str(toml_content).expect("config content parse failed");
+        let source =
+            TomlSource::new("toml string", toml_content).expect("config content parse failed");
+        self.config_source = Arc::new(source);
+        self.config = self.load_config().expect("config content parse failed");
+        self
+    }
+
+    /// Add a [`PropertySource`] to the configuration.
+    ///
+    /// The properties are loaded in the following order, later sources override earlier ones:
+    /// 1. the configuration file (or string)
+    /// 2. the sources added by this method, in the order they are added
+    /// 3. environment variables such as `SPRING_WEB_PORT=9000`, see [`EnvSource`]
+    /// 4. command line arguments such as `--web.port=9000`, see [`CommandLineSource`]
+    pub fn add_property_source<S: PropertySource>(&mut self, source: S) -> &mut Self {
+        let name = source.name();
+        self.property_sources.push(Arc::new(source));
+        self.config = self
+            .load_config()
+            .unwrap_or_else(|e| panic!("property source {name} load failed: {e:?}"));
+        self
+    }
+
+    /// Add a [`SecretResolver`] for secret references in the configuration.
+    ///
+    /// It is consulted before the default resolvers, which handle `FILE(...)` and,
+    /// with the `encrypt` feature, `ENC(...)` values.
+    pub fn add_secret_resolver<R: SecretResolver>(&mut self, resolver: R) -> &mut Self {
+        self.secret_resolvers.push(Arc::new(resolver));
+        self.config = self.load_config().expect("config secret resolve failed");
         self
     }
 
+    /// Get the configuration registry, e.g. to inspect the [property sources][TomlConfigRegistry::property_sources]
+    /// and where a [property comes from][TomlConfigRegistry::property_origin]
+    pub fn config_registry(&self) -> &TomlConfigRegistry {
+        &self.config
+    }
+
+    fn load_config(&self) -> Result<Arc<TomlConfigRegistry>> {
+        Self::config_registry_of(
+            self.config_source.clone(),
+            &self.property_sources,
+            &self.secret_resolvers,
+            self.system_sources,
+        )
+        .map(Arc::new)
+    }
+
+    fn config_registry_of(
+        config_source: Arc<dyn PropertySource>,
+        property_sources: &[Arc<dyn PropertySource>],
+        secret_resolvers: &[Arc<dyn SecretResolver>],
+        system_sources: bool,
+    ) -> Result<TomlConfigRegistry> {
+        let mut sources = vec![config_source];
+        sources.extend(property_sources.iter().cloned());
+        if system_sources {
+            sources.push(Arc::new(EnvSource::default()));
+            sources.push(Arc::new(CommandLineSource::from_args()));
+        }
+        let mut resolvers = secret_resolvers.to_vec();
+        resolvers.extend(default_secret_resolvers());
+        TomlConfigRegistry::with_secret_resolvers(sources, resolvers)
+    }
+
+    /// Watch the configuration files and reload them when they are modified.
+    ///
+    /// The files are checked every `interval` once the application is built.
+    /// Components can subscribe to the changes through [`App::watch_config`].
+    pub fn hot_reload_config(&mut self, interval: Duration) -> &mut Self {
+        self.config_reload_interval = Some(interval);
+        self
+    }
+
+    /// Enable or disable the configuration validation, enabled by default.
+    ///
+    /// Before the plugins are built, the configuration is validated against the schemas
+    /// registered by [`submit_config_schema!`](crate::submit_config_schema).
+    /// All problems are reported at once, with the file and line they come from.
+    /// Unknown keys are logged as warnings, other problems fail the startup.
+    pub fn validate_config(&mut self, enable: bool) -> &mut Self {
+        self.validate_config = enable;
+        self
+    }
+
+    /// Handle the built-in [config commands][ConfigCommand] of the command line.
+    ///
+    /// When the application is started with `--print-config`, `--print-schema`,
+    /// `--print-defaults` or `--check-config`, [`run`][Self::run] and [`build`][Self::build]
+    /// execute the command on the final configuration instead of starting the application.
+    /// `--check-config` fails with a non-zero exit code if the configuration has errors,
+    /// which makes it usable in CI pipelines and container entrypoints.
+    ///
+    /// When started with `--print-graph` (Graphviz) or `--print-graph=json`, the application is built
+    /// until the services are injected, then the [dependency graph](App::dependency_graph) is printed.
+    ///
+    /// The process is not exited: [`try_run`][Self::try_run] and [`build`][Self::build] return
+    /// [`AppError::CommandExecuted`] with the exit code, and `main` decides what to do with it.
+    ///
+    /// ```rust,ignore
+    /// #[tokio::main]
+    /// async fn main() -> ExitCode {
+    ///     match App::new().cli().add_plugin(WebPlugin).try_run().await {
+    ///         Ok(()) => ExitCode::SUCCESS,
+    ///         Err(AppError::CommandExecuted(code)) => ExitCode::from(code),
+    ///         Err(e) => {
+    ///             log::error!("{e:?}");
+    ///             ExitCode::FAILURE
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn cli(&mut self) -> &mut Self {
+        self.config_command = ConfigCommand::from_args();
+        self.graph_format = GraphFormat::from_args();
+        self
+    }
+
+    /// Subscribe to configuration changes of `T`'s prefix.
+    ///
+    /// See [`App::watch_config`]
+    pub fn watch_config<T>(&self) -> ConfigWatch<T>
+    where
+        T: serde::de::DeserializeOwned + Configurable,
+    {
+        self.config.watch::<T>()
+    }
+
     /// add [tracing_subscriber::layer]
     pub fn add_layer<L>(&mut self, layer: L) -> &mut Self
     where
@@ -214,7 +545,82 @@ impl AppBuilder {
         self
     }
 
-    /// Add a shutdown hook
+    /// Add an init hook.
+    ///
+    /// Init hooks run in the order they are added, once the plugins are built and the services are injected.
+    /// The app fails to build if one of them returns an error, the shutdown hooks are run before the error is returned.
+    /// The `init` methods of the services, see `#[service(init = "...")]`, are init hooks.
+    pub fn add_init_hook<T>(&mut self, hook: T) -> &mut Self
+    where
+        T: FnOnce() -> Box<dyn Future<Output = Result<()>> + Send> + 'static,
+    {
+        self.init_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Add a startup hook.
+    ///
+    /// Startup hooks run in the order they are added, once all schedulers are listening
+    /// and the application is [ready][LifecycleState::Ready].
+    /// A failing hook shuts the application down, the error is returned once the shutdown hooks have run.
+    pub fn add_startup_hook<T>(&mut self, hook: T) -> &mut Self
+    where
+        T: FnOnce(Arc<App>) -> Box<dyn Future<Output = Result<String>> + Send> + 'static,
+    {
+        self.startup_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Set the grace period of the schedulers after shutdown is requested, 30 seconds by default.
+    ///
+    /// Schedulers that are still running when the period expires are aborted.
+    pub fn shutdown_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Lifecycle of the application, see [`App::lifecycle`]
+    pub fn lifecycle(&self) -> &Arc<Lifecycle> {
+        &self.lifecycle
+    }
+
+    /// Publisher of the application events, see [`App::event_publisher`]
+    pub fn event_publisher(&self) -> &ApplicationEventPublisher {
+        &self.event_publisher
+    }
+
+    /// Register `component` in place of the component of the same type that is added later,
+    /// e.g. to replace the client registered by a plugin with a mock in tests.
+    ///
+    /// Unlike [`add_component`](MutableComponentRegistry::add_component) this replaces an existing component,
+    /// and the following `add_component` calls for the same type are ignored.
+    pub fn override_component<C>(&mut self, component: C) -> &mut Self
+    where
+        C: Clone + Any + Send + Sync,
+    {
+        let component_id = TypeId::of::<C>();
+        log::debug!("override component: {}", std::any::type_name::<C>());
+        self.overridden_components.insert(component_id);
+        self.components
+            .insert(component_id, DynComponentRef::new(component));
+        self
+    }
+
+    /// Set the built app as the [global app](App::global) of the current thread only,
+    /// so apps built by tests running in parallel don't replace each other.
+    ///
+    /// The tasks using [`App::global`] must run on the thread that built the app,
+    /// e.g. with the default current thread runtime of `#[tokio::test]`.
+    pub fn isolate_global(&mut self, isolate: bool) -> &mut Self {
+        self.isolate_global = isolate;
+        self
+    }
+
+    /// Add a shutdown hook.
+    ///
+    /// Shutdown hooks run in the reverse order they are added, once the schedulers are stopped.
+    /// A failing hook is logged and doesn't prevent the other hooks from running.
+    /// The `destroy` methods of the services, see `#[service(destroy = "...")]`, are shutdown hooks.
     pub fn add_shutdown_hook<T>(&mut self, hook: T) -> &mut Self
     where
         T: FnOnce(Arc<App>) -> Box<dyn Future<Output = Result<String>> + Send> + 'static,
@@ -230,7 +636,8 @@ impl AppBuilder {
     /// * [spring-job](https://docs.rs/spring-job)
     /// * [spring-stream](https://docs.rs/spring-stream)
     pub async fn run(&mut self) {
-        match self.inner_run().await {
+        match self.try_run().await {
+            Err(AppError::CommandExecuted(_)) => { /* the command output is the result */ }
             Err(e) => {
                 log::error!("{e:?}");
             }
@@ -238,35 +645,54 @@ impl AppBuilder {
         }
     }
 
-    async fn inner_run(&mut self) -> Result<()> {
+    /// Same as [`Self::run`], but the error that stopped the application is returned instead of logged.
+    pub async fn try_run(&mut self) -> Result<()> {
+        self.execute_config_command()?;
+
         // 1. print banner
         banner::print_banner(self);
 
         // 2. build plugin
-        self.build_plugins().await;
+        self.build_plugins().await?;
 
         // 3. service dependency inject
         service::auto_inject_service(self)?;
+        self.execute_graph_command()?;
 
-        // 4. schedule
+        // 4. init services
+        self.run_init_hooks().await?;
+
+        // 5. schedule
         self.schedule().await
     }
 
     /// Unlike the [`Self::run`] method, the `build` method is suitable for applications that do not contain scheduling logic.
     /// This method returns the built App, and developers can implement logic such as command lines and task scheduling by themselves.
     pub async fn build(&mut self) -> Result<Arc<App>> {
+        self.execute_config_command()?;
+
         // 1. build plugin
-        self.build_plugins().await;
+        self.build_plugins().await?;
 
         // 2. service dependency inject
         service::auto_inject_service(self)?;
+        self.execute_graph_command()?;
 
-        Ok(self.build_app())
+        // 3. init services
+        self.run_init_hooks().await?;
+
+        let app = self.build_app();
+        app.lifecycle.advance(LifecycleState::Ready);
+        Ok(app)
     }
 
-    async fn build_plugins(&mut self) {
+    async fn build_plugins(&mut self) -> Result<()> {
         LogPlugin.immediately_build(self);
 
+        if self.validate_config {
+            self.check_config()?;
+        }
+
         // Automatically collect plugins registered via #[component] macro
         self.add_auto_plugins();
 
@@ -286,82 +712,544 @@ impl AppBuilder {
         let mut registered: HashSet<String> = HashSet::new();
 
         while !to_register.is_empty() {
-            let mut progress = false;
-            let mut next_round = vec![];
-
-            for plugin in to_register {
-                let deps = plugin.dependencies();
-                if deps.iter().all(|dep| registered.contains(*dep)) {
-                    plugin.build(self).await;
-                    registered.insert(plugin.name().to_string());
-                    log::info!("{} plugin registered", plugin.name());
-                    progress = true;
-                } else {
-                    next_round.push(plugin);
-                }
-            }
+            let ready = |plugin: &PluginRef| {
+                plugin
+                    .dependencies()
+                    .iter()
+                    .all(|dep| registered.contains(*dep))
+            };
+            let (mut built, mut next_round): (Vec<_>, Vec<_>) = to_register
+                .into_iter()
+                .partition(|plugin| !plugin.deferred() && ready(plugin));
 
-            if !progress {
-                panic!("Cyclic dependency detected or missing dependencies for some plugins");
+            if !built.is_empty() {
+                // plugins whose dependencies are built don't depend on each other
+                self.build_layer(&built).await;
+            } else {
+                // deferred plugins are built one by one once no other plugin is ready
+                (built, next_round) = next_round.into_iter().partition(|plugin| ready(plugin));
+                if built.is_empty() {
+                    return Err(Self::unresolved_plugins(&next_round, &registered));
+                }
+                for plugin in &built {
+                    let elapsed = Self::build_plugin(plugin, self).await;
+                    self.plugin_built(plugin, elapsed);
+                }
             }
 
+            registered.extend(built.iter().map(|plugin| plugin.name().to_string()));
             to_register = next_round;
         }
         self.plugin_registry = registry;
+        crate::cache::add_default_manager(self);
+        Ok(())
+    }
+
+    /// Build the plugins of a layer concurrently.
+    ///
+    /// Each plugin is built on a [fork](Self::fork) of the builder,
+    /// the forks are merged back in the order of the plugins.
+    async fn build_layer(&mut self, layer: &[PluginRef]) {
+        if let [plugin] = layer {
+            let elapsed = Self::build_plugin(plugin, self).await;
+            self.plugin_built(plugin, elapsed);
+            return;
+        }
+        let mut forks: Vec<AppBuilder> = layer.iter().map(|_| self.fork()).collect();
+        let builds = layer
+            .iter()
+            .zip(forks.iter_mut())
+            .map(|(plugin, fork)| Self::build_plugin(plugin, fork));
+        let elapsed = futures_util::future::join_all(builds).await;
+        for ((plugin, fork), elapsed) in layer.iter().zip(forks).zip(elapsed) {
+            self.merge(fork);
+            self.plugin_built(plugin, elapsed);
+        }
+    }
+
+    /// Build `plugin` on `app`, the components it adds are recorded as registered by it
+    async fn build_plugin(plugin: &PluginRef, app: &mut AppBuilder) -> Duration {
+        let start = Instant::now();
+        app.building_plugin = Some(plugin.name().to_string());
+        plugin.build(app).await;
+        app.building_plugin = None;
+        start.elapsed()
+    }
+
+    fn plugin_built(&mut self, plugin: &PluginRef, elapsed: Duration) {
+        self.plugin_graph.add_node(NodeKind::Plugin, plugin.name());
+        for dep in plugin.dependencies() {
+            self.plugin_graph.add_edge(plugin.name(), dep);
+        }
+        log::info!("{} plugin registered in {elapsed:?}", plugin.name());
+    }
+
+    /// Builder sharing the configuration and the components registered so far,
+    /// a plugin built on it doesn't block the other plugins of its layer.
+    fn fork(&self) -> AppBuilder {
+        AppBuilder {
+            env: self.env,
+            profiles: self.profiles.clone(),
+            layers: Default::default(),
+            plugin_registry: Default::default(),
+            dynamic_plugins: Default::default(),
+            components: self.components.clone(),
+            named_components: self.named_components.clone(),
+            component_lists: self.component_lists.clone(),
+            factories: self.factories.clone(),
+            config: self.config.clone(),
+            config_source: self.config_source.clone(),
+            property_sources: self.property_sources.clone(),
+            secret_resolvers: self.secret_resolvers.clone(),
+            system_sources: self.system_sources,
+            config_reload_interval: self.config_reload_interval,
+            validate_config: self.validate_config,
+            lifecycle: self.lifecycle.clone(),
+            event_publisher: self.event_publisher.clone(),
+            shutdown_timeout: self.shutdown_timeout,
+            config_command: None,
+            graph_format: None,
+            plugin_graph: Default::default(),
+            building_plugin: None,
+            component_plugins: Default::default(),
+            overridden_components: self.overridden_components.clone(),
+            isolate_global: self.isolate_global,
+            schedulers: Default::default(),
+            init_hooks: Default::default(),
+            startup_hooks: Default::default(),
+            shutdown_hooks: Default::default(),
+        }
+    }
+
+    /// Merge the components, layers, tasks and hooks registered on a [fork](Self::fork)
+    fn merge(&mut self, fork: AppBuilder) {
+        for (id, component) in fork.components {
+            if let Some(existing) = self.components.get(&id) {
+                if existing.ptr_eq(&component) {
+                    continue;
+                }
+                if !fork.overridden_components.contains(&id) {
+                    panic!(
+                        "Error adding component {}: component was already added in application",
+                        component.type_name()
+                    )
+                }
+            }
+            self.components.insert(id, component);
+        }
+        for (key, component) in fork.named_components {
+            if let Some(existing) = self.named_components.get(&key) {
+                if existing.ptr_eq(&component) {
+                    continue;
+                }
+                panic!(
+                    "Error adding component {} named \"{}\": component was already added in application",
+                    component.type_name(),
+                    key.1
+                )
+            }
+            self.named_components.insert(key, component);
+        }
+        for (id, list) in fork.component_lists {
+            let mut merged = self.component_lists.entry(id).or_default();
+            let appended: Vec<_> = list
+                .into_iter()
+                .filter(|c| !merged.iter().any(|m| m.ptr_eq(c)))
+                .collect();
+            merged.extend(appended);
+        }
+        for (id, factory) in fork.factories {
+            if !self.factories.contains_key(&id) {
+                self.factories.insert(id, factory);
+            }
+        }
+        self.overridden_components.extend(fork.overridden_components);
+        self.component_plugins.extend(fork.component_plugins);
+        self.layers.extend(fork.layers);
+        self.schedulers.extend(fork.schedulers);
+        self.init_hooks.extend(fork.init_hooks);
+        self.startup_hooks.extend(fork.startup_hooks);
+        self.shutdown_hooks.extend(fork.shutdown_hooks);
+    }
+
+    /// Explain why none of the `pending` plugins can be built:
+    /// the plugins they depend on are missing, or they depend on each other
+    fn unresolved_plugins(pending: &[PluginRef], registered: &HashSet<String>) -> AppError {
+        let mut graph = DependencyGraph::default();
+        for plugin in pending {
+            graph.add_node(NodeKind::Plugin, plugin.name());
+        }
+        let mut missing = vec![];
+        for plugin in pending {
+            let mut absent = vec![];
+            for dep in plugin.dependencies() {
+                if graph.contains(dep) {
+                    graph.add_edge(plugin.name(), dep);
+                } else if !registered.contains(dep) {
+                    absent.push(dep);
+                }
+            }
+            if !absent.is_empty() {
+                let reason = format!("missing plugin {}", absent.join(", "));
+                missing.push((plugin.name().to_string(), reason));
+            }
+        }
+        if !missing.is_empty() {
+            return AppError::UnresolvedDependencies(missing);
+        }
+        match graph.find_cycle() {
+            Some(cycle) => AppError::DependencyCycle(cycle),
+            None => AppError::UnresolvedDependencies(
+                pending
+                    .iter()
+                    .map(|p| (p.name().to_string(), "not ready".to_string()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Dependency graph of the plugins built so far, the registered components and the installed services
+    fn dependency_graph(&self) -> DependencyGraph {
+        let mut graph = self.plugin_graph.clone();
+        let component_names: HashSet<&'static str> = self
+            .components
+            .iter()
+            .map(|c| c.value().type_name())
+            .chain(self.factories.iter().map(|f| f.value().type_name()))
+            .collect();
+        let services: Vec<_> = inventory::iter::<&dyn ServiceRegistrar>()
+            .filter(|s| component_names.contains(s.service_name()))
+            .collect();
+        let mut components: Vec<_> = component_names
+            .iter()
+            .filter(|name| !services.iter().any(|s| s.service_name() == **name))
+            .collect();
+        components.sort_unstable();
+        for name in components {
+            graph.add_node(NodeKind::Component, *name);
+        }
+        for service in &services {
+            graph.add_node(NodeKind::Service, service.service_name());
+        }
+        for service in services {
+            for dep in service.dependencies() {
+                graph.add_edge(service.service_name(), dep);
+            }
+        }
+        // a component depends on the plugin that registered it
+        let mut owned: Vec<_> = self
+            .component_plugins
+            .iter()
+            .filter(|(name, _)| component_names.contains(*name))
+            .collect();
+        owned.sort_unstable();
+        for (name, plugin) in owned {
+            graph.add_edge(*name, plugin.as_str());
+        }
+        graph
+    }
+
+    /// Register the factory of a component of prototype or request scope
+    fn add_component_factory(
+        &mut self,
+        component_id: TypeId,
+        factory: ComponentFactory,
+    ) -> &mut Self {
+        let component_name = factory.type_name();
+        log::debug!(
+            "added component: {component_name} with {:?} scope",
+            factory.scope()
+        );
+        if self.components.contains_key(&component_id) || self.factories.contains_key(&component_id)
+        {
+            panic!("Error adding component {component_name}: component was already added in application")
+        }
+        self.factories.insert(component_id, factory);
+        self.record_component_plugin(component_name);
+        self
+    }
+
+    fn record_component_plugin(&mut self, component_name: &'static str) {
+        if let Some(plugin) = &self.building_plugin {
+            self.component_plugins
+                .insert(component_name, plugin.clone());
+        }
+    }
+
+    fn execute_graph_command(&self) -> Result<()> {
+        match self.graph_format {
+            Some(format) => {
+                let mut out = std::io::stdout().lock();
+                let code = match format.write(&self.dependency_graph(), &mut out) {
+                    Ok(()) => 0,
+                    Err(e) => {
+                        eprintln!("failed to write the output: {e}");
+                        1
+                    }
+                };
+                Err(AppError::CommandExecuted(code))
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn run_init_hooks(&mut self) -> Result<()> {
+        for hook in std::mem::take(&mut self.init_hooks) {
+            if let Err(e) = Box::into_pin(hook()).await {
+                // destroy the services and release the resources of the plugins built so far
+                let app = self.create_app();
+                self.run_shutdown_hooks(app).await;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn run_shutdown_hooks(&mut self, app: Arc<App>) {
+        // FILO: The hooks added by the plugin built first should be executed later
+        while let Some(hook) = self.shutdown_hooks.pop() {
+            match Box::into_pin(hook(app.clone())).await {
+                Ok(result) => log::info!("shutdown result: {result}"),
+                Err(e) => log::error!("shutdown hook failed: {e:?}"),
+            }
+        }
+    }
+
+    fn execute_config_command(&self) -> Result<()> {
+        match self.config_command {
+            Some(command) => {
+                let code = command.execute(&self.config, &mut std::io::stdout().lock());
+                Err(AppError::CommandExecuted(code))
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn check_config(&self) -> Result<()> {
+        let report = self.config.validate();
+        if report.has_errors() {
+            return Err(AppError::ConfigValidationErr(report));
+        }
+        if !report.is_empty() {
+            log::warn!("{report}");
+        }
+        Ok(())
     }
 
     async fn schedule(&mut self) -> Result<()> {
         let app = self.build_app();
+        let lifecycle = app.lifecycle.clone();
+        let events = app.event_publisher.clone();
+        events.publish(AppStarted).await;
+
+        let signal = tokio::spawn({
+            let lifecycle = lifecycle.clone();
+            async move {
+                signal::shutdown_signal("application").await;
+                lifecycle.shutdown();
+            }
+        });
 
         let schedulers = std::mem::take(&mut self.schedulers);
-        let mut handles = vec![];
+        let mut tasks = JoinSet::new();
         for task in schedulers {
             let poll_future = task(app.clone());
             let poll_future = Box::into_pin(poll_future);
-            handles.push(tokio::spawn(poll_future));
+            tasks.spawn(poll_future);
         }
 
-        while let Some(handle) = handles.pop() {
-            match handle.await? {
-                Err(e) => log::error!("{e:?}"),
-                Ok(msg) => log::info!("scheduled result: {msg}"),
+        // The startup hooks run once all schedulers are listening,
+        // unless the shutdown is requested while the schedulers are starting
+        tokio::select! {
+            _ = lifecycle.wait_startup() => {}
+            _ = lifecycle.wait_shutdown() => {}
+        }
+        let mut startup_error = None;
+        if lifecycle.advance(LifecycleState::Ready) {
+            // the health check only logs, the slow indicators must not delay the readiness
+            tokio::spawn({
+                let app = app.clone();
+                async move {
+                    if app.check_health().await.status != HealthStatus::Up {
+                        log::warn!("application is started but some components are down");
+                    }
+                }
+            });
+            for hook in std::mem::take(&mut self.startup_hooks) {
+                match Box::into_pin(hook(app.clone())).await {
+                    Ok(result) => log::info!("startup result: {result}"),
+                    Err(e) => {
+                        // the schedulers are running, stop them before returning the error
+                        startup_error = Some(e);
+                        lifecycle.shutdown();
+                        break;
+                    }
+                }
+            }
+            if startup_error.is_none() {
+                events.publish(AppReady).await;
             }
         }
 
-        // FILO: The hooks added by the plugin built first should be executed later
-        while let Some(hook) = self.shutdown_hooks.pop() {
-            let result = Box::into_pin(hook(app.clone())).await?;
-            log::info!("shutdown result: {result}");
+        tokio::select! {
+            result = Self::join_schedulers(&mut tasks) => result?,
+            _ = lifecycle.wait_shutdown() => {}
+        }
+
+        lifecycle.shutdown();
+        events.publish(ShutdownStarted).await;
+        if !tasks.is_empty() {
+            let timeout = self.shutdown_timeout;
+            match tokio::time::timeout(timeout, Self::join_schedulers(&mut tasks)).await {
+                Ok(result) => result?,
+                Err(_) => {
+                    log::warn!(
+                        "{} scheduler(s) did not finish within {timeout:?}, forcing termination",
+                        tasks.len()
+                    );
+                    tasks.shutdown().await;
+                }
+            }
+        }
+        signal.abort();
+
+        self.run_shutdown_hooks(app).await;
+        lifecycle.advance(LifecycleState::Stopped);
+        startup_error.map_or(Ok(()), Err)
+    }
+
+    async fn join_schedulers(tasks: &mut JoinSet<Result<String>>) -> Result<()> {
+        while let Some(result) = tasks.join_next().await {
+            match result? {
+                Err(e) => log::error!("{e:?}"),
+                Ok(msg) => log::info!("scheduled result: {msg}"),
+            }
         }
         Ok(())
     }
 
     fn build_app(&mut self) -> Arc<App> {
+        let app = self.create_app();
+        self.event_publisher.bind(&app);
+        if self.isolate_global {
+            App::set_thread_global(Some(app.clone()));
+        } else {
+            App::set_thread_global(None);
+            App::set_global(app.clone());
+        }
+        if let Some(interval) = self.config_reload_interval {
+            Self::spawn_config_reloader(Arc::downgrade(&app), interval);
+        }
+        app
+    }
+
+    fn create_app(&mut self) -> Arc<App> {
+        let dependency_graph = self.dependency_graph();
         let components = std::mem::take(&mut self.components);
+        let named_components = std::mem::take(&mut self.named_components);
+        let component_lists = std::mem::take(&mut self.component_lists);
+        let factories = std::mem::take(&mut self.factories);
         let config = std::mem::take(&mut self.config);
-        let app = Arc::new(App {
+        Arc::new(App {
             env: self.env,
+            profiles: self.profiles.clone(),
             components,
+            named_components,
+            component_lists,
+            factories,
             config,
+            lifecycle: self.lifecycle.clone(),
+            event_publisher: self.event_publisher.clone(),
+            dependency_graph,
+        })
+    }
+
+    fn spawn_config_reloader(app: std::sync::Weak<App>, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let app = match app.upgrade() {
+                    Some(app) => app,
+                    None => break,
+                };
+                match app.config.reload_if_modified() {
+                    Ok(true) => log::info!("configuration reloaded"),
+                    Ok(false) => {}
+                    Err(e) => log::error!("configuration reload failed: {e:?}"),
+                }
+            }
         });
-        App::set_global(app.clone());
-        app
     }
 }
 
 impl Default for AppBuilder {
     fn default() -> Self {
-        let env = Env::init();
-        let config = TomlConfigRegistry::new(Path::new("./config/app.toml"), env)
+        let profiles = Profiles::init();
+        let config_source = Arc::new(ConfigFileSource::with_profiles(
+            "./config/app.toml",
+            profiles.clone(),
+        ));
+        Self::with_config_source(profiles, config_source, true)
+    }
+}
+
+impl AppBuilder {
+    /// Create a builder configured only by the toml string, e.g. for tests.
+    ///
+    /// Unlike [`App::new`], neither the configuration files nor the `.env` file are read,
+    /// and the environment variables and command line arguments don't override the configuration.
+    pub fn from_config_str(toml_content: &str) -> Self {
+        let source =
+            TomlSource::new("toml string", toml_content).expect("config content parse failed");
+        Self::with_config_source(Profiles::default(), Arc::new(source), false)
+    }
+
+    fn with_config_source(
+        profiles: Profiles,
+        config_source: Arc<dyn PropertySource>,
+        system_sources: bool,
+    ) -> Self {
+        let env = profiles.env();
+        let config = Self::config_registry_of(config_source.clone(), &[], &[], system_sources)
             .expect("toml config load failed");
+        let event_publisher = ApplicationEventPublisher::default();
+        let components = Registry::default();
+        components.insert(
+            TypeId::of::<ApplicationEventPublisher>(),
+            DynComponentRef::new(event_publisher.clone()),
+        );
         Self {
             env,
-            config,
+            profiles,
+            config: Arc::new(config),
+            config_source,
+            property_sources: Default::default(),
+            secret_resolvers: Default::default(),
+            system_sources,
+            config_reload_interval: None,
+            validate_config: true,
+            config_command: None,
+            graph_format: None,
+            plugin_graph: Default::default(),
+            building_plugin: None,
+            component_plugins: Default::default(),
+            overridden_components: Default::default(),
+            isolate_global: false,
+            lifecycle: Default::default(),
+            event_publisher,
+            shutdown_timeout: Duration::from_secs(30),
             layers: Default::default(),
             plugin_registry: Default::default(),
             dynamic_plugins: Default::default(),
-            components: Default::default(),
+            components,
+            named_components: Default::default(),
+            component_lists: Default::default(),
+            factories: Default::default(),
             schedulers: Default::default(),
+            init_hooks: Default::default(),
+            startup_hooks: Default::default(),
             shutdown_hooks: Default::default(),
         }
     }
@@ -386,18 +1274,57 @@ impl ConfigRegistry for AppBuilder {
 }
 
 macro_rules! impl_component_registry {
-    ($ty:ident) => {
+    ($ty:ident, $registry:ident) => {
+        impl $ty {
+            /// Create the component if it has the prototype scope
+            fn create_prototype<T>(&self) -> Option<Result<ComponentRef<T>>>
+            where
+                T: Any + Send + Sync,
+            {
+                let factory = self
+                    .factories
+                    .get(&TypeId::of::<T>())
+                    .map(|f| f.value().clone())
+                    .filter(|f| f.scope() == Scope::Prototype)?;
+                Some(factory.create(RegistryRef::$registry(self)))
+            }
+        }
+
         impl ComponentRegistry for $ty {
             fn get_component_ref<T>(&self) -> Option<ComponentRef<T>>
             where
                 T: Any + Send + Sync,
             {
                 let component_id = TypeId::of::<T>();
-                let pair = self.components.get(&component_id)?;
+                let Some(pair) = self.components.get(&component_id) else {
+                    return match self.create_prototype::<T>()? {
+                        Ok(component_ref) => Some(component_ref),
+                        Err(e) => {
+                            log::error!(
+                                "{} component creation failed: {e:?}",
+                                std::any::type_name::<T>()
+                            );
+                            None
+                        }
+                    };
+                };
                 let component_ref = pair.value().clone();
                 component_ref.downcast::<T>()
             }
 
+            fn try_get_component_ref<T>(&self) -> Result<ComponentRef<T>>
+            where
+                T: Clone + Send + Sync + 'static,
+            {
+                if !self.components.contains_key(&TypeId::of::<T>()) {
+                    if let Some(component_ref) = self.create_prototype::<T>() {
+                        return component_ref;
+                    }
+                }
+                self.get_component_ref()
+                    .ok_or_else(|| AppError::ComponentNotExist(std::any::type_name::<T>()))
+            }
+
             fn get_component<T>(&self) -> Option<T>
             where
                 T: Clone + Send + Sync + 'static,
@@ -406,19 +1333,64 @@ macro_rules! impl_component_registry {
                 component_ref.map(|arc| T::clone(&arc))
             }
 
+            fn try_get_component<T>(&self) -> Result<T>
+            where
+                T: Clone + Send + Sync + 'static,
+            {
+                self.try_get_component_ref().map(|arc| T::clone(&arc))
+            }
+
             fn has_component<T>(&self) -> bool
             where
                 T: Any + Send + Sync,
             {
                 let component_id = TypeId::of::<T>();
                 self.components.contains_key(&component_id)
+                    || self
+                        .factories
+                        .get(&component_id)
+                        .is_some_and(|f| f.scope() == Scope::Prototype)
+            }
+
+            fn get_components<T>(&self) -> Vec<T>
+            where
+                T: Clone + Send + Sync + 'static,
+            {
+                let mut components: Vec<T> = self.get_component().into_iter().collect();
+                if let Some(list) = self.component_lists.get(&TypeId::of::<T>()) {
+                    components.extend(
+                        list.value()
+                            .iter()
+                            .filter_map(|c| c.clone().downcast::<T>())
+                            .map(|c| T::clone(&c)),
+                    );
+                }
+                components
+            }
+
+            fn get_component_ref_named<T>(&self, name: &str) -> Option<ComponentRef<T>>
+            where
+                T: Any + Send + Sync,
+            {
+                let key = (TypeId::of::<T>(), name.to_string());
+                let pair = self.named_components.get(&key)?;
+                let component_ref = pair.value().clone();
+                component_ref.downcast::<T>()
+            }
+
+            fn has_component_named<T>(&self, name: &str) -> bool
+            where
+                T: Any + Send + Sync,
+            {
+                let key = (TypeId::of::<T>(), name.to_string());
+                self.named_components.contains_key(&key)
             }
         }
     };
 }
 
-impl_component_registry!(App);
-impl_component_registry!(AppBuilder);
+impl_component_registry!(App, App);
+impl_component_registry!(AppBuilder, Builder);
 
 impl MutableComponentRegistry for AppBuilder {
     /// Add component to the registry
@@ -428,21 +1400,102 @@ impl MutableComponentRegistry for AppBuilder {
     {
         let component_id = TypeId::of::<C>();
         let component_name = std::any::type_name::<C>();
+        if self.overridden_components.contains(&component_id) {
+            log::debug!("component {component_name} is overridden, the added component is ignored");
+            return self;
+        }
         log::debug!("added component: {component_name}");
         if self.components.contains_key(&component_id) {
             panic!("Error adding component {component_name}: component was already added in application")
         }
         self.components
             .insert(component_id, DynComponentRef::new(component));
+        self.record_component_plugin(component_name);
+        self
+    }
+
+    /// Add component to the registry under a name
+    fn add_named_component<C>(&mut self, name: &str, component: C) -> &mut Self
+    where
+        C: Clone + Any + Send + Sync,
+    {
+        let key = (TypeId::of::<C>(), name.to_string());
+        let component_name = std::any::type_name::<C>();
+        log::debug!("added component: {component_name} named \"{name}\"");
+        if self.named_components.contains_key(&key) {
+            panic!("Error adding component {component_name} named \"{name}\": component was already added in application")
+        }
+        self.named_components
+            .insert(key, DynComponentRef::new(component));
+        self
+    }
+
+    /// Add a component created by a factory according to its scope
+    fn add_scoped_component<C, F>(&mut self, scope: Scope, factory: F) -> &mut Self
+    where
+        C: Clone + Any + Send + Sync,
+        F: Fn(RegistryRef<'_>) -> Result<C> + Send + Sync + 'static,
+    {
+        let component_id = TypeId::of::<C>();
+        let component_name = std::any::type_name::<C>();
+        if scope == Scope::Singleton {
+            let component = factory(RegistryRef::Builder(self))
+                .unwrap_or_else(|e| panic!("{component_name} component creation failed: {e:?}"));
+            return self.add_component(component);
+        }
+        if self.overridden_components.contains(&component_id) {
+            log::debug!("component {component_name} is overridden, the added component is ignored");
+            return self;
+        }
+        self.add_component_factory(component_id, ComponentFactory::new(scope, factory))
+    }
+
+    /// Add a component of request scope created by an asynchronous factory
+    fn add_async_request_component<C, F, Fut>(&mut self, factory: F) -> &mut Self
+    where
+        C: Clone + Any + Send + Sync,
+        F: Fn(RequestRegistry) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<C>> + Send + 'static,
+    {
+        let component_id = TypeId::of::<C>();
+        if self.overridden_components.contains(&component_id) {
+            log::debug!(
+                "component {} is overridden, the added component is ignored",
+                std::any::type_name::<C>()
+            );
+            return self;
+        }
+        self.add_component_factory(component_id, ComponentFactory::new_async(factory))
+    }
+
+    /// Append component to the list of its type
+    fn append_component<C>(&mut self, component: C) -> &mut Self
+    where
+        C: Clone + Any + Send + Sync,
+    {
+        log::debug!("appended component: {}", std::any::type_name::<C>());
+        self.component_lists
+            .entry(TypeId::of::<C>())
+            .or_default()
+            .push(DynComponentRef::new(component));
         self
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::app::AppBuilder;
     use crate::plugin::{ComponentRegistry, MutableComponentRegistry};
     use crate::App;
 
+    /// Builder without the config files, env variables and command line arguments,
+    /// replacing the global app of the current thread only
+    fn test_app() -> AppBuilder {
+        let mut app = AppBuilder::from_config_str("");
+        app.isolate_global(true);
+        app
+    }
+
     #[tokio::test]
     async fn test_component_registry() {
         #[derive(Clone)]
@@ -486,4 +1539,439 @@ mod tests {
         let p = app.get_component::<Point<i32>>();
         assert!(p.is_none())
     }
+
+    #[tokio::test]
+    async fn test_named_component() {
+        use crate::error::AppError;
+
+        #[derive(Clone)]
+        struct Pool(&'static str);
+
+        let app = test_app()
+            .add_component(Pool("default"))
+            .add_named_component("primary", Pool("primary"))
+            .add_named_component("replica", Pool("replica"))
+            .build()
+            .await
+            .expect("app build failed");
+
+        assert_eq!(app.get_expect_component::<Pool>().0, "default");
+        let primary = app.get_component_named::<Pool>("primary").unwrap();
+        assert_eq!(primary.0, "primary");
+        let replica = app.try_get_component_ref_named::<Pool>("replica").unwrap();
+        assert_eq!(replica.0, "replica");
+        assert!(app.has_component_named::<Pool>("replica"));
+        assert!(!app.has_component_named::<Pool>("reporting"));
+        assert!(!app.has_component_named::<String>("primary"));
+        assert!(matches!(
+            app.try_get_component_named::<Pool>("reporting"),
+            Err(AppError::NamedComponentNotExist(_, name)) if name == "reporting"
+        ));
+        let names = app.named_component_names();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0].1, "primary");
+    }
+
+    #[tokio::test]
+    async fn test_trait_object_component() {
+        use std::sync::Arc;
+
+        trait Greeter: Send + Sync {
+            fn greet(&self) -> String;
+        }
+
+        struct English;
+        impl Greeter for English {
+            fn greet(&self) -> String {
+                "hello".into()
+            }
+        }
+
+        struct French;
+        impl Greeter for French {
+            fn greet(&self) -> String {
+                "bonjour".into()
+            }
+        }
+
+        let mut app = AppBuilder::default();
+        app.add_component::<Arc<dyn Greeter>>(Arc::new(English));
+        let greeter = app.get_expect_component::<Arc<dyn Greeter>>();
+        assert_eq!(greeter.greet(), "hello");
+
+        app.append_component::<Arc<dyn Greeter>>(Arc::new(French))
+            .append_component::<Arc<dyn Greeter>>(Arc::new(English));
+        let greetings: Vec<_> = app
+            .get_components::<Arc<dyn Greeter>>()
+            .iter()
+            .map(|g| g.greet())
+            .collect();
+        assert_eq!(greetings, ["hello", "bonjour", "hello"]);
+        assert_eq!(
+            app.get_expect_component::<Arc<dyn Greeter>>().greet(),
+            "hello"
+        );
+        assert!(app.get_components::<Arc<English>>().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle() {
+        use crate::lifecycle::LifecycleState;
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut builder = test_app();
+        let server_events = events.clone();
+        builder.add_scheduler(move |app: Arc<App>| {
+            let startup = app.lifecycle().startup_task("server");
+            Box::new(async move {
+                server_events.lock().unwrap().push("listening");
+                startup.ready();
+                app.lifecycle().shutdown_signal("server").await;
+                server_events.lock().unwrap().push("drained");
+                Ok("server stopped".to_string())
+            })
+        });
+        // never finishes, aborted when the grace period expires
+        builder.add_scheduler(|_| Box::new(std::future::pending()));
+        let startup_events = events.clone();
+        builder.add_startup_hook(move |app: Arc<App>| {
+            Box::new(async move {
+                assert!(app.lifecycle().is_ready());
+                startup_events.lock().unwrap().push("startup");
+                app.lifecycle().shutdown();
+                Ok("started".to_string())
+            })
+        });
+        let shutdown_events = events.clone();
+        builder.add_shutdown_hook(move |_| {
+            Box::new(async move {
+                shutdown_events.lock().unwrap().push("shutdown");
+                Ok("stopped".to_string())
+            })
+        });
+        builder.shutdown_timeout(Duration::from_millis(50));
+        let lifecycle = builder.lifecycle().clone();
+        builder.run().await;
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec!["listening", "startup", "drained", "shutdown"]
+        );
+        assert_eq!(lifecycle.state(), LifecycleState::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_failures() {
+        use crate::lifecycle::LifecycleState;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // a failing startup hook stops the schedulers and runs the shutdown hooks
+        let shutdown_hooks = Arc::new(AtomicUsize::new(0));
+        let mut builder = test_app();
+        builder.add_scheduler(|app: Arc<App>| {
+            Box::new(async move {
+                app.lifecycle().shutdown_signal("server").await;
+                Ok("server stopped".to_string())
+            })
+        });
+        builder.add_startup_hook(|_| Box::new(async { Err(anyhow::anyhow!("failed").into()) }));
+        let counter = shutdown_hooks.clone();
+        builder.add_shutdown_hook(move |_| {
+            Box::new(async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok("stopped".to_string())
+            })
+        });
+        assert!(builder.try_run().await.is_err());
+        assert_eq!(shutdown_hooks.load(Ordering::SeqCst), 1);
+        assert_eq!(builder.lifecycle().state(), LifecycleState::Stopped);
+
+        // the shutdown requested while a scheduler is starting doesn't wait for it
+        let mut builder = test_app();
+        builder.add_scheduler(|app: Arc<App>| {
+            let _startup = app.lifecycle().startup_task("server");
+            Box::new(async move {
+                app.lifecycle().shutdown();
+                std::future::pending().await
+            })
+        });
+        builder.shutdown_timeout(std::time::Duration::from_millis(50));
+        builder.try_run().await.expect("app run failed");
+        assert_eq!(builder.lifecycle().state(), LifecycleState::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_hook_failures() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // a failing init hook runs all the shutdown hooks, even if one of them fails
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let mut builder = test_app();
+        let counter = destroyed.clone();
+        builder.add_shutdown_hook(move |_| {
+            Box::new(async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok("pool closed".to_string())
+            })
+        });
+        builder.add_shutdown_hook(|_| Box::new(async { Err(anyhow::anyhow!("failed").into()) }));
+        builder.add_init_hook(|| Box::new(async { Err(anyhow::anyhow!("failed").into()) }));
+        let result = builder.build().await;
+        assert!(result.is_err());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_override_component() {
+        #[derive(Clone)]
+        struct Client(&'static str);
+
+        let app = test_app()
+            .override_component(Client("mock"))
+            .add_component(Client("real"))
+            .build()
+            .await
+            .expect("app build failed");
+
+        assert_eq!(app.get_expect_component::<Client>().0, "mock");
+        assert!(std::sync::Arc::ptr_eq(&app, &App::global()));
+
+        let global = std::thread::spawn(|| App::global().has_component::<Client>())
+            .join()
+            .unwrap();
+        assert!(!global);
+    }
+
+    #[test]
+    fn test_from_config_str() {
+        let builder = AppBuilder::from_config_str("[web]\nport = 8080");
+        assert_eq!(
+            builder.config_registry().property_sources(),
+            ["toml string"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_config_command() {
+        use crate::config::cli::ConfigCommand;
+        use crate::error::AppError;
+
+        let mut builder = test_app();
+        builder.config_command = Some(ConfigCommand::PrintDefaults);
+        let result = builder.build().await;
+        assert!(matches!(result, Err(AppError::CommandExecuted(0))));
+    }
+
+    #[tokio::test]
+    async fn test_plugin_dependency_errors() {
+        use crate::error::AppError;
+        use crate::plugin::graph::NodeKind;
+        use crate::plugin::Plugin;
+
+        // plugins are registered by type, `ID` distinguishes them
+        struct DependentPlugin<const ID: u8>(&'static str, Vec<&'static str>);
+
+        #[derive(Clone)]
+        struct Pool<const ID: u8>;
+
+        #[async_trait::async_trait]
+        impl<const ID: u8> Plugin for DependentPlugin<ID> {
+            async fn build(&self, app: &mut crate::app::AppBuilder) {
+                app.add_component(Pool::<ID>);
+            }
+
+            fn name(&self) -> &str {
+                self.0
+            }
+
+            fn dependencies(&self) -> Vec<&str> {
+                self.1.clone()
+            }
+        }
+
+        let app = test_app()
+            .add_plugin(DependentPlugin::<1>("WebPlugin", vec!["SqlxPlugin"]))
+            .add_plugin(DependentPlugin::<2>("SqlxPlugin", vec![]))
+            .build()
+            .await
+            .expect("app build failed");
+        let graph = app.dependency_graph();
+        assert!(graph
+            .nodes()
+            .iter()
+            .any(|n| n.kind == NodeKind::Plugin && n.name == "WebPlugin"));
+        assert_eq!(
+            graph.dependencies("WebPlugin").collect::<Vec<_>>(),
+            ["SqlxPlugin"]
+        );
+        assert_eq!(
+            graph
+                .dependencies(std::any::type_name::<Pool<2>>())
+                .collect::<Vec<_>>(),
+            ["SqlxPlugin"]
+        );
+
+        let err = test_app()
+            .add_plugin(DependentPlugin::<1>("WebPlugin", vec!["SqlxPlugin"]))
+            .add_plugin(DependentPlugin::<2>("SqlxPlugin", vec!["RedisPlugin"]))
+            .add_plugin(DependentPlugin::<3>("RedisPlugin", vec!["SqlxPlugin"]))
+            .build()
+            .await
+            .err()
+            .expect("cycle not detected");
+        assert!(matches!(err, AppError::DependencyCycle(path)
+            if path.len() == 3 && path[0] == path[2] && path.contains(&"RedisPlugin".to_string())));
+
+        let err = test_app()
+            .add_plugin(DependentPlugin::<1>("WebPlugin", vec!["SqlxPlugin"]))
+            .build()
+            .await
+            .err()
+            .expect("missing plugin not detected");
+        assert_eq!(
+            err.to_string(),
+            "unresolved dependencies: WebPlugin (missing plugin SqlxPlugin)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parallel_plugins() {
+        use crate::plugin::Plugin;
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio::sync::Barrier;
+
+        #[derive(Clone)]
+        struct Connection(&'static str);
+
+        // plugins are registered by type, `ID` distinguishes them,
+        // the barrier is only passed when the plugins are built concurrently
+        struct ConnectPlugin<const ID: u8>(&'static str, Arc<Barrier>);
+
+        #[async_trait::async_trait]
+        impl<const ID: u8> Plugin for ConnectPlugin<ID> {
+            async fn build(&self, app: &mut crate::app::AppBuilder) {
+                self.1.wait().await;
+                app.add_named_component(self.0, Connection(self.0))
+                    .append_component(Connection(self.0));
+            }
+
+            fn name(&self) -> &str {
+                self.0
+            }
+        }
+
+        struct ServerPlugin;
+
+        #[async_trait::async_trait]
+        impl Plugin for ServerPlugin {
+            async fn build(&self, app: &mut crate::app::AppBuilder) {
+                let connections = app.get_components::<Connection>();
+                app.add_component(connections.len());
+            }
+
+            fn dependencies(&self) -> Vec<&str> {
+                vec!["postgres", "redis", "kafka"]
+            }
+        }
+
+        let barrier = Arc::new(Barrier::new(3));
+        let mut app = test_app();
+        app.add_plugin(ServerPlugin)
+            .add_plugin(ConnectPlugin::<1>("postgres", barrier.clone()))
+            .add_plugin(ConnectPlugin::<2>("redis", barrier.clone()))
+            .add_plugin(ConnectPlugin::<3>("kafka", barrier));
+        let app = tokio::time::timeout(Duration::from_secs(10), app.build())
+            .await
+            .expect("plugins of the same layer not built concurrently")
+            .expect("app build failed");
+
+        assert_eq!(app.get_expect_component::<usize>(), 3);
+        for name in ["postgres", "redis", "kafka"] {
+            assert_eq!(app.get_component_named::<Connection>(name).unwrap().0, name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scoped_component() {
+        use crate::plugin::scope::{RegistryRef, RequestComponents, RequestRegistry, Scope};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        #[derive(Clone)]
+        struct Counter(Arc<AtomicUsize>);
+
+        #[derive(Clone)]
+        struct Instance(usize);
+
+        #[derive(Clone)]
+        struct Shared(usize);
+
+        #[derive(Clone)]
+        struct PerRequest;
+
+        #[derive(Clone)]
+        struct Transaction(usize);
+
+        #[derive(Clone)]
+        struct Audit(usize);
+
+        let app = test_app()
+            .add_component(Counter(Default::default()))
+            .add_scoped_component(Scope::Prototype, |registry| {
+                let Counter(counter) = registry.try_get_component()?;
+                Ok(Instance(counter.fetch_add(1, Ordering::SeqCst)))
+            })
+            .add_scoped_component(Scope::Singleton, |registry| {
+                Ok(Shared(registry.get_component::<Instance>().unwrap().0))
+            })
+            .add_scoped_component(Scope::Request, |_| Ok(PerRequest))
+            .add_async_request_component(|registry| async move {
+                registry.try_get_component::<PerRequest>()?;
+                let Counter(counter) = registry.try_get_component()?;
+                Ok(Transaction(counter.fetch_add(1, Ordering::SeqCst)))
+            })
+            .add_async_request_component(|registry| async move {
+                let tx = registry.get_scoped_component::<Transaction>().await?;
+                Ok(Audit(tx.unwrap().0))
+            })
+            .build()
+            .await
+            .expect("app build failed");
+
+        assert!(app.has_component::<Instance>());
+        assert_eq!(app.get_expect_component::<Instance>().0, 1);
+        assert_eq!(app.try_get_component::<Instance>().unwrap().0, 2);
+        assert_eq!(app.get_expect_component::<Shared>().0, 0);
+        assert_eq!(app.get_expect_component::<Shared>().0, 0);
+
+        assert!(!app.has_component::<PerRequest>());
+        assert!(app.get_component::<PerRequest>().is_none());
+        let factory = app.component_factory::<PerRequest>().unwrap();
+        assert_eq!(factory.scope(), Scope::Request);
+
+        let factory = app.component_factory::<Transaction>().unwrap();
+        assert!(factory.is_async());
+        assert!(factory
+            .create::<Transaction>(RegistryRef::App(&app))
+            .is_err());
+
+        let registry = RequestRegistry::new(app.clone(), RequestComponents::default());
+        let audit = registry.get_scoped_component::<Audit>().await.unwrap();
+        let tx = registry
+            .get_scoped_component::<Transaction>()
+            .await
+            .unwrap();
+        assert_eq!(audit.unwrap().0, tx.unwrap().0);
+        assert!(registry.has_component::<PerRequest>());
+        assert!(registry
+            .get_scoped_component::<Instance>()
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
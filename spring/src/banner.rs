@@ -26,6 +26,7 @@ pub(crate) fn print_banner(app: &AppBuilder) {
         Env::Prod => Color::Green.paint("Prod"),
     };
     println!("environment: {env}");
+    println!("   profiles: {}", Color::LightCyan.paint(app.profiles.to_string()));
     if cfg!(debug_assertions) {
         println!("compilation: {}", Color::LightRed.paint("Debug"));
     } else {
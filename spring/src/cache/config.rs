@@ -0,0 +1,122 @@
+use crate::config::Configurable;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+impl Configurable for CacheConfig {
+    fn config_prefix() -> &'static str {
+        "cache"
+    }
+}
+
+crate::config::submit! {
+    crate::config::ConfigSchema {
+        prefix: "cache",
+        schema: || crate::config::schema_for!(CacheConfig),
+    }
+}
+
+/// Configuration of the caches used by the `#[cache]` macro
+#[derive(Debug, Clone, JsonSchema, Deserialize)]
+pub struct CacheConfig {
+    /// Default backend of the caches.
+    /// The cache manager registered by a plugin decides the backend when it is not set:
+    /// `redis` with spring-redis, `memory` otherwise.
+    ///
+    /// * options: `memory` | `redis` | `two-level`
+    #[serde(rename = "type")]
+    pub cache_type: Option<CacheType>,
+
+    /// Default seconds before the cached values expire,
+    /// the values are kept until they are evicted if not set.
+    /// The `expire` attribute of the cache macros takes precedence.
+    pub ttl: Option<u64>,
+
+    /// Maximum number of entries of each in-memory cache, the least recently used entries are evicted first.
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+
+    /// Seconds an entry is kept in the local level of a `two-level` cache,
+    /// the other instances of the application see the changes after this delay at most.
+    #[serde(default = "default_local_ttl")]
+    pub local_ttl: u64,
+
+    /// Configuration of each cache, by cache name
+    #[serde(default)]
+    pub caches: HashMap<String, CacheRegionConfig>,
+}
+
+/// Configuration of a named cache, overriding the defaults of [`CacheConfig`]
+#[derive(Debug, Clone, Default, JsonSchema, Deserialize)]
+pub struct CacheRegionConfig {
+    /// Backend of the cache
+    #[serde(rename = "type")]
+    pub cache_type: Option<CacheType>,
+
+    /// Seconds before the cached values expire
+    pub ttl: Option<u64>,
+
+    /// Maximum number of entries of the cache if it is kept in memory
+    pub capacity: Option<usize>,
+}
+
+/// Backend of a cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, JsonSchema, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheType {
+    /// In-process LRU cache with expiration
+    Memory,
+    /// Redis, shared by all the instances of the application
+    Redis,
+    /// In-process cache in front of Redis
+    TwoLevel,
+}
+
+fn default_capacity() -> usize {
+    10_000
+}
+
+fn default_local_ttl() -> u64 {
+    60
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            cache_type: None,
+            ttl: None,
+            capacity: default_capacity(),
+            local_ttl: default_local_ttl(),
+            caches: Default::default(),
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Backend of the cache named `name`, `default` if neither the cache nor the default backend is configured
+    pub fn cache_type(&self, name: &str, default: CacheType) -> CacheType {
+        self.caches
+            .get(name)
+            .and_then(|c| c.cache_type)
+            .or(self.cache_type)
+            .unwrap_or(default)
+    }
+
+    /// Default expiration of the values of the cache named `name`
+    pub fn ttl(&self, name: &str) -> Option<Duration> {
+        self.caches
+            .get(name)
+            .and_then(|c| c.ttl)
+            .or(self.ttl)
+            .map(Duration::from_secs)
+    }
+
+    /// Maximum number of in-memory entries of the cache named `name`
+    pub fn capacity(&self, name: &str) -> usize {
+        self.caches
+            .get(name)
+            .and_then(|c| c.capacity)
+            .unwrap_or(self.capacity)
+    }
+}
@@ -0,0 +1,124 @@
+use super::Cache;
+use dashmap::DashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// Time a distributed lock is held at most, and waited for at most
+pub const LOCK_TTL: Duration = Duration::from_secs(10);
+
+/// Delay between two attempts to take a distributed lock
+const LOCK_RETRY: Duration = Duration::from_millis(50);
+
+/// How `#[cache(sync = ...)]` lets a single caller compute a missing value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    /// `sync = "local"`: one caller per key in this process
+    Local,
+    /// `sync = "distributed"`: one caller per key in this process,
+    /// and one process per key if the cache is shared, with a lock stored in the cache
+    Distributed,
+}
+
+type LocalLocks = DashMap<(String, String), Arc<Mutex<()>>>;
+
+fn local_locks() -> &'static LocalLocks {
+    static LOCKS: OnceLock<LocalLocks> = OnceLock::new();
+    LOCKS.get_or_init(Default::default)
+}
+
+/// Lock of a key of a cache, released when it is dropped
+pub struct CacheLock {
+    local_key: (String, String),
+    local: Option<OwnedMutexGuard<()>>,
+    /// cache, key and token of the distributed lock if it was taken
+    remote: Option<(Arc<dyn Cache>, String, String)>,
+}
+
+/// Wait until the caller is the only one computing the value of `key` in the cache named `name`.
+///
+/// The callers waiting for a distributed lock stop waiting as soon as the value is cached,
+/// or after [`LOCK_TTL`]: the value is then computed without the lock.
+pub async fn lock(cache: &Arc<dyn Cache>, name: &str, key: &str, mode: SyncMode) -> CacheLock {
+    let local_key = (name.to_string(), key.to_string());
+    let mutex = local_locks().entry(local_key.clone()).or_default().clone();
+    let local = Some(mutex.lock_owned().await);
+    let mut lock = CacheLock {
+        local_key,
+        local,
+        remote: None,
+    };
+    if mode == SyncMode::Local {
+        return lock;
+    }
+
+    let token = format!("{:016x}", rand::random::<u64>());
+    let deadline = Instant::now() + LOCK_TTL;
+    loop {
+        match cache.try_lock(key, &token, LOCK_TTL).await {
+            Ok(true) => {
+                lock.remote = Some((cache.clone(), key.to_string(), token));
+                return lock;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                log::error!("failed to lock cache {name} for key {key}: {e:?}");
+                return lock;
+            }
+        }
+        if matches!(cache.get(key).await, Ok(Some(_))) || Instant::now() >= deadline {
+            return lock;
+        }
+        tokio::time::sleep(LOCK_RETRY).await;
+    }
+}
+
+impl Drop for CacheLock {
+    fn drop(&mut self) {
+        if let Some((cache, key, token)) = self.remote.take() {
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    handle.spawn(async move {
+                        if let Err(e) = cache.unlock(&key, &token).await {
+                            log::error!("failed to unlock cache for key {key}: {e:?}");
+                        }
+                    });
+                }
+                Err(_) => {
+                    log::warn!("no tokio runtime to unlock cache for key {key}, the lock expires")
+                }
+            }
+        }
+        drop(self.local.take());
+        local_locks().remove_if(&self.local_key, |_, mutex| Arc::strong_count(mutex) == 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{local_locks, lock, SyncMode};
+    use crate::cache::{Cache, MemoryCache};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_local_lock() {
+        let cache: Arc<dyn Cache> = Arc::new(MemoryCache::new(10));
+        let running = Arc::new(AtomicUsize::new(0));
+        let tasks = (0..4).map(|_| {
+            let cache = cache.clone();
+            let running = running.clone();
+            tokio::spawn(async move {
+                let _lock = lock(&cache, "test_local_lock", "key", SyncMode::Distributed).await;
+                assert_eq!(running.fetch_add(1, Ordering::SeqCst), 0);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        });
+        for task in tasks.collect::<Vec<_>>() {
+            task.await.unwrap();
+        }
+        assert!(!local_locks().contains_key(&("test_local_lock".to_string(), "key".to_string())));
+    }
+}
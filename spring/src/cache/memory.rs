@@ -0,0 +1,190 @@
+use super::Cache;
+use crate::async_trait;
+use crate::error::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// In-process cache evicting the least recently used entries beyond its capacity.
+///
+/// Expired entries are removed when they are read or evicted.
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    ttl: Option<Duration>,
+    state: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<String, Entry>,
+    /// keys by last access, the least recently used first
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: Vec<u8>,
+    expires_at: Option<Instant>,
+    tick: u64,
+}
+
+impl LruState {
+    fn touch(&mut self, key: &str) -> u64 {
+        self.tick += 1;
+        self.recency.insert(self.tick, key.to_string());
+        self.tick
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.tick);
+        Some(entry)
+    }
+}
+
+impl MemoryCache {
+    /// Cache holding at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ttl: None,
+            state: Default::default(),
+        }
+    }
+
+    /// Expire the values stored without ttl after `ttl`
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Number of entries, including the expired entries not removed yet
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .expect("cache lock poisoned")
+            .entries
+            .len()
+    }
+
+    /// Whether the cache has no entry
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Value of `key` if it is present and not expired
+    pub fn get_value(&self, key: &str) -> Option<Vec<u8>> {
+        let mut state = self.state.lock().expect("cache lock poisoned");
+        let entry = state.remove(key)?;
+        if entry.expires_at.is_some_and(|at| at <= Instant::now()) {
+            return None;
+        }
+        let value = entry.value.clone();
+        let tick = state.touch(key);
+        state
+            .entries
+            .insert(key.to_string(), Entry { tick, ..entry });
+        Some(value)
+    }
+
+    /// Store `value` for `key`, it expires after `ttl`, or after the default ttl of the cache
+    pub fn put_value(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.state.lock().expect("cache lock poisoned");
+        state.remove(key);
+        let tick = state.touch(key);
+        let expires_at = ttl.or(self.ttl).map(|ttl| Instant::now() + ttl);
+        state.entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                expires_at,
+                tick,
+            },
+        );
+        while state.entries.len() > self.capacity {
+            match state.recency.pop_first() {
+                Some((_, lru)) => state.entries.remove(&lru),
+                None => break,
+            };
+        }
+    }
+
+    /// Remove `key`
+    pub fn evict_value(&self, key: &str) {
+        self.state.lock().expect("cache lock poisoned").remove(key);
+    }
+
+    /// Remove all the entries
+    pub fn clear_values(&self) {
+        *self.state.lock().expect("cache lock poisoned") = LruState::default();
+    }
+}
+
+#[async_trait]
+impl Cache for MemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.get_value(key))
+    }
+
+    async fn put(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        self.put_value(key, value, ttl);
+        Ok(())
+    }
+
+    async fn evict(&self, key: &str) -> Result<()> {
+        self.evict_value(key);
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.clear_values();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryCache;
+    use std::time::Duration;
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = MemoryCache::new(2);
+        cache.put_value("a", b"1".to_vec(), None);
+        cache.put_value("b", b"2".to_vec(), None);
+        assert_eq!(cache.get_value("a"), Some(b"1".to_vec()));
+
+        // "b" is the least recently used
+        cache.put_value("c", b"3".to_vec(), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get_value("b"), None);
+        assert_eq!(cache.get_value("a"), Some(b"1".to_vec()));
+        assert_eq!(cache.get_value("c"), Some(b"3".to_vec()));
+
+        cache.evict_value("a");
+        assert_eq!(cache.get_value("a"), None);
+        cache.clear_values();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_expiration() {
+        let cache = MemoryCache::new(10);
+        cache.put_value("short", b"1".to_vec(), Some(Duration::ZERO));
+        cache.put_value("long", b"2".to_vec(), Some(Duration::from_secs(60)));
+        assert_eq!(cache.get_value("short"), None);
+        assert_eq!(cache.get_value("long"), Some(b"2".to_vec()));
+        assert_eq!(cache.len(), 1);
+
+        let cache = MemoryCache::new(10).with_ttl(Some(Duration::ZERO));
+        cache.put_value("default", b"1".to_vec(), None);
+        cache.put_value("explicit", b"2".to_vec(), Some(Duration::from_secs(60)));
+        assert_eq!(cache.get_value("default"), None);
+        assert_eq!(cache.get_value("explicit"), Some(b"2".to_vec()));
+    }
+}
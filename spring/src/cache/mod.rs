@@ -0,0 +1,237 @@
+//! Caches of the `#[cache]` macro.
+//!
+//! The macro looks up the [`CacheManager`] registered as an `Arc<dyn CacheManager>` component,
+//! such as the manager of spring-redis. When no plugin registered one, the application registers
+//! an in-memory [`MemoryCacheManager`] once the plugins are built, so that cached functions
+//! can be tested without a Redis server.
+//!
+//! The backend and the expiration of each named cache are selected by the `[cache]` configuration:
+//!
+//! ```toml
+//! [cache]
+//! type = "redis"        # default backend: memory | redis | two-level
+//! ttl = 3600            # default seconds before the values expire
+//! capacity = 10000      # maximum number of entries of each in-memory cache
+//! local_ttl = 60        # seconds an entry is kept in the local level of a two-level cache
+//!
+//! [cache.caches.users]
+//! type = "two-level"
+//! ttl = 600
+//! ```
+//!
+//! The values are serialized in JSON, or with the [`Serializer`] selected by the `serializer` parameter
+//! of the macros: `msgpack` with the `cache-msgpack` feature, `bincode` with the `cache-bincode` feature.
+
+/// Configuration of the caches
+pub mod config;
+/// Single-flight locks of `#[cache(sync = ...)]`
+pub mod lock;
+/// In-process LRU cache
+pub mod memory;
+/// Formats of the cached values
+pub mod serializer;
+
+pub use config::{CacheConfig, CacheType};
+pub use lock::{lock, CacheLock, SyncMode};
+pub use memory::MemoryCache;
+pub use serializer::Serializer;
+
+use crate::app::{App, AppBuilder};
+use crate::async_trait;
+use crate::config::ConfigRegistry;
+use crate::error::Result;
+use crate::plugin::{ComponentRegistry, MutableComponentRegistry};
+use dashmap::DashMap;
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Name of the cache used when `#[cache]` has no `name`
+pub const DEFAULT_CACHE: &str = "default";
+
+/// Storage of the serialized values of a cache
+#[async_trait]
+pub trait Cache: Send + Sync {
+    /// Value of `key`, `None` if it is missing or expired
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Store `value` for `key`, it expires after `ttl`, or after the default ttl of the cache if `None`
+    async fn put(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()>;
+
+    /// Remove `key`
+    async fn evict(&self, key: &str) -> Result<()>;
+
+    /// Remove all the entries of the cache
+    async fn clear(&self) -> Result<()>;
+
+    /// Take the lock of `key` for `ttl` with `token` if it is free, shared by all the users of the cache.
+    /// The caches local to the process have nothing to share and always succeed.
+    async fn try_lock(&self, _key: &str, _token: &str, _ttl: Duration) -> Result<bool> {
+        Ok(true)
+    }
+
+    /// Release the lock of `key` if it is still held with `token`
+    async fn unlock(&self, _key: &str, _token: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Provides the caches by name
+pub trait CacheManager: Send + Sync {
+    /// The cache named `name`, created on first use
+    fn cache(&self, name: &str) -> Arc<dyn Cache>;
+}
+
+/// Cache manager keeping every cache in memory
+pub struct MemoryCacheManager {
+    config: CacheConfig,
+    caches: DashMap<String, Arc<MemoryCache>>,
+}
+
+impl MemoryCacheManager {
+    /// Manager creating the caches with the capacity and ttl configured for their name
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            caches: Default::default(),
+        }
+    }
+}
+
+impl CacheManager for MemoryCacheManager {
+    fn cache(&self, name: &str) -> Arc<dyn Cache> {
+        self.caches
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                let cache_type = self.config.cache_type(name, CacheType::Memory);
+                if cache_type != CacheType::Memory {
+                    log::warn!(
+                        "cache {name} is configured as {cache_type:?} but no plugin provides it, it is kept in memory"
+                    );
+                }
+                Arc::new(
+                    MemoryCache::new(self.config.capacity(name)).with_ttl(self.config.ttl(name)),
+                )
+            })
+            .clone()
+    }
+}
+
+/// The cache manager registered as an `Arc<dyn CacheManager>` component of `app`.
+///
+/// An application which isn't built has no manager, its cached functions aren't cached:
+/// each call gets an empty [`MemoryCacheManager`].
+pub fn cache_manager(app: &App) -> Arc<dyn CacheManager> {
+    app.get_component::<Arc<dyn CacheManager>>()
+        .unwrap_or_else(|| {
+            log::debug!("no cache manager registered, the values are not cached");
+            Arc::new(MemoryCacheManager::new(CacheConfig::default()))
+        })
+}
+
+/// Register a [`MemoryCacheManager`] configured by the `[cache]` section
+/// if no plugin registered an `Arc<dyn CacheManager>` component
+pub(crate) fn add_default_manager(app: &mut AppBuilder) {
+    if app.has_component::<Arc<dyn CacheManager>>() {
+        return;
+    }
+    let config = app.get_config::<CacheConfig>().unwrap_or_else(|e| {
+        log::warn!("cache config load failed, the defaults are used: {e}");
+        CacheConfig::default()
+    });
+    app.add_component::<Arc<dyn CacheManager>>(Arc::new(MemoryCacheManager::new(config)));
+}
+
+/// Serialize a cached value in JSON
+pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    Serializer::Json.encode(value)
+}
+
+/// Deserialize a cached value from JSON
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    Serializer::Json.decode(bytes)
+}
+
+/// `ttl` extended by a random delay up to `max`,
+/// so that the values cached at the same time don't expire at the same time
+pub fn jitter(ttl: Option<Duration>, max: Duration) -> Option<Duration> {
+    let max = max.as_millis() as u64;
+    ttl.map(|ttl| ttl + Duration::from_millis(rand::random_range(0..=max)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{jitter, CacheManager, MemoryCacheManager};
+    use crate::cache::{CacheConfig, CacheType};
+    use std::time::Duration;
+
+    #[test]
+    fn test_cache_config() {
+        let config: CacheConfig = toml::from_str(
+            r#"
+            type = "redis"
+            capacity = 100
+            ttl = 3600
+
+            [caches.users]
+            type = "two-level"
+            capacity = 10
+            ttl = 600
+
+            [caches.sessions]
+            type = "memory"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.cache_type("users", CacheType::Memory),
+            CacheType::TwoLevel
+        );
+        assert_eq!(
+            config.cache_type("sessions", CacheType::Redis),
+            CacheType::Memory
+        );
+        assert_eq!(
+            config.cache_type("orders", CacheType::Memory),
+            CacheType::Redis
+        );
+        assert_eq!(config.capacity("users"), 10);
+        assert_eq!(config.capacity("orders"), 100);
+        assert_eq!(config.ttl("users"), Some(Duration::from_secs(600)));
+        assert_eq!(config.ttl("orders"), Some(Duration::from_secs(3600)));
+        assert_eq!(config.local_ttl, 60);
+
+        let config: CacheConfig = toml::from_str("").unwrap();
+        assert_eq!(
+            config.cache_type("users", CacheType::Memory),
+            CacheType::Memory
+        );
+        assert_eq!(config.capacity("users"), 10_000);
+        assert_eq!(config.ttl("users"), None);
+    }
+
+    #[test]
+    fn test_jitter() {
+        let ttl = Duration::from_secs(60);
+        let max = Duration::from_secs(10);
+        for _ in 0..100 {
+            let jittered = jitter(Some(ttl), max).unwrap();
+            assert!(jittered >= ttl && jittered <= ttl + max);
+        }
+        assert_eq!(jitter(None, max), None);
+        assert_eq!(jitter(Some(ttl), Duration::ZERO), Some(ttl));
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_manager() {
+        let manager = MemoryCacheManager::new(CacheConfig::default());
+        let users = manager.cache("users");
+        users.put("1", b"alice".to_vec(), None).await.unwrap();
+
+        assert_eq!(
+            manager.cache("users").get("1").await.unwrap(),
+            Some(b"alice".to_vec())
+        );
+        assert_eq!(manager.cache("orders").get("1").await.unwrap(), None);
+    }
+}
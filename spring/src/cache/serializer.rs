@@ -0,0 +1,78 @@
+use crate::error::Result;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Format of the values stored by the cache macros, selected by their `serializer` parameter
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Serializer {
+    /// `serializer = "json"`: readable by the other applications sharing the cache
+    #[default]
+    Json,
+    /// `serializer = "msgpack"`: compact binary format, requires the `cache-msgpack` feature
+    #[cfg(feature = "cache-msgpack")]
+    MessagePack,
+    /// `serializer = "bincode"`: fastest binary format, requires the `cache-bincode` feature.
+    /// The values can't be read after a change of their type.
+    #[cfg(feature = "cache-bincode")]
+    Bincode,
+}
+
+impl Serializer {
+    /// Serialize a cached value
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>> {
+        let bytes = match self {
+            Self::Json => serde_json::to_vec(value).map_err(anyhow::Error::from)?,
+            #[cfg(feature = "cache-msgpack")]
+            Self::MessagePack => rmp_serde::to_vec(value).map_err(anyhow::Error::from)?,
+            #[cfg(feature = "cache-bincode")]
+            Self::Bincode => bincode::serialize(value).map_err(anyhow::Error::from)?,
+        };
+        Ok(bytes)
+    }
+
+    /// Deserialize a cached value
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T> {
+        let value = match self {
+            Self::Json => serde_json::from_slice(bytes).map_err(anyhow::Error::from)?,
+            #[cfg(feature = "cache-msgpack")]
+            Self::MessagePack => rmp_serde::from_slice(bytes).map_err(anyhow::Error::from)?,
+            #[cfg(feature = "cache-bincode")]
+            Self::Bincode => bincode::deserialize(bytes).map_err(anyhow::Error::from)?,
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Serializer;
+
+    fn assert_round_trip(serializer: Serializer) {
+        let value = (Some("alice".to_string()), vec![1u32, 2, 3], None::<bool>);
+        let bytes = serializer.encode(&value).unwrap();
+        assert_eq!(
+            serializer
+                .decode::<(Option<String>, Vec<u32>, Option<bool>)>(&bytes)
+                .unwrap(),
+            value
+        );
+        assert!(serializer.decode::<u32>(b"").is_err());
+    }
+
+    #[test]
+    fn test_json() {
+        assert_round_trip(Serializer::Json);
+        assert_eq!(Serializer::Json.encode(&None::<u32>).unwrap(), b"null");
+    }
+
+    #[cfg(feature = "cache-msgpack")]
+    #[test]
+    fn test_msgpack() {
+        assert_round_trip(Serializer::MessagePack);
+    }
+
+    #[cfg(feature = "cache-bincode")]
+    #[test]
+    fn test_bincode() {
+        assert_round_trip(Serializer::Bincode);
+    }
+}
@@ -0,0 +1,137 @@
+use super::toml::TomlConfigRegistry;
+use super::{default_values, merge_all_schemas};
+use std::io::Write;
+
+/// Built-in commands to inspect the configuration, see [`AppBuilder::cli`](crate::app::AppBuilder::cli).
+///
+/// | argument           | command                                                          |
+/// |--------------------|------------------------------------------------------------------|
+/// | `--print-config`   | print the effective configuration and where each value comes from |
+/// | `--print-schema`   | print the json schema of all registered configurations           |
+/// | `--print-defaults` | print the default values declared in the schemas                 |
+/// | `--check-config`   | validate the configuration, exit code `1` if it has errors       |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigCommand {
+    /// `--print-config`
+    PrintConfig,
+    /// `--print-schema`
+    PrintSchema,
+    /// `--print-defaults`
+    PrintDefaults,
+    /// `--check-config`
+    CheckConfig,
+}
+
+impl ConfigCommand {
+    /// Find the first config command in the arguments the current process was started with
+    pub fn from_args() -> Option<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Find the first config command in `args`, other arguments are ignored
+    pub fn parse<I, S>(args: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        args.into_iter().find_map(|arg| match arg.as_ref() {
+            "--print-config" => Some(Self::PrintConfig),
+            "--print-schema" => Some(Self::PrintSchema),
+            "--print-defaults" => Some(Self::PrintDefaults),
+            "--check-config" => Some(Self::CheckConfig),
+            _ => None,
+        })
+    }
+
+    /// Execute the command, returns the exit code of the process
+    pub fn execute<W: Write>(&self, registry: &TomlConfigRegistry, out: &mut W) -> u8 {
+        match self.write(registry, out) {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("failed to write the output: {e}");
+                1
+            }
+        }
+    }
+
+    fn write<W: Write>(&self, registry: &TomlConfigRegistry, out: &mut W) -> std::io::Result<u8> {
+        match self {
+            Self::PrintConfig => write!(out, "{}", registry.dump())?,
+            Self::PrintSchema => writeln!(
+                out,
+                "{}",
+                serde_json::to_string_pretty(&merge_all_schemas())?
+            )?,
+            Self::PrintDefaults => write!(out, "{}", default_values())?,
+            Self::CheckConfig => {
+                let report = registry.validate();
+                if report.is_empty() {
+                    writeln!(out, "configuration is valid")?;
+                } else {
+                    writeln!(out, "{report}")?;
+                }
+                if report.has_errors() {
+                    return Ok(1);
+                }
+            }
+        }
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigCommand;
+    use crate::config::toml::TomlConfigRegistry;
+    use crate::error::Result;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            ConfigCommand::parse(["--web.port=80", "--check-config"]),
+            Some(ConfigCommand::CheckConfig)
+        );
+        assert_eq!(
+            ConfigCommand::parse(["--print-config"]),
+            Some(ConfigCommand::PrintConfig)
+        );
+        assert_eq!(ConfigCommand::parse(["--web.port=80"]), None);
+    }
+
+    #[test]
+    fn test_print_defaults() -> Result<()> {
+        let mut out = vec![];
+        let registry = TomlConfigRegistry::from_str("")?;
+        assert_eq!(ConfigCommand::PrintDefaults.execute(&registry, &mut out), 0);
+        let defaults: toml::Table = toml::from_str(&String::from_utf8_lossy(&out))?;
+        assert_eq!(defaults["logger"]["enable"].as_bool(), Some(true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_config() -> Result<()> {
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        struct CheckedConfig {
+            port: u16,
+        }
+        inventory::submit! {
+            crate::config::ConfigSchema {
+                prefix: "checked",
+                schema: || schemars::schema_for!(CheckedConfig),
+            }
+        }
+
+        let mut out = vec![];
+        let registry = TomlConfigRegistry::from_str("[checked]\nport = 8080")?;
+        assert_eq!(ConfigCommand::CheckConfig.execute(&registry, &mut out), 0);
+        assert_eq!(String::from_utf8_lossy(&out), "configuration is valid\n");
+
+        let mut out = vec![];
+        let registry = TomlConfigRegistry::from_str("[checked]\nport = -1")?;
+        assert_eq!(ConfigCommand::CheckConfig.execute(&registry, &mut out), 1);
+        assert!(String::from_utf8_lossy(&out).contains("checked.port"));
+        Ok(())
+    }
+}
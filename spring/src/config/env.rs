@@ -3,6 +3,7 @@ use anyhow::Context;
 use std::{
     env,
     ffi::OsStr,
+    fmt,
     io::ErrorKind,
     path::{Path, PathBuf},
 };
@@ -22,13 +23,7 @@ pub enum Env {
 impl Env {
     /// Initializes environment variables from the `.env` file and reads `SPRING_ENV` to determine the active environment for the application.
     pub fn init() -> Self {
-        match dotenvy::dotenv() {
-            Ok(path) => {
-                log::debug!("Loaded the environment variable file under the path: \"{path:?}\"",)
-            }
-            Err(e) => log::debug!("Environment variable file not found: {e}"),
-        }
-
+        load_dotenv();
         Self::from_env()
     }
 
@@ -43,31 +38,160 @@ impl Env {
 
     /// Parse the string to get the corresponding environment
     pub fn from_string<S: Into<String>>(str: S) -> Self {
-        match str.into() {
-            s if s.eq_ignore_ascii_case("dev") => Self::Dev,
-            s if s.eq_ignore_ascii_case("test") => Self::Test,
-            s if s.eq_ignore_ascii_case("prod") => Self::Prod,
-            _ => Self::Dev,
+        Self::from_name(&str.into()).unwrap_or(Self::Dev)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            s if s.eq_ignore_ascii_case("dev") => Some(Self::Dev),
+            s if s.eq_ignore_ascii_case("test") => Some(Self::Test),
+            s if s.eq_ignore_ascii_case("prod") => Some(Self::Prod),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Dev => "dev",
+            Self::Test => "test",
+            Self::Prod => "prod",
         }
     }
+}
+
+/// The active profiles of the application.
+///
+/// Profiles are read from the comma separated `SPRING_PROFILES_ACTIVE` variable,
+/// or from `SPRING_ENV` if it is not set, and default to `dev`.
+/// Unlike [`Env`], any profile name is accepted, e.g. `SPRING_PROFILES_ACTIVE=staging,eu-west`.
+/// For each profile, the `app-{profile}.toml` file next to the main configuration file is loaded in order,
+/// so the configuration of a later profile overrides an earlier one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profiles(Vec<String>);
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Env::default().into()
+    }
+}
+
+impl From<Env> for Profiles {
+    fn from(env: Env) -> Self {
+        Self(vec![env.name().to_string()])
+    }
+}
 
-    pub(crate) fn get_config_path(&self, path: &Path) -> Result<PathBuf> {
-        let stem = path.file_stem().and_then(OsStr::to_str).unwrap_or("");
-        let ext = path.extension().and_then(OsStr::to_str).unwrap_or("");
-        let canonicalize = path
-            .canonicalize()
-            .with_context(|| format!("canonicalize {path:?} failed"))?;
-        let parent = canonicalize
-            .parent()
-            .ok_or_else(|| AppError::from_io(ErrorKind::NotFound, "config file path not found"))?;
-        Ok(match self {
-            Self::Dev => parent.join(format!("{stem}-dev.{ext}")),
-            Self::Test => parent.join(format!("{stem}-test.{ext}")),
-            Self::Prod => parent.join(format!("{stem}-prod.{ext}")),
-        })
+impl fmt::Display for Profiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.join(","))
     }
 }
 
+impl Profiles {
+    /// Initializes environment variables from the `.env` file and reads the active profiles.
+    pub fn init() -> Self {
+        load_dotenv();
+        Self::from_env()
+    }
+
+    /// Read `SPRING_PROFILES_ACTIVE` or `SPRING_ENV` to determine the active profiles.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        match var("SPRING_PROFILES_ACTIVE").or_else(|| var("SPRING_ENV")) {
+            Some(var) => Self::from_string(var),
+            None => Self::default(),
+        }
+    }
+
+    /// Parse a comma separated list of profile names.
+    ///
+    /// The names keep their case since they name the `app-{profile}.toml` files,
+    /// except the built-in `dev`, `test` and `prod` profiles which are always lowercase.
+    pub fn from_string<S: AsRef<str>>(str: S) -> Self {
+        let mut profiles: Vec<String> = Vec::new();
+        for name in str.as_ref().split(',') {
+            let name = name.trim();
+            let name = match Env::from_name(name) {
+                Some(env) => env.name().to_string(),
+                None => name.to_string(),
+            };
+            if !name.is_empty() && !profiles.contains(&name) {
+                profiles.push(name);
+            }
+        }
+        if profiles.is_empty() {
+            Self::default()
+        } else {
+            Self(profiles)
+        }
+    }
+
+    /// Returns `true` if the profile is active
+    pub fn is_active(&self, profile: &str) -> bool {
+        self.0.iter().any(|p| p.eq_ignore_ascii_case(profile))
+    }
+
+    /// Returns `true` if the profile expression matches the active profiles.
+    ///
+    /// The expression is a comma separated list of profiles, one of which must be active.
+    /// A profile prefixed with `!` matches when that profile is not active,
+    /// e.g. `"staging,prod"` or `"!prod"`.
+    pub fn accepts(&self, expression: &str) -> bool {
+        expression
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .any(|p| match p.strip_prefix('!') {
+                Some(p) => !self.is_active(p.trim()),
+                None => self.is_active(p),
+            })
+    }
+
+    /// Iterate the active profiles in order
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// The [`Env`] of the first active profile named `dev`, `test` or `prod`,
+    /// defaults to [`Env::Dev`] if none of them is active.
+    pub fn env(&self) -> Env {
+        self.0
+            .iter()
+            .find_map(|p| Env::from_name(p))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn get_config_paths(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        self.iter()
+            .map(|profile| profile_config_path(path, profile))
+            .collect()
+    }
+}
+
+fn load_dotenv() {
+    match dotenvy::dotenv() {
+        Ok(path) => {
+            log::debug!("Loaded the environment variable file under the path: \"{path:?}\"",)
+        }
+        Err(e) => log::debug!("Environment variable file not found: {e}"),
+    }
+}
+
+fn profile_config_path(path: &Path, profile: &str) -> Result<PathBuf> {
+    let stem = path.file_stem().and_then(OsStr::to_str).unwrap_or("");
+    let ext = path.extension().and_then(OsStr::to_str).unwrap_or("");
+    let canonicalize = path
+        .canonicalize()
+        .with_context(|| format!("canonicalize {path:?} failed"))?;
+    let parent = canonicalize
+        .parent()
+        .ok_or_else(|| AppError::from_io(ErrorKind::NotFound, "config file path not found"))?;
+    Ok(parent.join(format!("{stem}-{profile}.{ext}")))
+}
+
 pub(crate) fn interpolate(template: &str) -> String {
     let mut result = String::new();
     let mut i = 0;
@@ -117,9 +241,9 @@ pub(crate) fn interpolate(template: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::Env;
+    use super::{Env, Profiles};
     use crate::error::Result;
-    use std::{fs, path::PathBuf};
+    use std::{collections::HashMap, fs, path::PathBuf};
 
     #[test]
     fn test_get_config_path() -> Result<()> {
@@ -131,23 +255,32 @@ mod tests {
         let _ = touch(&foo);
 
         assert_eq!(
-            Env::from_string("dev").get_config_path(foo.as_path())?,
-            temp_dir.join("foo-dev.toml")
+            Profiles::from_string("dev").get_config_paths(foo.as_path())?,
+            vec![temp_dir.join("foo-dev.toml")]
         );
 
         assert_eq!(
-            Env::from_string("test").get_config_path(foo.as_path())?,
-            temp_dir.join("foo-test.toml")
+            Profiles::from_string("test").get_config_paths(foo.as_path())?,
+            vec![temp_dir.join("foo-test.toml")]
         );
 
         assert_eq!(
-            Env::from_string("prod").get_config_path(foo.as_path())?,
-            temp_dir.join("foo-prod.toml")
+            Profiles::from_string("prod").get_config_paths(foo.as_path())?,
+            vec![temp_dir.join("foo-prod.toml")]
         );
 
         assert_eq!(
-            Env::from_string("other").get_config_path(foo.as_path())?,
-            temp_dir.join("foo-dev.toml")
+            Profiles::from_string("other").get_config_paths(foo.as_path())?,
+            vec![temp_dir.join("foo-other.toml")]
+        );
+
+        assert_eq!(
+            Profiles::from_string("Staging, eu-west, PROD").get_config_paths(foo.as_path())?,
+            vec![
+                temp_dir.join("foo-Staging.toml"),
+                temp_dir.join("foo-eu-west.toml"),
+                temp_dir.join("foo-prod.toml")
+            ]
         );
 
         Ok(())
@@ -160,33 +293,57 @@ mod tests {
         let foo = temp_dir.join("foo.toml");
         let _ = touch(&foo);
 
-        std::env::set_var("SPRING_ENV", "dev");
+        let from_vars = |vars: &[(&str, &str)]| {
+            let vars: HashMap<String, String> = vars
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            Profiles::from_vars(|name| vars.get(name).cloned())
+        };
+
         assert_eq!(
-            Env::from_env().get_config_path(foo.as_path())?,
-            temp_dir.join("foo-dev.toml")
+            from_vars(&[("SPRING_ENV", "dev")]).get_config_paths(foo.as_path())?,
+            vec![temp_dir.join("foo-dev.toml")]
         );
-
-        std::env::set_var("SPRING_ENV", "TEST");
         assert_eq!(
-            Env::from_env().get_config_path(foo.as_path())?,
-            temp_dir.join("foo-test.toml")
+            from_vars(&[("SPRING_ENV", "TEST")]).get_config_paths(foo.as_path())?,
+            vec![temp_dir.join("foo-test.toml")]
         );
-
-        std::env::set_var("SPRING_ENV", "Prod");
         assert_eq!(
-            Env::from_env().get_config_path(foo.as_path())?,
-            temp_dir.join("foo-prod.toml")
+            from_vars(&[("SPRING_ENV", "Prod")]).get_config_paths(foo.as_path())?,
+            vec![temp_dir.join("foo-prod.toml")]
         );
 
-        std::env::set_var("SPRING_ENV", "Other");
+        let other = from_vars(&[("SPRING_ENV", "Other")]);
         assert_eq!(
-            Env::from_env().get_config_path(foo.as_path())?,
-            temp_dir.join("foo-dev.toml")
+            other.get_config_paths(foo.as_path())?,
+            vec![temp_dir.join("foo-Other.toml")]
         );
+        assert!(matches!(other.env(), Env::Dev));
+
+        let profiles = from_vars(&[
+            ("SPRING_ENV", "test"),
+            ("SPRING_PROFILES_ACTIVE", "staging,prod"),
+        ]);
+        assert_eq!(profiles.to_string(), "staging,prod");
+        assert!(matches!(profiles.env(), Env::Prod));
+
+        assert_eq!(from_vars(&[]), Profiles::default());
 
         Ok(())
     }
 
+    #[test]
+    fn test_profiles_accepts() {
+        let profiles = Profiles::from_string("staging,eu-west");
+        assert!(profiles.is_active("Staging"));
+        assert!(!profiles.is_active("prod"));
+        assert!(profiles.accepts("prod,staging"));
+        assert!(profiles.accepts("!prod"));
+        assert!(!profiles.accepts("!staging"));
+        assert!(!profiles.accepts("prod"));
+    }
+
     #[allow(dead_code)]
     fn touch(path: &PathBuf) -> Result<()> {
         let _ = fs::OpenOptions::new()
@@ -1,10 +1,18 @@
 //! This module implements configuration loading.
 //!
 #![doc = include_str!("../../Config.md")]
+/// Command line commands to inspect the configuration
+pub mod cli;
 /// Environment Configuration
 pub mod env;
+/// Secret values in the configuration
+pub mod secret;
+/// Sources of configuration properties
+pub mod source;
 /// Implement reading toml configuration
 pub mod toml;
+/// Validate configuration against the registered schemas
+pub mod validation;
 
 pub use inventory::submit;
 pub use schemars::schema_for;
@@ -98,6 +106,41 @@ pub fn merge_all_schemas() -> serde_json::Value {
     })
 }
 
+/// Collect the default values declared in the registered schemas, grouped by prefix.
+///
+/// Only the properties with a `default` in their schema are included,
+/// e.g. the fields annotated with `#[serde(default = "...")]`.
+pub fn default_values() -> ::toml::Table {
+    let mut defaults = ::toml::Table::new();
+    for (prefix, schema) in auto_config_schemas() {
+        let schema = schema.to_value();
+        if let Some(::toml::Value::Table(table)) = schema_defaults(&schema, &schema) {
+            defaults.insert(prefix, ::toml::Value::Table(table));
+        }
+    }
+    defaults
+}
+
+fn schema_defaults(root: &serde_json::Value, schema: &serde_json::Value) -> Option<::toml::Value> {
+    if let Some(default) = schema.get("default") {
+        return ::toml::Value::try_from(default).ok();
+    }
+    if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+        let target = reference.strip_prefix('#').and_then(|p| root.pointer(p))?;
+        return schema_defaults(root, target);
+    }
+    let properties = schema.get("properties")?.as_object()?;
+    let table: ::toml::Table = properties
+        .iter()
+        .filter_map(|(key, property)| Some((key.clone(), schema_defaults(root, property)?)))
+        .collect();
+    if table.is_empty() {
+        None
+    } else {
+        Some(::toml::Value::Table(table))
+    }
+}
+
 /// write merged json schema to file
 pub fn write_merged_schema_to_file(path: &str) -> std::io::Result<()> {
     let merged = merge_all_schemas();
@@ -0,0 +1,339 @@
+use crate::error::Result;
+use anyhow::Context;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Deref;
+use toml::{Table, Value};
+
+/// Resolves secret values referenced in the configuration.
+///
+/// After all [property sources](super::source::PropertySource) are merged,
+/// every string value of the configuration is passed to the resolvers,
+/// the first resolver that recognizes the value replaces it with the secret.
+///
+/// Two resolvers are installed by default:
+/// * [`FileSecretResolver`]: `FILE(/run/secrets/db_password)` reads a Docker/Kubernetes secret mount
+/// * [`EncryptedSecretResolver`] (requires the `encrypt` feature): `ENC(...)` decrypts an encrypted value
+///
+/// ```rust,ignore
+/// struct VaultResolver;
+///
+/// impl SecretResolver for VaultResolver {
+///     fn resolve(&self, value: &str) -> Option<Result<String>> {
+///         let path = value.strip_prefix("VAULT(")?.strip_suffix(')')?;
+///         Some(read_from_vault(path))
+///     }
+/// }
+/// ```
+pub trait SecretResolver: Send + Sync + 'static {
+    /// Resolve the secret referenced by `value`.
+    ///
+    /// Returns `None` if the value is not handled by this resolver.
+    fn resolve(&self, value: &str) -> Option<Result<String>>;
+}
+
+fn unwrap_syntax<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    value.trim().strip_prefix(prefix)?.strip_suffix(')')
+}
+
+/// Reads secrets from files: `password = "FILE(/run/secrets/db_password)"`.
+///
+/// The trailing newline of the file is removed.
+#[derive(Debug, Clone, Default)]
+pub struct FileSecretResolver;
+
+impl SecretResolver for FileSecretResolver {
+    fn resolve(&self, value: &str) -> Option<Result<String>> {
+        let path = unwrap_syntax(value, "FILE(")?.trim();
+        Some(
+            std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read secret file {path:?}"))
+                .map(|secret| secret.trim_end_matches(['\r', '\n']).to_string())
+                .map_err(Into::into),
+        )
+    }
+}
+
+/// Decrypts values encrypted with AES-256-GCM: `password = "ENC(...)"`.
+///
+/// The key is read from the `SPRING_CONFIG_KEY` environment variable,
+/// or from the file named by `SPRING_CONFIG_KEY_FILE`.
+/// Use [`EncryptedSecretResolver::encrypt`] to produce the encrypted values.
+#[cfg(feature = "encrypt")]
+#[derive(Clone, Default)]
+pub struct EncryptedSecretResolver {
+    key: Option<[u8; 32]>,
+}
+
+#[cfg(feature = "encrypt")]
+impl EncryptedSecretResolver {
+    /// Use the given key, any passphrase is accepted and hashed into an AES-256 key
+    pub fn new<K: AsRef<[u8]>>(key: K) -> Self {
+        use sha2::Digest;
+        Self {
+            key: Some(sha2::Sha256::digest(key.as_ref()).into()),
+        }
+    }
+
+    /// Read the key from `SPRING_CONFIG_KEY` or `SPRING_CONFIG_KEY_FILE`.
+    ///
+    /// If no key is configured, resolving an `ENC(...)` value fails.
+    pub fn from_env() -> Self {
+        if let Ok(key) = std::env::var("SPRING_CONFIG_KEY") {
+            return Self::new(key);
+        }
+        match std::env::var("SPRING_CONFIG_KEY_FILE") {
+            Ok(path) => match std::fs::read_to_string(&path) {
+                Ok(key) => Self::new(key.trim_end_matches(['\r', '\n'])),
+                Err(e) => {
+                    log::warn!("Failed to read the config key file {path:?}: {e}");
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Encrypt `plaintext`, returns the `ENC(...)` value to put into the configuration file
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+        use base64::Engine;
+
+        let cipher = aes_gcm::Aes256Gcm::new(&self.key()?.into());
+        let nonce = aes_gcm::Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|e| anyhow::anyhow!("encrypt config value failed: {e}"))?;
+        let mut data = nonce.to_vec();
+        data.extend(ciphertext);
+        Ok(format!(
+            "ENC({})",
+            base64::engine::general_purpose::STANDARD.encode(data)
+        ))
+    }
+
+    fn decrypt(&self, encoded: &str) -> Result<String> {
+        use aes_gcm::aead::{Aead, KeyInit};
+        use base64::Engine;
+
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .context("encrypted config value is not valid base64")?;
+        if data.len() < 12 {
+            return Err(anyhow::anyhow!("encrypted config value is too short").into());
+        }
+        let (nonce, ciphertext) = data.split_at(12);
+        let cipher = aes_gcm::Aes256Gcm::new(&self.key()?.into());
+        let plaintext = cipher
+            .decrypt(nonce.into(), ciphertext)
+            .map_err(|_| anyhow::anyhow!("decrypt config value failed, check the config key"))?;
+        Ok(String::from_utf8(plaintext).context("decrypted config value is not utf-8")?)
+    }
+
+    fn key(&self) -> Result<[u8; 32]> {
+        Ok(self.key.ok_or_else(|| {
+            anyhow::anyhow!("SPRING_CONFIG_KEY or SPRING_CONFIG_KEY_FILE is required for ENC(...) values")
+        })?)
+    }
+}
+
+#[cfg(feature = "encrypt")]
+impl fmt::Debug for EncryptedSecretResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedSecretResolver")
+            .field("key", &self.key.map(|_| "******"))
+            .finish()
+    }
+}
+
+#[cfg(feature = "encrypt")]
+impl SecretResolver for EncryptedSecretResolver {
+    fn resolve(&self, value: &str) -> Option<Result<String>> {
+        let encoded = unwrap_syntax(value, "ENC(")?;
+        Some(self.decrypt(encoded))
+    }
+}
+
+/// The secret resolvers installed by default
+pub fn default_secret_resolvers() -> Vec<std::sync::Arc<dyn SecretResolver>> {
+    vec![
+        std::sync::Arc::new(FileSecretResolver),
+        #[cfg(feature = "encrypt")]
+        std::sync::Arc::new(EncryptedSecretResolver::from_env()),
+    ]
+}
+
+/// Replace the secret references in `table` with the resolved secrets,
+/// returns the dotted keys of the resolved values.
+pub(crate) fn resolve_secrets(
+    table: &mut Table,
+    resolvers: &[std::sync::Arc<dyn SecretResolver>],
+) -> Result<Vec<String>> {
+    let mut resolved = vec![];
+    if !resolvers.is_empty() {
+        for (key, value) in table.iter_mut() {
+            resolve_value(key, value, resolvers, &mut resolved)?;
+        }
+    }
+    Ok(resolved)
+}
+
+fn resolve_value(
+    key: &str,
+    value: &mut Value,
+    resolvers: &[std::sync::Arc<dyn SecretResolver>],
+    resolved: &mut Vec<String>,
+) -> Result<()> {
+    match value {
+        Value::String(s) => {
+            if let Some(secret) = resolvers.iter().find_map(|r| r.resolve(s)) {
+                *s = secret.with_context(|| format!("Failed to resolve the secret of {key}"))?;
+                resolved.push(key.to_string());
+            }
+        }
+        Value::Table(table) => {
+            for (k, v) in table.iter_mut() {
+                resolve_value(&format!("{key}.{k}"), v, resolvers, resolved)?;
+            }
+        }
+        Value::Array(array) => {
+            for (i, v) in array.iter_mut().enumerate() {
+                resolve_value(&format!("{key}[{i}]"), v, resolvers, resolved)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// A configuration value that is masked in `Debug` output.
+///
+/// Use it for passwords and other credentials in configuration structs,
+/// so that printing the configuration never leaks the secret:
+///
+/// ```rust,ignore
+/// #[derive(Debug, Configurable, Deserialize)]
+/// #[config_prefix = "my-plugin"]
+/// struct Config {
+///     user: String,
+///     password: Secret<String>,
+/// }
+///
+/// // prints `Config { user: "admin", password: ****** }`
+/// println!("{config:?}");
+/// connect(&config.user, config.password.expose());
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wrap a secret value
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Get the secret value
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Take the secret value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("******")
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self)
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Secret<T> {
+    fn schema_name() -> Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        T::json_schema(generator)
+    }
+
+    fn inline_schema() -> bool {
+        T::inline_schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_secrets, FileSecretResolver, Secret, SecretResolver};
+    use crate::error::Result;
+    use std::sync::Arc;
+    use toml::Table;
+
+    #[test]
+    fn test_file_secret() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let secret = temp_dir.path().join("db_password");
+        std::fs::write(&secret, "p@ssw0rd\n")?;
+
+        let mut table: Table = toml::from_str(&format!(
+            "[db]\nuser = \"admin\"\npassword = \"FILE({})\"",
+            secret.display()
+        ))?;
+        let resolvers: Vec<Arc<dyn SecretResolver>> = vec![Arc::new(FileSecretResolver)];
+        let resolved = resolve_secrets(&mut table, &resolvers)?;
+        assert_eq!(resolved, vec!["db.password"]);
+        assert_eq!(table["db"]["password"].as_str(), Some("p@ssw0rd"));
+        assert_eq!(table["db"]["user"].as_str(), Some("admin"));
+
+        let mut table: Table = toml::from_str("password = \"FILE(/not/exists)\"")?;
+        assert!(resolve_secrets(&mut table, &resolvers).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "encrypt")]
+    #[test]
+    fn test_encrypted_secret() -> Result<()> {
+        use super::EncryptedSecretResolver;
+
+        let resolver = EncryptedSecretResolver::new("my-key");
+        let encrypted = resolver.encrypt("p@ssw0rd")?;
+        assert!(encrypted.starts_with("ENC("));
+        assert_eq!(resolver.resolve(&encrypted).unwrap()?, "p@ssw0rd");
+        assert!(EncryptedSecretResolver::new("other-key")
+            .resolve(&encrypted)
+            .unwrap()
+            .is_err());
+        assert!(resolver.resolve("plain").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_secret_debug() {
+        let secret: Secret<String> = toml::Value::String("p@ssw0rd".into()).try_into().unwrap();
+        assert_eq!(format!("{secret:?}"), "******");
+        assert_eq!(secret.expose(), "p@ssw0rd");
+    }
+}
@@ -0,0 +1,636 @@
+use super::env::{interpolate, Env, Profiles};
+use crate::error::{AppError, Result};
+use anyhow::Context;
+use serde_toml_merge::merge_tables;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// A source of configuration properties, such as a configuration file or environment variables.
+///
+/// The sources of an application are merged in order of precedence:
+/// properties of a source added later override the properties loaded before it.
+///
+/// Implement this trait to load configuration from other places, e.g. a Consul or etcd key-value store:
+///
+/// ```rust,ignore
+/// use spring::config::source::PropertySource;
+///
+/// struct ConsulSource {
+///     client: ConsulClient,
+/// }
+///
+/// impl PropertySource for ConsulSource {
+///     fn name(&self) -> String {
+///         "consul".to_string()
+///     }
+///
+///     fn load(&self, _loaded: &Table) -> Result<Table> {
+///         let content = self.client.get_blocking("config/my-app")?;
+///         Ok(toml::from_str(&content)?)
+///     }
+/// }
+/// ```
+pub trait PropertySource: Send + Sync + 'static {
+    /// Name of the source, used to show where a property comes from
+    fn name(&self) -> String;
+
+    /// Load the properties of this source.
+    ///
+    /// `loaded` contains the properties merged from the sources with lower precedence.
+    fn load(&self, loaded: &Table) -> Result<Table>;
+
+    /// Files that this source reads, they are checked for changes when hot reload is enabled
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        vec![]
+    }
+
+    /// Location of the property `path` in this source, such as `config/app.toml:12`.
+    /// Used to point at the problems found by [configuration validation](super::validation).
+    fn locate(&self, _path: &[&str]) -> Option<String> {
+        None
+    }
+}
+
+/// File formats supported by [`FileSource`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// `.toml`
+    Toml,
+    /// `.json`
+    Json,
+    /// `.yaml` or `.yml`, requires the `yaml` feature
+    #[cfg(feature = "yaml")]
+    Yaml,
+}
+
+impl FileFormat {
+    /// Guess the format from the file extension, toml is used for unknown extensions
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::Json,
+            #[cfg(feature = "yaml")]
+            Some("yaml") | Some("yml") => Self::Yaml,
+            _ => Self::Toml,
+        }
+    }
+
+    fn parse(&self, content: &str) -> Result<Table> {
+        Ok(match self {
+            Self::Toml => toml::from_str(content)?,
+            Self::Json => serde_json::from_str(content).context("invalid json")?,
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_yaml::from_str(content).context("invalid yaml")?,
+        })
+    }
+}
+
+/// Properties read from a configuration file.
+///
+/// `${ENV_VAR:default}` placeholders in the file are interpolated before parsing.
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    path: PathBuf,
+    format: FileFormat,
+    required: bool,
+}
+
+impl FileSource {
+    /// A required file, the format is guessed from its extension
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        let format = FileFormat::from_path(&path);
+        Self {
+            path,
+            format,
+            required: true,
+        }
+    }
+
+    /// An optional file, it is ignored if it doesn't exist
+    pub fn optional<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            required: false,
+            ..Self::new(path)
+        }
+    }
+
+    /// Override the format guessed from the file extension
+    pub fn format(mut self, format: FileFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl PropertySource for FileSource {
+    fn name(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn load(&self, _loaded: &Table) -> Result<Table> {
+        if !self.required && !self.path.exists() {
+            return Ok(Table::new());
+        }
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read configuration file {:?}", self.path))?;
+        let content = interpolate(&content);
+        Ok(self
+            .format
+            .parse(&content)
+            .with_context(|| format!("Failed to parse the configuration file {:?}", self.path))?)
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+
+    fn locate(&self, path: &[&str]) -> Option<String> {
+        match self.format {
+            FileFormat::Toml => locate_in_toml(&self.path, path).map(|(location, _)| location),
+            _ => Some(self.path.display().to_string()),
+        }
+    }
+}
+
+/// The main configuration file merged with the configuration files of the [active profiles][Profiles].
+///
+/// This is the source used by default, e.g. `./config/app.toml` and `./config/app-dev.toml`.
+/// The profile files are merged in the order of the profiles, a missing profile file is skipped.
+/// A missing main configuration file is logged and treated as empty.
+#[derive(Debug, Clone)]
+pub struct ConfigFileSource {
+    path: PathBuf,
+    profiles: Profiles,
+}
+
+impl ConfigFileSource {
+    /// The `path` of the main configuration file and the active environment
+    pub fn new<P: Into<PathBuf>>(path: P, env: Env) -> Self {
+        Self::with_profiles(path, env.into())
+    }
+
+    /// The `path` of the main configuration file and the active profiles
+    pub fn with_profiles<P: Into<PathBuf>>(path: P, profiles: Profiles) -> Self {
+        Self {
+            path: path.into(),
+            profiles,
+        }
+    }
+}
+
+impl PropertySource for ConfigFileSource {
+    fn name(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn load(&self, _loaded: &Table) -> Result<Table> {
+        let config_path = self.path.as_path();
+        let main_toml_str = match fs::read_to_string(config_path) {
+            Err(e) => {
+                log::warn!("Failed to read configuration file {config_path:?}: {e}");
+                return Ok(Table::new());
+            }
+            Ok(content) => interpolate(&content),
+        };
+
+        let mut config_table = toml::from_str::<Table>(main_toml_str.as_str())
+            .with_context(|| format!("Failed to parse the toml file at path {config_path:?}"))?;
+
+        let profile_paths = match self.profiles.get_config_paths(config_path) {
+            Ok(paths) => paths,
+            Err(_) => {
+                log::debug!("{} config not found", self.profiles);
+                return Ok(config_table);
+            }
+        };
+        for (profile, profile_path) in self.profiles.iter().zip(profile_paths) {
+            let profile_path = profile_path.as_path();
+            if !profile_path.exists() {
+                log::debug!("{profile} config not found");
+                continue;
+            }
+            log::info!("The {profile} profile is active");
+
+            let profile_toml_str = fs::read_to_string(profile_path)
+                .with_context(|| format!("Failed to read configuration file {profile_path:?}"))?;
+            let profile_toml_str = interpolate(&profile_toml_str);
+            let profile_table =
+                toml::from_str::<Table>(profile_toml_str.as_str()).with_context(|| {
+                    format!("Failed to parse the toml file at path {profile_path:?}")
+                })?;
+            config_table = merge_tables(config_table, profile_table)
+                .map_err(|e| AppError::TomlMergeError(e.to_string()))
+                .with_context(|| {
+                    format!("Failed to merge files {config_path:?} and {profile_path:?}")
+                })?;
+        }
+
+        Ok(config_table)
+    }
+
+    fn watch_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.path.clone()];
+        if let Ok(profile_paths) = self.profiles.get_config_paths(&self.path) {
+            paths.extend(profile_paths);
+        }
+        paths
+    }
+
+    fn locate(&self, path: &[&str]) -> Option<String> {
+        // profile files override the main file, look for the property in reverse order
+        let files = self.watch_paths();
+        files
+            .iter()
+            .rev()
+            .filter_map(|file| locate_in_toml(file, path))
+            .find(|(_, exact)| *exact)
+            .or_else(|| locate_in_toml(&self.path, path))
+            .map(|(location, _)| location)
+    }
+}
+
+/// Properties parsed from a toml string
+#[derive(Debug, Clone)]
+pub struct TomlSource {
+    name: String,
+    table: Table,
+}
+
+impl TomlSource {
+    /// Parse a toml string
+    pub fn new<S: Into<String>>(name: S, toml: &str) -> Result<Self> {
+        Ok(Self {
+            name: name.into(),
+            table: toml::from_str(toml)?,
+        })
+    }
+}
+
+impl PropertySource for TomlSource {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn load(&self, _loaded: &Table) -> Result<Table> {
+        Ok(self.table.clone())
+    }
+}
+
+/// Properties overridden by environment variables.
+///
+/// A variable such as `SPRING_WEB_PORT=9000` overrides the `port` item of the `[web]` prefix.
+/// The part after the `SPRING_` prefix is matched against the known configuration prefixes
+/// (`-` in a prefix is written as `_`), and `__` separates nested tables:
+///
+/// | variable                               | property                              |
+/// |----------------------------------------|---------------------------------------|
+/// | `SPRING_WEB_PORT=9000`                 | `[web] port = 9000`                   |
+/// | `SPRING_WEB_GLOBAL_PREFIX=/api`        | `[web] global_prefix = "/api"`        |
+/// | `SPRING_SEA_ORM_URI=postgres://...`    | `[sea-orm] uri = "postgres://..."`    |
+/// | `SPRING_LOGGER_FILE__ENABLE=true`      | `[logger.file] enable = true`         |
+///
+/// `SPRING_ENV` and `SPRING_PROFILES_ACTIVE` are reserved and never mapped.
+#[derive(Debug, Clone)]
+pub struct EnvSource {
+    prefix: String,
+    vars: Option<Vec<(String, String)>>,
+}
+
+impl Default for EnvSource {
+    fn default() -> Self {
+        Self::new("SPRING_")
+    }
+}
+
+const RESERVED_ENV_VARS: [&str; 2] = ["SPRING_ENV", "SPRING_PROFILES_ACTIVE"];
+
+impl EnvSource {
+    /// Map environment variables starting with `prefix`
+    pub fn new<S: Into<String>>(prefix: S) -> Self {
+        Self {
+            prefix: prefix.into(),
+            vars: None,
+        }
+    }
+
+    /// Map the given variables starting with `prefix` instead of the environment of the process
+    pub fn with_vars<S, I, K, V>(prefix: S, vars: I) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            prefix: prefix.into(),
+            vars: Some(
+                vars.into_iter()
+                    .map(|(k, v)| (k.into(), v.into()))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn resolve_key(&self, var: &str, prefixes: &[String]) -> Option<Vec<String>> {
+        let rest = var.strip_prefix(&self.prefix)?;
+        let config_prefix = prefixes
+            .iter()
+            .filter(|p| {
+                let normalized = p.to_uppercase().replace('-', "_");
+                rest.len() > normalized.len()
+                    && rest.starts_with(&normalized)
+                    && rest[normalized.len()..].starts_with('_')
+            })
+            .max_by_key(|p| p.len())?;
+        let key = &rest[config_prefix.len() + 1..];
+        let mut path = vec![config_prefix.clone()];
+        path.extend(
+            key.split("__")
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_lowercase()),
+        );
+        Some(path).filter(|p| p.len() > 1)
+    }
+}
+
+impl PropertySource for EnvSource {
+    fn name(&self) -> String {
+        format!("environment variables {}*", self.prefix)
+    }
+
+    fn load(&self, loaded: &Table) -> Result<Table> {
+        let prefixes = known_prefixes(loaded);
+        let mut table = Table::new();
+        let vars = match &self.vars {
+            Some(vars) => vars.clone(),
+            None => std::env::vars().collect(),
+        };
+        for (var, value) in vars {
+            if RESERVED_ENV_VARS.contains(&var.as_str()) {
+                continue;
+            }
+            if let Some(path) = self.resolve_key(&var, &prefixes) {
+                insert_property(&mut table, loaded, &path, &value);
+            }
+        }
+        Ok(table)
+    }
+}
+
+/// Properties overridden by command line arguments such as `--web.port=9000`.
+///
+/// Only arguments of the form `--<prefix>.<key>=<value>` are used, other arguments are ignored,
+/// so the source can be combined with the application's own command line parsing.
+#[derive(Debug, Clone)]
+pub struct CommandLineSource {
+    args: Vec<String>,
+}
+
+impl CommandLineSource {
+    /// Use the arguments the current process was started with
+    pub fn from_args() -> Self {
+        Self::new(std::env::args_os().skip(1))
+    }
+
+    /// Use the given arguments
+    pub fn new<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        Self {
+            args: args
+                .into_iter()
+                .filter_map(|arg| arg.into().into_string().ok())
+                .collect(),
+        }
+    }
+}
+
+impl PropertySource for CommandLineSource {
+    fn name(&self) -> String {
+        "command line arguments".to_string()
+    }
+
+    fn load(&self, loaded: &Table) -> Result<Table> {
+        let mut table = Table::new();
+        for arg in &self.args {
+            let (key, value) = match arg.strip_prefix("--").and_then(|a| a.split_once('=')) {
+                Some(kv) => kv,
+                None => continue,
+            };
+            let path: Vec<String> = key.split('.').map(str::to_string).collect();
+            if path.len() < 2 || path.iter().any(String::is_empty) {
+                continue;
+            }
+            insert_property(&mut table, loaded, &path, value);
+        }
+        Ok(table)
+    }
+}
+
+/// Find the line of the property `path` in a toml file.
+///
+/// Returns the location and whether the whole path was found,
+/// if not the location points at the deepest table of the path that exists.
+fn locate_in_toml(file: &Path, path: &[&str]) -> Option<(String, bool)> {
+    use toml::de::{DeTable, DeValue};
+
+    let content = interpolate(&fs::read_to_string(file).ok()?);
+    let document = DeTable::parse(&content).ok()?;
+    let mut table = document.get_ref();
+    let mut span = None;
+    let mut exact = true;
+    for (i, key) in path.iter().enumerate() {
+        let Some((k, v)) = table.iter().find(|(k, _)| k.get_ref() == key) else {
+            exact = false;
+            break;
+        };
+        span = Some(k.span());
+        match v.get_ref() {
+            DeValue::Table(t) => table = t,
+            _ => {
+                exact = i == path.len() - 1;
+                break;
+            }
+        }
+    }
+    let line = content[..span?.start].matches('\n').count() + 1;
+    Some((format!("{}:{line}", file.display()), exact))
+}
+
+fn known_prefixes(loaded: &Table) -> Vec<String> {
+    let mut prefixes: Vec<String> = loaded.keys().cloned().collect();
+    for schema in inventory::iter::<super::ConfigSchema> {
+        if !prefixes.iter().any(|p| p == schema.prefix) {
+            prefixes.push(schema.prefix.to_string());
+        }
+    }
+    prefixes
+}
+
+/// Insert a raw string value into `table`.
+/// The value keeps the type of the property it overrides, new properties are parsed as toml values when possible.
+fn insert_property(table: &mut Table, loaded: &Table, path: &[String], raw: &str) {
+    let existing = lookup(loaded, path);
+    let value = match existing {
+        Some(Value::String(_)) => Value::String(raw.to_string()),
+        _ => parse_value(raw),
+    };
+    let (last, parents) = path.split_last().expect("property path is empty");
+    let mut current = table;
+    for key in parents {
+        let entry = current
+            .entry(key.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        if !entry.is_table() {
+            *entry = Value::Table(Table::new());
+        }
+        current = entry.as_table_mut().expect("entry is table");
+    }
+    current.insert(last.clone(), value);
+}
+
+fn lookup<'a>(table: &'a Table, path: &[String]) -> Option<&'a Value> {
+    let (first, rest) = path.split_first()?;
+    let value = table.get(first)?;
+    if rest.is_empty() {
+        Some(value)
+    } else {
+        lookup(value.as_table()?, rest)
+    }
+}
+
+fn parse_value(raw: &str) -> Value {
+    match toml::from_str::<Table>(&format!("value = {raw}")) {
+        Ok(mut table) => table
+            .remove("value")
+            .unwrap_or_else(|| Value::String(raw.to_string())),
+        Err(_) => Value::String(raw.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CommandLineSource, ConfigFileSource, EnvSource, FileSource, PropertySource, TomlSource,
+    };
+    use crate::config::env::Profiles;
+    use crate::config::toml::TomlConfigRegistry;
+    use crate::error::Result;
+    use std::fs;
+    use std::sync::Arc;
+    use toml::Table;
+
+    #[test]
+    fn test_env_source() -> Result<()> {
+        let loaded: Table = toml::from_str(
+            r#"
+            [web]
+            port = 8080
+            host = "0.0.0.0"
+            [sea-orm]
+            uri = "postgres://localhost"
+            [logger.file]
+            enable = false
+            "#,
+        )?;
+        let source = EnvSource::with_vars(
+            "SPRING_",
+            [
+                ("SPRING_WEB_PORT", "9000"),
+                ("SPRING_WEB_HOST", "127.0.0.1"),
+                ("SPRING_SEA_ORM_URI", "mysql://localhost"),
+                ("SPRING_LOGGER_FILE__ENABLE", "true"),
+                ("SPRING_UNKNOWN", "1"),
+                ("SPRING_PROFILES_ACTIVE", "prod"),
+                ("OTHER_WEB_PORT", "7000"),
+            ],
+        );
+
+        let table = source.load(&loaded)?;
+        assert_eq!(table["web"]["port"].as_integer(), Some(9000));
+        assert_eq!(table["web"]["host"].as_str(), Some("127.0.0.1"));
+        assert_eq!(table["sea-orm"]["uri"].as_str(), Some("mysql://localhost"));
+        assert_eq!(table["logger"]["file"]["enable"].as_bool(), Some(true));
+        assert!(!table.contains_key("unknown"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_command_line_source() -> Result<()> {
+        let loaded: Table = toml::from_str("[web]\nhost = \"0.0.0.0\"")?;
+        let source = CommandLineSource::new([
+            "--web.port=9000",
+            "--web.host=127.0.0.1",
+            "--verbose",
+            "positional",
+        ]);
+        let table = source.load(&loaded)?;
+        assert_eq!(table["web"]["port"].as_integer(), Some(9000));
+        assert_eq!(table["web"]["host"].as_str(), Some("127.0.0.1"));
+        assert_eq!(table.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_files() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let app = temp_dir.path().join("app.toml");
+        fs::write(&app, "[web]\nport = 80\nhost = \"0.0.0.0\"")?;
+        fs::write(temp_dir.path().join("app-staging.toml"), "[web]\nport = 8000")?;
+        fs::write(temp_dir.path().join("app-eu.toml"), "[web]\nport = 9000")?;
+
+        let source = ConfigFileSource::with_profiles(&app, Profiles::from_string("staging"));
+        let table = source.load(&Table::new())?;
+        assert_eq!(table["web"]["port"].as_integer(), Some(8000));
+        assert_eq!(table["web"]["host"].as_str(), Some("0.0.0.0"));
+
+        let source =
+            ConfigFileSource::with_profiles(&app, Profiles::from_string("staging,eu,missing"));
+        let table = source.load(&Table::new())?;
+        assert_eq!(table["web"]["port"].as_integer(), Some(9000));
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_source_precedence() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let json = temp_dir.path().join("app.json");
+        fs::write(&json, r#"{"web": {"port": 8000, "host": "localhost"}}"#)?;
+
+        let registry = TomlConfigRegistry::with_sources(vec![
+            Arc::new(TomlSource::new("defaults", "[web]\nport = 80\nmax_connections = 10")?),
+            Arc::new(FileSource::new(&json)),
+            Arc::new(FileSource::optional(temp_dir.path().join("missing.toml"))),
+            Arc::new(CommandLineSource::new(["--web.port=9000"])),
+        ])?;
+
+        let web = registry.get_by_prefix("web");
+        assert_eq!(web["port"].as_integer(), Some(9000));
+        assert_eq!(web["host"].as_str(), Some("localhost"));
+        assert_eq!(web["max_connections"].as_integer(), Some(10));
+
+        assert_eq!(registry.property_sources().len(), 4);
+        assert_eq!(
+            registry.property_origin("web.port").as_deref(),
+            Some("command line arguments")
+        );
+        assert_eq!(
+            registry.property_origin("web.host"),
+            Some(json.display().to_string())
+        );
+        assert_eq!(
+            registry.property_origin("web.max_connections").as_deref(),
+            Some("defaults")
+        );
+        assert_eq!(registry.property_origin("web.missing"), None);
+        Ok(())
+    }
+}
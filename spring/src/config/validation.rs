@@ -0,0 +1,475 @@
+use super::ConfigSchema;
+use serde_json::Value as Json;
+use std::fmt;
+use toml::{Table, Value};
+
+/// Severity of a [`ConfigProblem`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Suspicious configuration that is ignored, e.g. an unknown key
+    Warning,
+    /// Configuration that can not be deserialized
+    Error,
+}
+
+/// A problem found in the configuration
+#[derive(Debug, Clone)]
+pub struct ConfigProblem {
+    /// Severity of the problem
+    pub severity: Severity,
+    /// Dotted path of the property, such as `web.port`
+    pub key: String,
+    /// Description of the problem
+    pub message: String,
+    /// Where the property is defined, such as `config/app.toml:3`
+    pub location: Option<String>,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{severity}: {}: {}", self.key, self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, " ({location})")?;
+        }
+        Ok(())
+    }
+}
+
+/// All problems found by validating the configuration against the registered [`ConfigSchema`]s
+#[derive(Clone, Default)]
+pub struct ConfigReport {
+    problems: Vec<ConfigProblem>,
+}
+
+impl ConfigReport {
+    /// All problems found
+    pub fn problems(&self) -> &[ConfigProblem] {
+        &self.problems
+    }
+
+    /// Returns `true` if no problem was found
+    pub fn is_empty(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Returns `true` if any problem is an [error][Severity::Error]
+    pub fn has_errors(&self) -> bool {
+        self.problems.iter().any(|p| p.severity == Severity::Error)
+    }
+
+    pub(crate) fn locate_with<F>(mut self, locate: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        for problem in self.problems.iter_mut() {
+            problem.location = locate(&problem.key);
+        }
+        self
+    }
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors = self
+            .problems
+            .iter()
+            .filter(|p| p.severity == Severity::Error)
+            .count();
+        let warnings = self.problems.len() - errors;
+        write!(
+            f,
+            "configuration has {errors} error(s) and {warnings} warning(s)"
+        )?;
+        for problem in &self.problems {
+            write!(f, "\n  {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Validate the configuration against all registered [`ConfigSchema`]s.
+///
+/// Only the prefixes present in the configuration are validated,
+/// because plugins that are not configured are usually not used.
+pub(crate) fn validate(config: &Table) -> ConfigReport {
+    let mut report = ConfigReport::default();
+    for schema in inventory::iter::<ConfigSchema> {
+        if let Some(value) = config.get(schema.prefix) {
+            let root = (schema.schema)().to_value();
+            let mut validator = Validator {
+                root: &root,
+                problems: &mut report.problems,
+                check_unknown: true,
+            };
+            validator.validate(&root, value, schema.prefix);
+        }
+    }
+    report
+}
+
+struct Validator<'a> {
+    root: &'a Json,
+    problems: &'a mut Vec<ConfigProblem>,
+    check_unknown: bool,
+}
+
+impl<'a> Validator<'a> {
+    fn problem(&mut self, severity: Severity, key: &str, message: String) {
+        self.problems.push(ConfigProblem {
+            severity,
+            key: key.to_string(),
+            message,
+            location: None,
+        });
+    }
+
+    /// Resolve `$ref` to the schema definition
+    fn resolve<'s>(&self, schema: &'s Json) -> &'s Json
+    where
+        'a: 's,
+    {
+        match schema.get("$ref").and_then(Json::as_str) {
+            Some(reference) => reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+                .map(|s| self.resolve(s))
+                .unwrap_or(&Json::Bool(true)),
+            None => schema,
+        }
+    }
+
+    fn validate(&mut self, schema: &Json, value: &Value, key: &str) {
+        let schema = self.resolve(schema);
+        if !schema.is_object() {
+            return;
+        }
+
+        if let Some(variants) = schema
+            .get("oneOf")
+            .or_else(|| schema.get("anyOf"))
+            .and_then(Json::as_array)
+        {
+            match variants.iter().find(|v| self.matches(v, value, key)) {
+                // validate again to report the unknown keys of the matched variant
+                Some(variant) if self.check_unknown => self.validate(variant, value, key),
+                Some(_) => {}
+                None => {
+                    self.problem(
+                        Severity::Error,
+                        key,
+                        format!(
+                            "{} does not match any of the allowed values",
+                            describe(value)
+                        ),
+                    );
+                    return;
+                }
+            }
+        }
+        if let Some(all) = schema.get("allOf").and_then(Json::as_array) {
+            for s in all {
+                self.validate(s, value, key);
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Json::as_array) {
+            let json = to_json(value);
+            if !allowed.contains(&json) {
+                let allowed: Vec<String> = allowed.iter().map(Json::to_string).collect();
+                self.problem(
+                    Severity::Error,
+                    key,
+                    format!("{json} is not one of {}", allowed.join(", ")),
+                );
+                return;
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if *constant != to_json(value) {
+                self.problem(Severity::Error, key, format!("expected {constant}"));
+                return;
+            }
+        }
+
+        if let Some(expected) = schema.get("type") {
+            if !type_matches(expected, value) {
+                self.problem(
+                    Severity::Error,
+                    key,
+                    format!(
+                        "expected {}, found {}",
+                        expected_types(expected),
+                        describe(value)
+                    ),
+                );
+                return;
+            }
+        }
+
+        match value {
+            Value::Integer(i) => self.check_range(schema, *i as f64, key),
+            Value::Float(f) => self.check_range(schema, *f, key),
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (i, item) in items.iter().enumerate() {
+                        self.validate(item_schema, item, &format!("{key}[{i}]"));
+                    }
+                }
+            }
+            Value::Table(table) => self.validate_table(schema, table, key),
+            _ => {}
+        }
+    }
+
+    fn validate_table(&mut self, schema: &Json, table: &Table, key: &str) {
+        let properties = schema.get("properties").and_then(Json::as_object);
+        if let Some(required) = schema.get("required").and_then(Json::as_array) {
+            for name in required.iter().filter_map(Json::as_str) {
+                if !table.contains_key(name) {
+                    self.problem(
+                        Severity::Error,
+                        key,
+                        format!("missing required key `{name}`"),
+                    );
+                }
+            }
+        }
+        let additional = schema.get("additionalProperties");
+        for (name, value) in table {
+            let child_key = format!("{key}.{name}");
+            match properties.and_then(|p| p.get(name)) {
+                Some(property) => self.validate(property, value, &child_key),
+                None => match additional {
+                    Some(Json::Bool(false)) => {
+                        self.problem(Severity::Error, &child_key, "unknown key".to_string())
+                    }
+                    Some(additional @ Json::Object(_)) => {
+                        self.validate(additional, value, &child_key)
+                    }
+                    Some(_) => {}
+                    None => {
+                        if self.check_unknown
+                            && properties.is_some()
+                            && !self.is_variant_key(schema, name)
+                        {
+                            self.problem(Severity::Warning, &child_key, "unknown key".to_string())
+                        }
+                    }
+                },
+            }
+        }
+    }
+
+    fn check_range(&mut self, schema: &Json, number: f64, key: &str) {
+        if let Some(minimum) = schema.get("minimum").and_then(Json::as_f64) {
+            if number < minimum {
+                self.problem(
+                    Severity::Error,
+                    key,
+                    format!("{number} is less than the minimum {minimum}"),
+                );
+            }
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(Json::as_f64) {
+            if number > maximum {
+                self.problem(
+                    Severity::Error,
+                    key,
+                    format!("{number} is greater than the maximum {maximum}"),
+                );
+            }
+        }
+    }
+
+    /// Validate against a variant of `oneOf`/`anyOf` without reporting the problems
+    fn matches(&self, schema: &Json, value: &Value, key: &str) -> bool {
+        let mut problems = vec![];
+        let mut validator = Validator {
+            root: self.root,
+            problems: &mut problems,
+            check_unknown: false,
+        };
+        validator.validate(schema, value, key);
+        problems.is_empty()
+    }
+
+    /// Keys of flattened enums are declared by the variants instead of the table itself
+    fn is_variant_key(&self, schema: &Json, name: &str) -> bool {
+        ["oneOf", "anyOf", "allOf"]
+            .iter()
+            .filter_map(|k| schema.get(*k).and_then(Json::as_array))
+            .flatten()
+            .map(|variant| self.resolve(variant))
+            .any(|variant| {
+                variant
+                    .get("properties")
+                    .and_then(Json::as_object)
+                    .is_some_and(|p| p.contains_key(name))
+                    || self.is_variant_key(variant, name)
+            })
+    }
+}
+
+fn type_matches(expected: &Json, value: &Value) -> bool {
+    let matches = |ty: &str| {
+        matches!(
+            (ty, value),
+            ("string", Value::String(_) | Value::Datetime(_))
+                | ("integer", Value::Integer(_))
+                | ("number", Value::Integer(_) | Value::Float(_))
+                | ("boolean", Value::Boolean(_))
+                | ("array", Value::Array(_))
+                | ("object", Value::Table(_))
+        )
+    };
+    match expected {
+        Json::String(ty) => matches(ty),
+        Json::Array(types) => types.iter().filter_map(Json::as_str).any(matches),
+        _ => true,
+    }
+}
+
+fn expected_types(expected: &Json) -> String {
+    match expected {
+        Json::Array(types) => types
+            .iter()
+            .filter_map(Json::as_str)
+            .filter(|t| *t != "null")
+            .collect::<Vec<_>>()
+            .join(" or "),
+        other => other.as_str().unwrap_or_default().to_string(),
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("string {s:?}"),
+        Value::Integer(i) => format!("integer {i}"),
+        Value::Float(f) => format!("float {f}"),
+        Value::Boolean(b) => format!("boolean {b}"),
+        Value::Datetime(d) => format!("datetime {d}"),
+        Value::Array(_) => "array".to_string(),
+        Value::Table(_) => "table".to_string(),
+    }
+}
+
+fn to_json(value: &Value) -> Json {
+    serde_json::to_value(value).unwrap_or(Json::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Severity, Validator};
+    use schemars::JsonSchema;
+    use toml::Table;
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct ServerConfig {
+        port: u16,
+        host: Option<String>,
+        mode: Mode,
+        #[serde(default)]
+        tags: Vec<String>,
+        tls: Option<TlsConfig>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct TlsConfig {
+        cert: String,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    enum Mode {
+        Fast,
+        Safe,
+    }
+
+    fn problems(toml: &str) -> Vec<(Severity, String, String)> {
+        let root = schemars::schema_for!(ServerConfig).to_value();
+        let table: Table = toml::from_str(toml).unwrap();
+        let mut problems = vec![];
+        let mut validator = Validator {
+            root: &root,
+            problems: &mut problems,
+            check_unknown: true,
+        };
+        validator.validate(&root, &toml::Value::Table(table), "server");
+        problems
+            .into_iter()
+            .map(|p| (p.severity, p.key, p.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(problems("port = 8080\nmode = \"Fast\"\ntags = [\"a\"]").is_empty());
+        assert_eq!(
+            problems("port = 8080\nmode = \"Fast\"\ntls = { cert = \"a\", kye = \"b\" }"),
+            vec![(
+                Severity::Warning,
+                "server.tls.kye".to_string(),
+                "unknown key".to_string()
+            )]
+        );
+
+        let problems = problems("prot = 8080\nport = \"80\"\nmode = \"Slow\"\ntags = [1]");
+        assert_eq!(
+            problems,
+            vec![
+                (
+                    Severity::Error,
+                    "server.mode".to_string(),
+                    r#""Slow" is not one of "Fast", "Safe""#.to_string()
+                ),
+                (
+                    Severity::Error,
+                    "server.port".to_string(),
+                    "expected integer, found string \"80\"".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    "server.prot".to_string(),
+                    "unknown key".to_string()
+                ),
+                (
+                    Severity::Error,
+                    "server.tags[0]".to_string(),
+                    "expected string, found integer 1".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_range_and_required() {
+        let problems = problems("port = 70000");
+        assert_eq!(
+            problems,
+            vec![
+                (
+                    Severity::Error,
+                    "server".to_string(),
+                    "missing required key `mode`".to_string()
+                ),
+                (
+                    Severity::Error,
+                    "server.port".to_string(),
+                    "70000 is greater than the maximum 65535".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -8,6 +8,10 @@ pub enum AppError {
     #[error("{0} component not exists")]
     ComponentNotExist(&'static str),
 
+    /// named component not exists
+    #[error("{0} component named \"{1}\" not exists")]
+    NamedComponentNotExist(&'static str, String),
+
     /// `.env` file reading failed
     #[error(transparent)]
     EnvError(#[from] dotenvy::Error),
@@ -32,6 +36,23 @@ pub enum AppError {
     #[error("Failed to deserialize the configuration of prefix \"{0}\": {1}")]
     DeserializeErr(&'static str, toml::de::Error),
 
+    /// The configuration does not match the registered schemas
+    #[error("{0}")]
+    ConfigValidationErr(crate::config::validation::ConfigReport),
+
+    /// Plugins or services depend on each other, the path of the cycle starts and ends with the same name
+    #[error("dependency cycle detected: {}", .0.join(" -> "))]
+    DependencyCycle(Vec<String>),
+
+    /// Plugins or services whose dependencies can't be resolved, with the reason
+    #[error("unresolved dependencies: {}", .0.iter().map(|(name, reason)| format!("{name} ({reason})")).collect::<Vec<_>>().join(", "))]
+    UnresolvedDependencies(Vec<(String, String)>),
+
+    /// A command of the command line was executed instead of starting the application,
+    /// contains the exit code of the process, see [`AppBuilder::cli`](crate::app::AppBuilder::cli)
+    #[error("command executed with exit code {0}")]
+    CommandExecuted(u8),
+
     /// Other runtime errors
     #[error(transparent)]
     OtherError(#[from] anyhow::Error),
@@ -59,6 +80,21 @@ mod tests {
         assert!(error_msg.contains("component not exists"));
     }
 
+    #[test]
+    fn test_dependency_errors() {
+        let error = AppError::DependencyCycle(vec!["A".into(), "B".into(), "A".into()]);
+        assert_eq!(error.to_string(), "dependency cycle detected: A -> B -> A");
+
+        let error = AppError::UnresolvedDependencies(vec![
+            ("WebPlugin".into(), "missing plugin SqlxPlugin".into()),
+            ("UserService".into(), "Redis component not exists".into()),
+        ]);
+        assert_eq!(
+            error.to_string(),
+            "unresolved dependencies: WebPlugin (missing plugin SqlxPlugin), UserService (Redis component not exists)"
+        );
+    }
+
     #[test]
     fn test_from_io_error() {
         let error = AppError::from_io(ErrorKind::NotFound, "file not found");
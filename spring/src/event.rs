@@ -0,0 +1,195 @@
+pub use futures_util::future::BoxFuture;
+pub use inventory::submit;
+
+use crate::app::App;
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::sync::{Arc, OnceLock, Weak};
+
+/// The application is built and its schedulers are starting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppStarted;
+
+/// All schedulers are listening and the startup hooks have been run,
+/// see [`LifecycleState::Ready`](crate::lifecycle::LifecycleState::Ready)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppReady;
+
+/// Shutdown was requested, the schedulers are finishing their in-flight work,
+/// see [`LifecycleState::Draining`](crate::lifecycle::LifecycleState::Draining)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownStarted;
+
+type ListenerFn =
+    dyn for<'a> Fn(&'a App, &'a (dyn Any + Send + Sync)) -> BoxFuture<'a, ()> + Send + Sync;
+
+/// Listener of the events of type `E`, generated by the `#[event_listener]` macro
+#[derive(Clone)]
+pub struct EventListener {
+    name: &'static str,
+    event_type: TypeId,
+    order: i32,
+    call: Arc<ListenerFn>,
+}
+
+impl EventListener {
+    /// Listener named `name` calling `call` for each event of type `E`.
+    ///
+    /// The listeners of an event are called by ascending `order`.
+    pub fn new<E>(
+        name: &'static str,
+        order: i32,
+        call: for<'a> fn(&'a App, &'a E) -> BoxFuture<'a, ()>,
+    ) -> Self
+    where
+        E: Any + Send + Sync,
+    {
+        Self {
+            name,
+            event_type: TypeId::of::<E>(),
+            order,
+            call: Arc::new(move |app, event| {
+                let event = event
+                    .downcast_ref::<E>()
+                    .expect("event type doesn't match the listener");
+                call(app, event)
+            }),
+        }
+    }
+
+    /// Name of the listener
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Order of the listener among the listeners of the same event
+    pub fn order(&self) -> i32 {
+        self.order
+    }
+}
+
+impl fmt::Debug for EventListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventListener")
+            .field("name", &self.name)
+            .field("order", &self.order)
+            .finish()
+    }
+}
+
+/// Registrar of the listeners generated by the `#[event_listener]` macro
+pub trait EventListenerRegistrar: Send + Sync + 'static {
+    /// The listener to register
+    fn listener(&self) -> EventListener;
+}
+
+inventory::collect!(&'static dyn EventListenerRegistrar);
+
+/// Submit the event listener registrar generated by the `#[event_listener]` macro
+#[macro_export]
+macro_rules! submit_event_listener {
+    ($ty:ident) => {
+        ::spring::event::submit! {
+            &$ty as &dyn ::spring::event::EventListenerRegistrar
+        }
+    };
+}
+
+/// Component publishing in-process events to the listeners registered with `#[event_listener]`.
+///
+/// Any `Send + Sync + 'static` type can be an event.
+/// The listeners receive a reference to the event, followed by the `Component<T>` and `Config<T>` they need;
+/// they can be sync or async functions and are called one after the other by ascending `order`:
+///
+/// ```rust,ignore
+/// #[derive(Debug)]
+/// struct UserRegistered { email: String }
+///
+/// #[event_listener]
+/// async fn send_welcome_mail(event: &UserRegistered, Component(mailer): Component<Mailer>) {
+///     mailer.send_welcome(&event.email).await;
+/// }
+///
+/// #[event_listener(order = 10)]
+/// fn count_registrations(_event: &UserRegistered) {
+///     REGISTRATIONS.fetch_add(1, Ordering::Relaxed);
+/// }
+///
+/// #[derive(Clone, Service)]
+/// struct UserService {
+///     #[inject(component)]
+///     events: ApplicationEventPublisher,
+/// }
+///
+/// impl UserService {
+///     async fn register(&self, email: String) {
+///         // ...
+///         self.events.publish(UserRegistered { email }).await;
+///     }
+/// }
+/// ```
+///
+/// The application publishes the lifecycle events [`AppStarted`], [`AppReady`] and [`ShutdownStarted`]
+/// when it is [run](crate::app::AppBuilder::run).
+///
+/// The listeners look their components up in the built application:
+/// the events published while the plugins are built or the services are initialized are dropped.
+#[derive(Clone)]
+pub struct ApplicationEventPublisher {
+    listeners: Arc<Vec<EventListener>>,
+    app: Arc<OnceLock<Weak<App>>>,
+}
+
+impl Default for ApplicationEventPublisher {
+    fn default() -> Self {
+        let mut listeners: Vec<_> = inventory::iter::<&dyn EventListenerRegistrar>
+            .into_iter()
+            .map(|registrar| registrar.listener())
+            .collect();
+        listeners.sort_by_key(|listener| listener.order);
+        Self {
+            listeners: Arc::new(listeners),
+            app: Default::default(),
+        }
+    }
+}
+
+impl ApplicationEventPublisher {
+    /// Listeners of the events of type `E`, by ascending order
+    pub fn listeners<E>(&self) -> impl Iterator<Item = &EventListener>
+    where
+        E: Any + Send + Sync,
+    {
+        self.listeners
+            .iter()
+            .filter(|listener| listener.event_type == TypeId::of::<E>())
+    }
+
+    /// Call the listeners of the event one after the other
+    pub async fn publish<E>(&self, event: E)
+    where
+        E: Any + Send + Sync,
+    {
+        let event_name = std::any::type_name::<E>();
+        let Some(app) = self.app.get().and_then(Weak::upgrade) else {
+            log::warn!("{event_name} event dropped, the application is not built yet");
+            return;
+        };
+        for listener in self.listeners::<E>() {
+            log::trace!("{event_name} event received by {}", listener.name);
+            (listener.call)(&app, &event).await;
+        }
+    }
+
+    pub(crate) fn bind(&self, app: &Arc<App>) {
+        let _ = self.app.set(Arc::downgrade(app));
+    }
+}
+
+impl fmt::Debug for ApplicationEventPublisher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApplicationEventPublisher")
+            .field("listeners", &self.listeners)
+            .finish()
+    }
+}
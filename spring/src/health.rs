@@ -0,0 +1,234 @@
+use crate::app::{App, AppBuilder};
+use crate::error::Result;
+use crate::plugin::{ComponentRegistry, MutableComponentRegistry};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+/// Maximum time a single [`HealthIndicator::check`] may take before it is reported as down
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Status of a [`HealthIndicator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HealthStatus {
+    /// The component is working
+    Up,
+    /// The component is not working
+    Down,
+}
+
+impl fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Up => "UP",
+            Self::Down => "DOWN",
+        })
+    }
+}
+
+/// Result of a successful [`HealthIndicator::check`]
+#[derive(Debug, Clone, Serialize)]
+pub struct Health {
+    /// status of the component
+    pub status: HealthStatus,
+    /// additional details, e.g. the size of a connection pool
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub details: Map<String, Value>,
+}
+
+impl Health {
+    /// The component is working
+    pub fn up() -> Self {
+        Self {
+            status: HealthStatus::Up,
+            details: Map::new(),
+        }
+    }
+
+    /// The component is not working
+    pub fn down() -> Self {
+        Self {
+            status: HealthStatus::Down,
+            details: Map::new(),
+        }
+    }
+
+    /// Add a detail to the health
+    pub fn with_detail<V: Into<Value>>(mut self, key: &str, value: V) -> Self {
+        self.details.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+/// Probe of a component, e.g. a database connection pool or a remote service.
+///
+/// Plugins register an indicator for the clients they create with
+/// [`HealthConfigurator::add_health_indicator`], the indicators are
+/// aggregated by [`check_health`].
+///
+/// ```rust,ignore
+/// struct ThirdPartyIndicator(Client);
+///
+/// #[async_trait]
+/// impl HealthIndicator for ThirdPartyIndicator {
+///     fn name(&self) -> &str {
+///         "third_party"
+///     }
+///
+///     async fn check(&self) -> Result<Health> {
+///         self.0.ping().await?;
+///         Ok(Health::up())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait HealthIndicator: Send + Sync + 'static {
+    /// Name of the indicator in the [`HealthReport`]
+    fn name(&self) -> &str;
+
+    /// Probe the component, an error is reported as [`HealthStatus::Down`]
+    async fn check(&self) -> Result<Health>;
+}
+
+/// Register [`HealthIndicator`]s in the application
+pub trait HealthConfigurator {
+    /// Add a health indicator to the app registry,
+    /// it is listed with the other indicators by `get_components::<Arc<dyn HealthIndicator>>()`
+    fn add_health_indicator<H: HealthIndicator>(&mut self, indicator: H) -> &mut Self;
+}
+
+impl HealthConfigurator for AppBuilder {
+    fn add_health_indicator<H: HealthIndicator>(&mut self, indicator: H) -> &mut Self {
+        self.append_component::<Arc<dyn HealthIndicator>>(Arc::new(indicator))
+    }
+}
+
+/// Health of a single indicator in the [`HealthReport`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    /// status of the component
+    pub status: HealthStatus,
+    /// time taken by the check in milliseconds
+    pub latency_ms: u64,
+    /// details returned by the indicator, or the error of a failed check
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub details: Map<String, Value>,
+}
+
+/// Aggregated result of all [`HealthIndicator`]s
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// [`HealthStatus::Up`] if every indicator is up
+    pub status: HealthStatus,
+    /// health of each indicator by name
+    pub components: BTreeMap<String, ComponentHealth>,
+}
+
+/// Run all registered [`HealthIndicator`]s concurrently.
+///
+/// A check failing or taking longer than [`HEALTH_CHECK_TIMEOUT`] is reported as down.
+pub async fn check_health(app: &App) -> HealthReport {
+    let indicators = app.get_components::<Arc<dyn HealthIndicator>>();
+
+    let mut checks = JoinSet::new();
+    for indicator in indicators {
+        checks.spawn(async move {
+            let start = Instant::now();
+            let result = tokio::time::timeout(HEALTH_CHECK_TIMEOUT, indicator.check()).await;
+            let latency_ms = start.elapsed().as_millis() as u64;
+            let health = match result {
+                Ok(Ok(health)) => health,
+                Ok(Err(e)) => Health::down().with_detail("error", e.to_string()),
+                Err(_) => Health::down().with_detail(
+                    "error",
+                    format!("health check timed out after {HEALTH_CHECK_TIMEOUT:?}"),
+                ),
+            };
+            let component = ComponentHealth {
+                status: health.status,
+                latency_ms,
+                details: health.details,
+            };
+            (indicator.name().to_string(), component)
+        });
+    }
+
+    let mut components = BTreeMap::new();
+    while let Some(result) = checks.join_next().await {
+        match result {
+            Ok((name, component)) => {
+                if component.status != HealthStatus::Up {
+                    tracing::warn!("health check of {name} is {}", component.status);
+                }
+                components.insert(name, component);
+            }
+            Err(e) => tracing::error!("health check panicked: {e}"),
+        }
+    }
+
+    let status = components
+        .values()
+        .map(|c| c.status)
+        .max()
+        .unwrap_or(HealthStatus::Up);
+    HealthReport { status, components }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_health, Health, HealthConfigurator, HealthIndicator, HealthStatus};
+    use crate::app::AppBuilder;
+    use crate::error::{AppError, Result};
+    use async_trait::async_trait;
+
+    struct Indicator(&'static str, bool);
+
+    #[async_trait]
+    impl HealthIndicator for Indicator {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        async fn check(&self) -> Result<Health> {
+            if self.1 {
+                Ok(Health::up().with_detail("size", 3))
+            } else {
+                Err(AppError::from(anyhow::anyhow!("connection refused")))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_health() -> Result<()> {
+        let app = AppBuilder::from_config_str("")
+            .isolate_global(true)
+            .build()
+            .await?;
+        let report = check_health(&app).await;
+        assert_eq!(report.status, HealthStatus::Up);
+        assert!(report.components.is_empty());
+
+        let app = AppBuilder::from_config_str("")
+            .add_health_indicator(Indicator("db", true))
+            .add_health_indicator(Indicator("redis", false))
+            .isolate_global(true)
+            .build()
+            .await?;
+        let report = check_health(&app).await;
+        assert_eq!(report.status, HealthStatus::Down);
+        assert_eq!(report.components["db"].status, HealthStatus::Up);
+        assert_eq!(report.components["db"].details["size"], 3);
+        assert_eq!(report.components["redis"].status, HealthStatus::Down);
+        assert_eq!(
+            report.components["redis"].details["error"],
+            "connection refused"
+        );
+        Ok(())
+    }
+}
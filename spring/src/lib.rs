@@ -8,22 +8,36 @@
 pub mod app;
 /// Banner
 pub mod banner;
+/// Caches of the `#[cache]` macro: in-memory by default, Redis with spring-redis
+pub mod cache;
 /// Config System:
 pub mod config;
 /// spring-rs definition error
 pub mod error;
+/// Application events published in-process to the listeners registered with `#[event_listener]`
+pub mod event;
 /// spring-rs extractor
 pub mod extractor;
+/// Health indicators to probe the components of the application
+pub mod health;
+/// Application lifecycle: readiness, liveness and graceful shutdown
+pub mod lifecycle;
 /// The log plugin is a built-in plugin of spring-rs and is also the first plugin loaded when the application starts.
 pub mod log;
 /// Plugin system: Through the documentation of this module you will learn how to implement your own plugins
 pub mod plugin;
 /// signal, such as "ctrl-c" notification
 pub mod signal;
+/// SQL helpers shared by the database plugins
+pub mod sql;
 
 pub use app::App;
 pub use async_trait::async_trait;
 pub use spring_macros::auto_config;
+pub use spring_macros::cache;
+pub use spring_macros::cache_evict;
+pub use spring_macros::cache_put;
 pub use spring_macros::component;
+pub use spring_macros::event_listener;
 pub use tracing;
 pub use tracing_error::SpanTrace;
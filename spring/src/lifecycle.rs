@@ -0,0 +1,203 @@
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// The state of an application, see [`Lifecycle`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LifecycleState {
+    /// The plugins are built and the schedulers are starting
+    Starting,
+    /// All schedulers are listening and the startup hooks have been run
+    Ready,
+    /// Shutdown was requested, the schedulers are finishing their in-flight work
+    Draining,
+    /// The schedulers have stopped and the shutdown hooks have been run
+    Stopped,
+}
+
+impl fmt::Display for LifecycleState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Starting => "starting",
+            Self::Ready => "ready",
+            Self::Draining => "draining",
+            Self::Stopped => "stopped",
+        })
+    }
+}
+
+/// Central lifecycle of an application.
+///
+/// [`AppBuilder::run`](crate::app::AppBuilder::run) drives the application through the states
+/// `Starting -> Ready -> Draining -> Stopped`:
+///
+/// 1. all schedulers are spawned, schedulers that need time to start (e.g. binding a listener)
+///    hold a [`StartupGuard`] until they are listening
+/// 2. once every guard is released, the application is `Ready` and the startup hooks are run
+/// 3. on Ctrl+C, SIGTERM or [`Lifecycle::shutdown`], the application is `Draining`,
+///    every scheduler waiting on [`Lifecycle::shutdown_signal`] is notified and
+///    has a grace period to finish before it is aborted
+/// 4. the shutdown hooks are run and the application is `Stopped`
+///
+/// ```rust,ignore
+/// app.add_scheduler(|app: Arc<App>| {
+///     let lifecycle = app.lifecycle().clone();
+///     Box::new(async move {
+///         lifecycle.shutdown_signal("my server").await;
+///         Ok("my server stopped".to_string())
+///     })
+/// });
+/// ```
+#[derive(Debug)]
+pub struct Lifecycle {
+    state: watch::Sender<LifecycleState>,
+    pending_startups: Arc<watch::Sender<usize>>,
+}
+
+impl Default for Lifecycle {
+    fn default() -> Self {
+        Self {
+            state: watch::Sender::new(LifecycleState::Starting),
+            pending_startups: Arc::new(watch::Sender::new(0)),
+        }
+    }
+}
+
+impl Lifecycle {
+    /// Current state of the application
+    pub fn state(&self) -> LifecycleState {
+        *self.state.borrow()
+    }
+
+    /// Subscribe to the state changes of the application
+    pub fn subscribe(&self) -> watch::Receiver<LifecycleState> {
+        self.state.subscribe()
+    }
+
+    /// Readiness: the application is started and accepts work
+    pub fn is_ready(&self) -> bool {
+        self.state() == LifecycleState::Ready
+    }
+
+    /// Liveness: the application has not stopped yet
+    pub fn is_alive(&self) -> bool {
+        self.state() != LifecycleState::Stopped
+    }
+
+    /// Returns `true` once shutdown has been requested
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.state() >= LifecycleState::Draining
+    }
+
+    /// Request the shutdown of the application, all schedulers are notified
+    pub fn shutdown(&self) {
+        self.advance(LifecycleState::Draining);
+    }
+
+    /// Wait until shutdown is requested.
+    ///
+    /// Schedulers should pass this future to the graceful shutdown of their servers.
+    pub async fn shutdown_signal(&self, name: &str) {
+        self.wait_shutdown().await;
+        tracing::info!("application is shutting down, waiting for {name} shutdown");
+    }
+
+    /// Register a scheduler that is still starting.
+    ///
+    /// The application becomes [ready][LifecycleState::Ready] when all guards are released.
+    /// The guard must be created synchronously in the scheduler closure, before its future is spawned.
+    pub fn startup_task(&self, name: &str) -> StartupGuard {
+        self.pending_startups.send_modify(|pending| *pending += 1);
+        StartupGuard {
+            name: name.to_string(),
+            pending_startups: self.pending_startups.clone(),
+        }
+    }
+
+    pub(crate) async fn wait_shutdown(&self) {
+        let mut receiver = self.state.subscribe();
+        let _ = receiver
+            .wait_for(|state| *state >= LifecycleState::Draining)
+            .await;
+    }
+
+    pub(crate) async fn wait_startup(&self) {
+        let mut receiver = self.pending_startups.subscribe();
+        let _ = receiver.wait_for(|pending| *pending == 0).await;
+    }
+
+    /// Move forward to `state`, the state never goes back.
+    /// Returns `true` if the state has changed.
+    pub(crate) fn advance(&self, state: LifecycleState) -> bool {
+        self.state.send_if_modified(|current| {
+            if *current < state {
+                tracing::info!("application is {state}");
+                *current = state;
+                true
+            } else {
+                false
+            }
+        })
+    }
+}
+
+/// Created by [`Lifecycle::startup_task`], release it once the scheduler is listening.
+///
+/// Dropping the guard releases it as well, so a scheduler that fails to start never blocks the application.
+#[must_use = "the scheduler is considered started as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct StartupGuard {
+    name: String,
+    pending_startups: Arc<watch::Sender<usize>>,
+}
+
+impl StartupGuard {
+    /// The scheduler is started
+    pub fn ready(self) {
+        tracing::debug!("{} is ready", self.name);
+    }
+}
+
+impl Drop for StartupGuard {
+    fn drop(&mut self) {
+        self.pending_startups
+            .send_modify(|pending| *pending = pending.saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lifecycle, LifecycleState};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_lifecycle_states() {
+        let lifecycle = Lifecycle::default();
+        assert_eq!(lifecycle.state(), LifecycleState::Starting);
+        assert!(lifecycle.is_alive() && !lifecycle.is_ready());
+
+        let guard = lifecycle.startup_task("server");
+        let startup = tokio::time::timeout(Duration::from_millis(20), lifecycle.wait_startup());
+        assert!(startup.await.is_err());
+        guard.ready();
+        lifecycle.wait_startup().await;
+
+        assert!(lifecycle.advance(LifecycleState::Ready));
+        assert!(lifecycle.is_ready());
+
+        let waiting = tokio::spawn({
+            let mut state = lifecycle.subscribe();
+            async move {
+                let _ = state.wait_for(|s| *s == LifecycleState::Draining).await;
+            }
+        });
+        lifecycle.shutdown();
+        waiting.await.unwrap();
+        lifecycle.shutdown_signal("server").await;
+        assert!(lifecycle.is_shutdown_requested());
+
+        assert!(!lifecycle.advance(LifecycleState::Ready));
+        assert!(lifecycle.advance(LifecycleState::Stopped));
+        assert!(!lifecycle.is_alive());
+    }
+}
@@ -0,0 +1,245 @@
+use super::BoxLayer;
+use crate::error::{AppError, Result};
+use anyhow::Context;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::layer::Layered;
+use tracing_subscriber::{reload, Registry};
+
+/// Name of the logger changing the default level of all targets
+pub const ROOT_LOGGER: &str = "root";
+
+type FilterHandle = reload::Handle<EnvFilter, Layered<Vec<BoxLayer>, Registry>>;
+
+static LOGGER: OnceLock<LoggerHandle> = OnceLock::new();
+
+/// The logger installed by the [`LogPlugin`](super::LogPlugin), `None` if another
+/// global subscriber was installed before the application was built
+pub(crate) fn logger() -> Option<&'static LoggerHandle> {
+    LOGGER.get()
+}
+
+/// Change the log levels at runtime, see [`App::logger`](crate::App::logger).
+///
+/// The levels set at runtime override the configured filter until they are reset,
+/// they are not written back to the configuration.
+///
+/// ```rust,ignore
+/// let logger = app.logger().expect("logger is not installed");
+/// // debug sqlx for the next 10 minutes
+/// logger.set_level("sqlx", "debug", Some(Duration::from_secs(600)))?;
+/// // change the default level
+/// logger.set_level(ROOT_LOGGER, "warn", None)?;
+/// logger.reset_all()?;
+/// ```
+#[derive(Clone)]
+pub struct LoggerHandle {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    configured: String,
+    overrides: Mutex<BTreeMap<String, Override>>,
+    generation: AtomicU64,
+    handle: FilterHandle,
+}
+
+#[derive(Debug, Clone)]
+struct Override {
+    level: LevelFilter,
+    generation: u64,
+}
+
+/// Current log levels returned by [`LoggerHandle::levels`]
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLevels {
+    /// filter directive built from the configuration or the `RUST_LOG` environment variable
+    pub configured: String,
+    /// levels set at runtime by target, [`ROOT_LOGGER`] for the default level
+    pub overrides: BTreeMap<String, String>,
+    /// filter directive currently in effect
+    pub effective: String,
+}
+
+impl LoggerHandle {
+    fn new(configured: String, handle: FilterHandle) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                configured,
+                overrides: Mutex::new(BTreeMap::new()),
+                generation: AtomicU64::new(0),
+                handle,
+            }),
+        }
+    }
+
+    pub(crate) fn install(configured: String, handle: FilterHandle) -> &'static Self {
+        LOGGER.get_or_init(|| Self::new(configured, handle))
+    }
+
+    /// Current configured and overridden levels
+    pub fn levels(&self) -> LogLevels {
+        let overrides = self.lock();
+        LogLevels {
+            configured: self.inner.configured.clone(),
+            effective: self.directive(&overrides),
+            overrides: overrides
+                .iter()
+                .map(|(target, o)| (target.clone(), level_name(o.level)))
+                .collect(),
+        }
+    }
+
+    /// Set the level of `target`, [`ROOT_LOGGER`] changes the default level.
+    ///
+    /// With `revert_after` the level is reset after the timeout, unless it was changed again in the meantime.
+    pub fn set_level(
+        &self,
+        target: &str,
+        level: &str,
+        revert_after: Option<Duration>,
+    ) -> Result<()> {
+        let level = LevelFilter::from_str(level)
+            .map_err(|_| AppError::from(anyhow::anyhow!("invalid log level: {level}")))?;
+        let runtime = match revert_after {
+            Some(_) => Some(
+                tokio::runtime::Handle::try_current()
+                    .context("reverting the log level requires a tokio runtime")?,
+            ),
+            None => None,
+        };
+        let generation = self.inner.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        self.update(|overrides| {
+            overrides.insert(target.to_string(), Override { level, generation });
+        })?;
+        tracing::info!("log level of {target} is set to {}", level_name(level));
+
+        if let (Some(runtime), Some(timeout)) = (runtime, revert_after) {
+            let logger = self.clone();
+            let target = target.to_string();
+            runtime.spawn(async move {
+                tokio::time::sleep(timeout).await;
+                let result = logger.update(|overrides| {
+                    if overrides.get(&target).map(|o| o.generation) == Some(generation) {
+                        overrides.remove(&target);
+                    }
+                });
+                match result {
+                    Ok(()) => tracing::info!("log level of {target} is reverted"),
+                    Err(e) => tracing::error!("revert log level of {target} failed: {e}"),
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Reset the level of `target` to the configured filter
+    pub fn reset(&self, target: &str) -> Result<()> {
+        self.update(|overrides| {
+            overrides.remove(target);
+        })
+    }
+
+    /// Reset all levels to the configured filter
+    pub fn reset_all(&self) -> Result<()> {
+        self.update(BTreeMap::clear)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, Override>> {
+        self.inner
+            .overrides
+            .lock()
+            .expect("log level overrides lock poisoned")
+    }
+
+    /// Modify the overrides and reload the filter, the overrides are unchanged if the filter is invalid
+    fn update<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut BTreeMap<String, Override>),
+    {
+        let mut overrides = self.lock();
+        let mut updated = overrides.clone();
+        f(&mut updated);
+        let directive = self.directive(&updated);
+        let filter = EnvFilter::try_new(&directive)
+            .with_context(|| format!("invalid log filter: {directive}"))?;
+        self.inner
+            .handle
+            .reload(filter)
+            .context("reload log filter failed")?;
+        *overrides = updated;
+        Ok(())
+    }
+
+    fn directive(&self, overrides: &BTreeMap<String, Override>) -> String {
+        let configured = self
+            .inner
+            .configured
+            .split(',')
+            .filter(|d| !d.trim().is_empty());
+        let mut directives: Vec<String> = match overrides.get(ROOT_LOGGER) {
+            // the default level of the configured filter is replaced
+            Some(root) => std::iter::once(level_name(root.level))
+                .chain(
+                    configured
+                        .filter(|d| LevelFilter::from_str(d.trim()).is_err())
+                        .map(str::to_string),
+                )
+                .collect(),
+            None => configured.map(str::to_string).collect(),
+        };
+        directives.extend(
+            overrides
+                .iter()
+                .filter(|(target, _)| *target != ROOT_LOGGER)
+                .map(|(target, o)| format!("{target}={}", level_name(o.level))),
+        );
+        directives.join(",")
+    }
+}
+
+fn level_name(level: LevelFilter) -> String {
+    level.to_string().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoggerHandle, ROOT_LOGGER};
+    use crate::log::BoxLayer;
+    use std::time::Duration;
+    use tracing_subscriber::filter::EnvFilter;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::{reload, Registry};
+
+    #[tokio::test]
+    async fn test_set_log_level() -> crate::error::Result<()> {
+        let (filter, handle) = reload::Layer::new(EnvFilter::new("info,axum=debug"));
+        let subscriber = Registry::default()
+            .with(Vec::<BoxLayer>::new())
+            .with(filter);
+        let _guard = tracing::subscriber::set_default(subscriber);
+        let logger = LoggerHandle::new("info,axum=debug".to_string(), handle);
+
+        logger.set_level(ROOT_LOGGER, "warn", None)?;
+        logger.set_level("sqlx", "DEBUG", Some(Duration::from_millis(50)))?;
+        let levels = logger.levels();
+        assert_eq!(levels.effective, "warn,axum=debug,sqlx=debug");
+        assert_eq!(levels.overrides["sqlx"], "debug");
+
+        assert!(logger.set_level("sqlx", "verbose", None).is_err());
+        assert!(logger.set_level("sqlx[", "debug", None).is_err());
+        assert_eq!(logger.levels().overrides.len(), 2);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(logger.levels().effective, "warn,axum=debug");
+
+        logger.reset(ROOT_LOGGER)?;
+        assert_eq!(logger.levels().effective, "info,axum=debug");
+        Ok(())
+    }
+}
@@ -1,9 +1,13 @@
 #![doc = include_str!("../../Log-Plugin.md")]
 mod config;
+mod level;
 
 use crate::app::AppBuilder;
 use crate::config::ConfigRegistry;
 use crate::plugin::Plugin;
+pub(crate) use level::logger;
+pub use level::{LogLevels, LoggerHandle, ROOT_LOGGER};
+
 use config::{Format, LogLevel, LoggerConfig, TimeStyle, WithFields};
 use nu_ansi_term::Color;
 use std::sync::OnceLock;
@@ -13,11 +17,11 @@ use tracing_subscriber::filter::EnvFilter;
 use tracing_subscriber::fmt::time::{ChronoLocal, ChronoUtc, FormatTime, SystemTime, Uptime};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::Registry;
 use tracing_subscriber::{
     fmt::{self, MakeWriter},
     Layer,
 };
+use tracing_subscriber::{reload, Registry};
 
 /// Boxed [Tracing Layer](https://docs.rs/tracing-subscriber/latest/tracing_subscriber/layer/index.html)
 pub type BoxLayer = Box<dyn Layer<Registry> + Send + Sync + 'static>;
@@ -56,15 +60,21 @@ impl Plugin for LogPlugin {
         let layers = config.config_subscriber(layers);
 
         let env_filter = config.build_env_filter();
+        let configured = env_filter.to_string();
+        // the filter can be changed at runtime through the LoggerHandle
+        let (env_filter, handle) = reload::Layer::new(env_filter);
 
         // try_init() instead of init() to handle cases where the global subscriber
         // has already been set (e.g., in test environments with multiple App instances)
         // This is the correct approach as tracing subscriber is a process-wide singleton
-        let _ = tracing_subscriber::registry()
+        let installed = tracing_subscriber::registry()
             .with(layers)
             .with(env_filter)
             .with(ErrorLayer::default())
             .try_init();
+        if installed.is_ok() {
+            LoggerHandle::install(configured, handle);
+        }
     }
 
     fn immediately(&self) -> bool {
@@ -2,7 +2,7 @@ use std::{any::Any, ops::Deref, sync::Arc};
 
 /// Component's dyn trait reference
 #[derive(Debug, Clone)]
-pub struct DynComponentRef(Arc<dyn Any + Send + Sync>);
+pub struct DynComponentRef(Arc<dyn Any + Send + Sync>, &'static str);
 
 impl DynComponentRef {
     /// constructor
@@ -10,7 +10,7 @@ impl DynComponentRef {
     where
         T: Any + Send + Sync,
     {
-        Self(Arc::new(component))
+        Self(Arc::new(component), std::any::type_name::<T>())
     }
 
     /// Downcast to the specified type
@@ -20,6 +20,16 @@ impl DynComponentRef {
     {
         self.0.downcast::<T>().ok().map(ComponentRef::new)
     }
+
+    /// Type name of the component
+    pub fn type_name(&self) -> &'static str {
+        self.1
+    }
+
+    /// Whether both references point to the same component
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 /// A component reference of a specified type
@@ -0,0 +1,132 @@
+use crate::app::AppBuilder;
+use crate::plugin::ComponentRegistry;
+use std::any::Any;
+use std::fmt;
+
+/// Condition of the registration of a component.
+///
+/// It is generated by `#[component(condition = ...)]` and `#[service(condition = ...)]`,
+/// the component is only registered when all its conditions match:
+///
+/// ```rust,ignore
+/// // only registered when `cache.type = "redis"`
+/// #[component(condition = on_property("cache.type", "redis"))]
+/// fn create_redis_cache(Component(redis): Component<Redis>) -> Arc<dyn Cache> {
+///     Arc::new(RedisCache::new(redis))
+/// }
+///
+/// // default registered only when the application registered no other `Arc<dyn Cache>`
+/// #[component(name = "DefaultCache", condition = on_missing_component)]
+/// fn create_memory_cache() -> Arc<dyn Cache> {
+///     Arc::new(MemoryCache::default())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    /// The property is set and is not `false`.
+    /// If `value` is given, the property must be equal to it.
+    OnProperty {
+        /// dotted key of the property, such as `cache.enabled`
+        key: &'static str,
+        /// expected value of the property
+        value: Option<&'static str>,
+    },
+    /// The profile expression matches the active profiles, see [`Profiles::accepts`](crate::config::env::Profiles::accepts)
+    OnProfile(&'static str),
+    /// No other component of the same type is registered.
+    ///
+    /// The plugins of these components are [deferred](crate::plugin::Plugin::deferred):
+    /// they are built after the plugins registering the component they could replace.
+    OnMissingComponent,
+}
+
+impl Condition {
+    /// Whether the condition matches for the component of type `C`
+    pub fn matches<C>(&self, app: &AppBuilder) -> bool
+    where
+        C: Any + Send + Sync,
+    {
+        match self {
+            Self::OnProperty { key, value } => {
+                let property = match app.config_registry().get_property(key) {
+                    Some(toml::Value::String(s)) => s,
+                    Some(property) => property.to_string(),
+                    None => return false,
+                };
+                match value {
+                    Some(value) => property == *value,
+                    None => property != "false",
+                }
+            }
+            Self::OnProfile(profiles) => app.get_profiles().accepts(profiles),
+            Self::OnMissingComponent => !app.has_component::<C>(),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OnProperty { key, value: None } => write!(f, "on_property(\"{key}\")"),
+            Self::OnProperty {
+                key,
+                value: Some(value),
+            } => write!(f, "on_property(\"{key}\", \"{value}\")"),
+            Self::OnProfile(profiles) => write!(f, "on_profile(\"{profiles}\")"),
+            Self::OnMissingComponent => f.write_str("on_missing_component"),
+        }
+    }
+}
+
+/// Check all the `conditions` of the component of type `C`, log the first one which doesn't match
+pub fn matches_all<C>(app: &AppBuilder, conditions: &[Condition]) -> bool
+where
+    C: Any + Send + Sync,
+{
+    match conditions.iter().find(|c| !c.matches::<C>(app)) {
+        Some(condition) => {
+            log::debug!(
+                "component {} skipped, the condition {condition} doesn't match",
+                std::any::type_name::<C>()
+            );
+            false
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Condition;
+    use crate::plugin::MutableComponentRegistry;
+    use crate::App;
+
+    #[derive(Clone)]
+    struct Cache;
+
+    #[test]
+    fn test_condition_matches() {
+        let mut app = App::new();
+        app.use_config_str(
+            r#"
+            [cache]
+            enabled = true
+            type = "redis"
+            ttl = 60
+            compress = false
+            "#,
+        );
+
+        let on_property = |key, value| Condition::OnProperty { key, value };
+        assert!(on_property("cache.enabled", None).matches::<Cache>(&app));
+        assert!(on_property("cache.type", Some("redis")).matches::<Cache>(&app));
+        assert!(on_property("cache.ttl", Some("60")).matches::<Cache>(&app));
+        assert!(!on_property("cache.type", Some("memory")).matches::<Cache>(&app));
+        assert!(!on_property("cache.compress", None).matches::<Cache>(&app));
+        assert!(!on_property("cache.size", None).matches::<Cache>(&app));
+
+        assert!(Condition::OnMissingComponent.matches::<Cache>(&app));
+        app.add_component(Cache);
+        assert!(!Condition::OnMissingComponent.matches::<Cache>(&app));
+    }
+}
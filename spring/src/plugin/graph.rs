@@ -0,0 +1,266 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Kind of a node of the [`DependencyGraph`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    /// A plugin, named by [`Plugin::name`](crate::plugin::Plugin::name)
+    Plugin,
+    /// A component, named by its type name
+    Component,
+    /// A [`Service`](crate::plugin::Service), named by its type name
+    Service,
+}
+
+/// Node of the [`DependencyGraph`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Node {
+    /// kind of the node
+    pub kind: NodeKind,
+    /// name of the plugin, type name of the component or service
+    pub name: String,
+}
+
+/// Edge of the [`DependencyGraph`], `from` depends on `to`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Edge {
+    /// name of the dependent node
+    pub from: String,
+    /// name of the dependency
+    pub to: String,
+}
+
+/// Dependency graph of the plugins, components and services of an application.
+///
+/// The graph of a built application is returned by [`App::dependency_graph`](crate::App::dependency_graph),
+/// and printed by the `--print-graph` argument, see [`AppBuilder::cli`](crate::app::AppBuilder::cli).
+///
+/// ```rust,ignore
+/// let app = App::new().add_plugin(WebPlugin).build().await?;
+/// std::fs::write("dependencies.dot", app.dependency_graph().to_dot())?;
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DependencyGraph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+impl DependencyGraph {
+    /// Add a node, a node with the same name is only added once
+    pub fn add_node<S: Into<String>>(&mut self, kind: NodeKind, name: S) -> &mut Self {
+        let name = name.into();
+        if !self.contains(&name) {
+            self.nodes.push(Node { kind, name });
+        }
+        self
+    }
+
+    /// Add an edge: `from` depends on `to`
+    pub fn add_edge<F: Into<String>, T: Into<String>>(&mut self, from: F, to: T) -> &mut Self {
+        self.edges.push(Edge {
+            from: from.into(),
+            to: to.into(),
+        });
+        self
+    }
+
+    /// Whether the graph has a node named `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.nodes.iter().any(|n| n.name == name)
+    }
+
+    /// Nodes in the order they were added
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Edges in the order they were added
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Names of the direct dependencies of `name`
+    pub fn dependencies<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.edges
+            .iter()
+            .filter(move |e| e.from == name)
+            .map(|e| e.to.as_str())
+    }
+
+    /// Find a dependency cycle, the returned path starts and ends with the same node: `a -> b -> a`
+    pub fn find_cycle(&self) -> Option<Vec<String>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            Visiting,
+            Visited,
+        }
+
+        fn visit<'a>(
+            graph: &'a DependencyGraph,
+            name: &'a str,
+            marks: &mut HashMap<&'a str, Mark>,
+            path: &mut Vec<&'a str>,
+        ) -> Option<Vec<String>> {
+            match marks.get(name) {
+                Some(Mark::Visited) => return None,
+                Some(Mark::Visiting) => {
+                    let start = path.iter().position(|n| *n == name)?;
+                    let mut cycle: Vec<String> =
+                        path[start..].iter().map(|n| n.to_string()).collect();
+                    cycle.push(name.to_string());
+                    return Some(cycle);
+                }
+                None => {}
+            }
+            marks.insert(name, Mark::Visiting);
+            path.push(name);
+            for dep in graph.dependencies(name) {
+                if let Some(cycle) = visit(graph, dep, marks, path) {
+                    return Some(cycle);
+                }
+            }
+            path.pop();
+            marks.insert(name, Mark::Visited);
+            None
+        }
+
+        let mut marks = HashMap::new();
+        self.nodes
+            .iter()
+            .find_map(|node| visit(self, &node.name, &mut marks, &mut vec![]))
+    }
+
+    /// Graphviz representation of the graph:
+    /// plugins are boxes, components ellipses and services components
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Plugin => "box",
+                NodeKind::Component => "ellipse",
+                NodeKind::Service => "component",
+            };
+            dot.push_str(&format!("    {:?} [shape={shape}];\n", node.name));
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!("    {:?} -> {:?};\n", edge.from, edge.to));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Json representation of the graph: `{"nodes": [{"kind": "plugin", "name": "..."}], "edges": [{"from": "...", "to": "..."}]}`
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("dependency graph serialization failed")
+    }
+}
+
+/// Format of the graph printed by the `--print-graph` argument, see [`AppBuilder::cli`](crate::app::AppBuilder::cli)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// `--print-graph` or `--print-graph=dot`
+    Dot,
+    /// `--print-graph=json`
+    Json,
+}
+
+impl GraphFormat {
+    /// Find the `--print-graph` argument the current process was started with
+    pub fn from_args() -> Option<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Find the `--print-graph` argument in `args`, other arguments are ignored
+    pub fn parse<I, S>(args: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        args.into_iter().find_map(|arg| match arg.as_ref() {
+            "--print-graph" | "--print-graph=dot" => Some(Self::Dot),
+            "--print-graph=json" => Some(Self::Json),
+            _ => None,
+        })
+    }
+
+    /// Write the graph in this format
+    pub fn write<W: Write>(&self, graph: &DependencyGraph, out: &mut W) -> std::io::Result<()> {
+        match self {
+            Self::Dot => write!(out, "{}", graph.to_dot()),
+            Self::Json => writeln!(out, "{}", graph.to_json()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DependencyGraph, GraphFormat, NodeKind};
+
+    fn graph(edges: &[(&str, &str)]) -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+        for (from, to) in edges {
+            graph.add_node(NodeKind::Plugin, *from);
+            graph.add_node(NodeKind::Plugin, *to);
+            graph.add_edge(*from, *to);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let acyclic = graph(&[("web", "sqlx"), ("job", "sqlx"), ("web", "job")]);
+        assert_eq!(acyclic.find_cycle(), None);
+
+        let cyclic = graph(&[("web", "sqlx"), ("sqlx", "redis"), ("redis", "sqlx")]);
+        assert_eq!(
+            cyclic.find_cycle(),
+            Some(vec!["sqlx".into(), "redis".into(), "sqlx".into()])
+        );
+
+        let self_cycle = graph(&[("web", "web")]);
+        assert_eq!(
+            self_cycle.find_cycle(),
+            Some(vec!["web".into(), "web".into()])
+        );
+    }
+
+    #[test]
+    fn test_export_graph() {
+        let mut graph = DependencyGraph::default();
+        graph
+            .add_node(NodeKind::Plugin, "SqlxPlugin")
+            .add_node(NodeKind::Component, "spring_sqlx::ConnectPool")
+            .add_node(NodeKind::Service, "app::UserService")
+            .add_edge("app::UserService", "spring_sqlx::ConnectPool");
+
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph dependencies {
+    "SqlxPlugin" [shape=box];
+    "spring_sqlx::ConnectPool" [shape=ellipse];
+    "app::UserService" [shape=component];
+    "app::UserService" -> "spring_sqlx::ConnectPool";
+}
+"#
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+        assert_eq!(json["nodes"][2]["kind"], "service");
+        assert_eq!(json["edges"][0]["to"], "spring_sqlx::ConnectPool");
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(
+            GraphFormat::parse(["--web.port=80", "--print-graph"]),
+            Some(GraphFormat::Dot)
+        );
+        assert_eq!(
+            GraphFormat::parse(["--print-graph=json"]),
+            Some(GraphFormat::Json)
+        );
+        assert_eq!(GraphFormat::parse(["--print-config"]), None);
+    }
+}
@@ -2,8 +2,14 @@
 
 /// Component definition
 pub mod component;
+/// Conditions of the registration of components
+pub mod condition;
+/// Dependency graph of the plugins, components and services
+pub mod graph;
 /// Lazy component loading for circular dependencies
 pub mod lazy;
+/// Scopes of components: singleton, prototype and request
+pub mod scope;
 /// Service is a special Component that supports dependency injection at compile time
 pub mod service;
 
@@ -73,6 +79,14 @@ pub trait Plugin: Any + Send + Sync {
     fn immediately(&self) -> bool {
         false
     }
+
+    /// Whether the plugin should be built after the other plugins,
+    /// once no plugin which isn't deferred can be built.
+    ///
+    /// It lets a plugin register a default component only if no other plugin registered one.
+    fn deferred(&self) -> bool {
+        false
+    }
 }
 
 impl PluginRef {
@@ -153,6 +167,74 @@ pub trait ComponentRegistry {
     fn has_component<T>(&self) -> bool
     where
         T: Any + Send + Sync;
+
+    /// Get all the components of the specified type: the component added by
+    /// [`add_component`](MutableComponentRegistry::add_component) if any, followed by the components
+    /// added by [`append_component`](MutableComponentRegistry::append_component) in registration order.
+    ///
+    /// With a trait object type this lists all the implementations of the trait:
+    ///
+    /// ```rust,ignore
+    /// let listeners = app.get_components::<Arc<dyn OrderListener>>();
+    /// ```
+    ///
+    /// The default implementation only returns the component of [`get_component`](Self::get_component),
+    /// for the registries without appended components.
+    fn get_components<T>(&self) -> Vec<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.get_component().into_iter().collect()
+    }
+
+    /// Get the reference of the component registered with the specified type and name.
+    ///
+    /// The default implementation returns `None`, for the registries without named components.
+    fn get_component_ref_named<T>(&self, _name: &str) -> Option<ComponentRef<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        None
+    }
+
+    /// Get the reference of the component registered with the specified type and name.
+    /// If the component does not exist, it will return AppError::NamedComponentNotExist.
+    fn try_get_component_ref_named<T>(&self, name: &str) -> Result<ComponentRef<T>>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.get_component_ref_named(name).ok_or_else(|| {
+            AppError::NamedComponentNotExist(std::any::type_name::<T>(), name.to_string())
+        })
+    }
+
+    /// Get the component registered with the specified type and name
+    fn get_component_named<T>(&self, name: &str) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.get_component_ref_named(name)
+            .map(|component| T::clone(&component))
+    }
+
+    /// Get the component registered with the specified type and name.
+    /// If the component does not exist, it will return AppError::NamedComponentNotExist.
+    fn try_get_component_named<T>(&self, name: &str) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.get_component_named(name).ok_or_else(|| {
+            AppError::NamedComponentNotExist(std::any::type_name::<T>(), name.to_string())
+        })
+    }
+
+    /// Is there a component of the specified type and name in the registry?
+    fn has_component_named<T>(&self, name: &str) -> bool
+    where
+        T: Any + Send + Sync,
+    {
+        self.get_component_ref_named::<T>(name).is_some()
+    }
 }
 
 /// Mutable Component Registry
@@ -161,6 +243,94 @@ pub trait MutableComponentRegistry: ComponentRegistry {
     fn add_component<C>(&mut self, component: C) -> &mut Self
     where
         C: Clone + any::Any + Send + Sync;
+
+    /// Add component to the registry under a name,
+    /// so that several components of the same type can coexist.
+    ///
+    /// Named components are independent of the component added by [`add_component`](Self::add_component)
+    /// for the same type, they are only found by [`get_component_named`](ComponentRegistry::get_component_named).
+    ///
+    /// The default implementation panics, the registries supporting named components override it.
+    fn add_named_component<C>(&mut self, name: &str, _component: C) -> &mut Self
+    where
+        C: Clone + any::Any + Send + Sync,
+    {
+        unsupported::<Self>(&format!("named component \"{name}\""))
+    }
+
+    /// Add a component created by `factory` according to its [`Scope`](scope::Scope):
+    ///
+    /// * [`Singleton`](scope::Scope::Singleton): the factory is called once, like [`add_component`](Self::add_component)
+    /// * [`Prototype`](scope::Scope::Prototype): the factory is called by each lookup of the component
+    /// * [`Request`](scope::Scope::Request): the factory is called once per web request by spring-web
+    ///
+    /// The dependencies of the component are looked up in the registry given to the factory,
+    /// those of request scope in the request for a component of request scope:
+    ///
+    /// ```rust,ignore
+    /// app.add_scoped_component(Scope::Prototype, |registry| {
+    ///     let db = registry.try_get_component::<ConnectPool>()?;
+    ///     Ok(AuditRecord::new(db))
+    /// });
+    /// ```
+    ///
+    /// The default implementation panics, the registries supporting scoped components override it.
+    fn add_scoped_component<C, F>(&mut self, scope: scope::Scope, _factory: F) -> &mut Self
+    where
+        C: Clone + any::Any + Send + Sync,
+        F: Fn(scope::RegistryRef<'_>) -> Result<C> + Send + Sync + 'static,
+    {
+        unsupported::<Self>(&format!("{scope:?} scoped component"))
+    }
+
+    /// Add a component of [request scope](scope::Scope::Request) created by an asynchronous `factory`,
+    /// for a component whose creation waits for I/O, like the transaction of the request:
+    ///
+    /// ```rust,ignore
+    /// app.add_async_request_component(|registry| async move {
+    ///     let db = registry.try_get_component::<ConnectPool>()?;
+    ///     let tx = db.begin().await.context("begin transaction failed")?;
+    ///     Ok(RequestTransaction::new(tx))
+    /// });
+    /// ```
+    ///
+    /// The dependencies of request scope are looked up in the request, the asynchronous ones with
+    /// [`get_scoped_component`](scope::RequestRegistry::get_scoped_component).
+    /// The component is created by the `Component` extractor of spring-web, the synchronous factories
+    /// can't depend on it.
+    ///
+    /// The default implementation panics, the registries supporting scoped components override it.
+    fn add_async_request_component<C, F, Fut>(&mut self, _factory: F) -> &mut Self
+    where
+        C: Clone + any::Any + Send + Sync,
+        F: Fn(scope::RequestRegistry) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<C>> + Send + 'static,
+    {
+        unsupported::<Self>("asynchronous request scoped component")
+    }
+
+    /// Append component to the components of its type listed by [`get_components`](ComponentRegistry::get_components),
+    /// any number of components of the same type can be appended.
+    ///
+    /// Appended components are not returned by [`get_component`](ComponentRegistry::get_component).
+    /// It is meant for the implementations of a trait, registered as a trait object:
+    ///
+    /// ```rust,ignore
+    /// app.append_component::<Arc<dyn OrderListener>>(Arc::new(AuditListener))
+    ///     .append_component::<Arc<dyn OrderListener>>(Arc::new(MailListener));
+    /// ```
+    ///
+    /// The default implementation panics, the registries supporting component lists override it.
+    fn append_component<C>(&mut self, _component: C) -> &mut Self
+    where
+        C: Clone + any::Any + Send + Sync,
+    {
+        unsupported::<Self>("appended component")
+    }
+}
+
+fn unsupported<R: ?Sized>(what: &str) -> ! {
+    panic!("{what} is not supported by {}", std::any::type_name::<R>())
 }
 
 #[cfg(test)]
@@ -415,4 +585,43 @@ mod tests {
         app.add_component(TestComponent { value: 1 });
         app.add_component(TestComponent { value: 2 }); // Should panic
     }
+
+    /// Registry implementing only the required methods
+    struct SingleComponentRegistry(Box<dyn Any + Send + Sync>);
+
+    impl ComponentRegistry for SingleComponentRegistry {
+        fn get_component_ref<T>(&self) -> Option<ComponentRef<T>>
+        where
+            T: Any + Send + Sync,
+        {
+            None
+        }
+
+        fn get_component<T>(&self) -> Option<T>
+        where
+            T: Clone + Send + Sync + 'static,
+        {
+            self.0.downcast_ref::<T>().cloned()
+        }
+
+        fn has_component<T>(&self) -> bool
+        where
+            T: Any + Send + Sync,
+        {
+            self.0.is::<T>()
+        }
+    }
+
+    #[test]
+    fn test_component_registry_defaults() {
+        let registry = SingleComponentRegistry(Box::new(TestComponent { value: 7 }));
+        let components = registry.get_components::<TestComponent>();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].value, 7);
+        assert!(registry.get_components::<AnotherComponent>().is_empty());
+        assert!(!registry.has_component_named::<TestComponent>("primary"));
+        assert!(registry
+            .try_get_component_named::<TestComponent>("primary")
+            .is_err());
+    }
 }
@@ -0,0 +1,384 @@
+use crate::app::{App, AppBuilder};
+use crate::config::{ConfigRegistry, Configurable};
+use crate::error::{AppError, Result};
+use crate::plugin::component::{ComponentRef, DynComponentRef};
+use crate::plugin::ComponentRegistry;
+use anyhow::anyhow;
+use futures_util::future::BoxFuture;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// Scope of a component, see [`MutableComponentRegistry::add_scoped_component`](crate::plugin::MutableComponentRegistry::add_scoped_component)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scope {
+    /// A single instance shared by the whole application
+    #[default]
+    Singleton,
+    /// A new instance for each lookup
+    Prototype,
+    /// A new instance for each web request, shared by the lookups of the request and dropped with it.
+    ///
+    /// The instances are created by the `Component` extractor of [spring-web](https://docs.rs/spring-web),
+    /// the lookups outside of a request don't find the component.
+    Request,
+}
+
+/// Registry the components of prototype or request scope are created from:
+/// the application under construction or the built application
+#[derive(Clone, Copy)]
+pub enum RegistryRef<'a> {
+    /// The application under construction
+    Builder(&'a AppBuilder),
+    /// The built application
+    App(&'a App),
+    /// The components of request scope of a web request, then the built application
+    Request(&'a RequestRegistry),
+}
+
+macro_rules! delegate {
+    ($self:ident.$method:ident$(::<$t:ident>)?($($arg:expr),*)) => {
+        match $self {
+            Self::Builder(app) => app.$method$(::<$t>)?($($arg),*),
+            Self::App(app) => app.$method$(::<$t>)?($($arg),*),
+            Self::Request(registry) => registry.$method$(::<$t>)?($($arg),*),
+        }
+    };
+}
+
+impl ComponentRegistry for RegistryRef<'_> {
+    fn get_component_ref<T>(&self) -> Option<ComponentRef<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        delegate!(self.get_component_ref::<T>())
+    }
+
+    fn try_get_component_ref<T>(&self) -> Result<ComponentRef<T>>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        delegate!(self.try_get_component_ref::<T>())
+    }
+
+    fn get_component<T>(&self) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        delegate!(self.get_component::<T>())
+    }
+
+    fn has_component<T>(&self) -> bool
+    where
+        T: Any + Send + Sync,
+    {
+        delegate!(self.has_component::<T>())
+    }
+
+    fn get_components<T>(&self) -> Vec<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        delegate!(self.get_components::<T>())
+    }
+
+    fn get_component_ref_named<T>(&self, name: &str) -> Option<ComponentRef<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        delegate!(self.get_component_ref_named::<T>(name))
+    }
+
+    fn has_component_named<T>(&self, name: &str) -> bool
+    where
+        T: Any + Send + Sync,
+    {
+        delegate!(self.has_component_named::<T>(name))
+    }
+}
+
+impl ConfigRegistry for RegistryRef<'_> {
+    fn get_config<T>(&self) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Configurable,
+    {
+        delegate!(self.get_config::<T>())
+    }
+}
+
+/// Components of [request scope](Scope::Request) of a web request,
+/// shared by the lookups of the request and dropped with it
+#[derive(Clone, Default)]
+pub struct RequestComponents(Arc<Mutex<HashMap<TypeId, DynComponentRef>>>);
+
+impl RequestComponents {
+    /// Put the component in the scope, it replaces the component of the same type
+    pub fn insert<T: Any + Send + Sync>(&self, component: T) {
+        self.insert_ref(TypeId::of::<T>(), DynComponentRef::new(component));
+    }
+
+    /// The component of type `T` of the scope
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<ComponentRef<T>> {
+        let component = self
+            .0
+            .lock()
+            .expect("request scope lock failed")
+            .get(&TypeId::of::<T>())
+            .cloned()?;
+        component.downcast::<T>()
+    }
+
+    fn insert_ref(&self, id: TypeId, component: DynComponentRef) {
+        self.0
+            .lock()
+            .expect("request scope lock failed")
+            .insert(id, component);
+    }
+}
+
+/// Registry of a web request: the components of request scope are looked up in the
+/// [`RequestComponents`] of the request and created by their factory the first time,
+/// the other components in the application.
+///
+/// The factories of request scope get it, so that a component of request scope
+/// can depend on another one.
+#[derive(Clone)]
+pub struct RequestRegistry {
+    app: Arc<App>,
+    components: RequestComponents,
+}
+
+impl RequestRegistry {
+    /// Registry of the request whose scoped components are `components`
+    pub fn new(app: Arc<App>, components: RequestComponents) -> Self {
+        Self { app, components }
+    }
+
+    /// The built application
+    pub fn app(&self) -> &Arc<App> {
+        &self.app
+    }
+
+    /// The component of type `T` of the request, created by its synchronous or
+    /// asynchronous factory the first time if `T` has the request scope.
+    ///
+    /// Returns `None` if the component is neither in the request nor of request scope.
+    pub async fn get_scoped_component<T>(&self) -> Result<Option<ComponentRef<T>>>
+    where
+        T: Any + Send + Sync,
+    {
+        if let Some(component) = self.components.get::<T>() {
+            return Ok(Some(component));
+        }
+        let Some(factory) = self.request_factory::<T>() else {
+            return Ok(None);
+        };
+        let component = match &factory.factory {
+            FactoryFn::Sync(factory) => factory(RegistryRef::Request(self))?,
+            FactoryFn::Async(factory) => factory(self.clone()).await?,
+        };
+        Ok(Some(self.store::<T>(component)?))
+    }
+
+    /// Same as [`get_scoped_component`](Self::get_scoped_component) for the lookups of the
+    /// synchronous factories, it fails for a component created asynchronously
+    fn get_or_create<T>(&self) -> Result<Option<ComponentRef<T>>>
+    where
+        T: Any + Send + Sync,
+    {
+        if let Some(component) = self.components.get::<T>() {
+            return Ok(Some(component));
+        }
+        let Some(factory) = self.request_factory::<T>() else {
+            return Ok(None);
+        };
+        let component = factory.create_dyn(RegistryRef::Request(self))?;
+        Ok(Some(self.store::<T>(component)?))
+    }
+
+    fn request_factory<T: Any + Send + Sync>(&self) -> Option<ComponentFactory> {
+        self.app
+            .component_factory::<T>()
+            .filter(|f| f.scope() == Scope::Request)
+    }
+
+    fn store<T: Any + Send + Sync>(&self, component: DynComponentRef) -> Result<ComponentRef<T>> {
+        self.components
+            .insert_ref(TypeId::of::<T>(), component.clone());
+        component
+            .downcast::<T>()
+            .ok_or(AppError::ComponentNotExist(std::any::type_name::<T>()))
+    }
+}
+
+impl ComponentRegistry for RequestRegistry {
+    fn get_component_ref<T>(&self) -> Option<ComponentRef<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        match self.get_or_create::<T>() {
+            Ok(Some(component)) => Some(component),
+            Ok(None) => self.app.get_component_ref(),
+            Err(e) => {
+                log::error!(
+                    "{} component creation failed: {e:?}",
+                    std::any::type_name::<T>()
+                );
+                None
+            }
+        }
+    }
+
+    fn try_get_component_ref<T>(&self) -> Result<ComponentRef<T>>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        match self.get_or_create::<T>()? {
+            Some(component) => Ok(component),
+            None => self.app.try_get_component_ref(),
+        }
+    }
+
+    fn get_component<T>(&self) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.get_component_ref().map(|c| T::clone(&c))
+    }
+
+    fn try_get_component<T>(&self) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.try_get_component_ref().map(|c| T::clone(&c))
+    }
+
+    fn has_component<T>(&self) -> bool
+    where
+        T: Any + Send + Sync,
+    {
+        self.components.get::<T>().is_some()
+            || self.request_factory::<T>().is_some()
+            || self.app.has_component::<T>()
+    }
+
+    fn get_components<T>(&self) -> Vec<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.app.get_components()
+    }
+
+    fn get_component_ref_named<T>(&self, name: &str) -> Option<ComponentRef<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        self.app.get_component_ref_named(name)
+    }
+
+    fn has_component_named<T>(&self, name: &str) -> bool
+    where
+        T: Any + Send + Sync,
+    {
+        self.app.has_component_named::<T>(name)
+    }
+}
+
+impl ConfigRegistry for RequestRegistry {
+    fn get_config<T>(&self) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Configurable,
+    {
+        self.app.get_config::<T>()
+    }
+}
+
+type Factory = dyn Fn(RegistryRef<'_>) -> Result<DynComponentRef> + Send + Sync;
+
+type AsyncFactory =
+    dyn Fn(RequestRegistry) -> BoxFuture<'static, Result<DynComponentRef>> + Send + Sync;
+
+#[derive(Clone)]
+enum FactoryFn {
+    Sync(Arc<Factory>),
+    Async(Arc<AsyncFactory>),
+}
+
+/// Factory of a component of [prototype](Scope::Prototype) or [request](Scope::Request) scope
+#[derive(Clone)]
+pub struct ComponentFactory {
+    scope: Scope,
+    type_name: &'static str,
+    factory: FactoryFn,
+}
+
+impl ComponentFactory {
+    pub(crate) fn new<C, F>(scope: Scope, factory: F) -> Self
+    where
+        C: Clone + Any + Send + Sync,
+        F: Fn(RegistryRef<'_>) -> Result<C> + Send + Sync + 'static,
+    {
+        Self {
+            scope,
+            type_name: std::any::type_name::<C>(),
+            factory: FactoryFn::Sync(Arc::new(move |registry| {
+                factory(registry).map(DynComponentRef::new)
+            })),
+        }
+    }
+
+    pub(crate) fn new_async<C, F, Fut>(factory: F) -> Self
+    where
+        C: Clone + Any + Send + Sync,
+        F: Fn(RequestRegistry) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<C>> + Send + 'static,
+    {
+        Self {
+            scope: Scope::Request,
+            type_name: std::any::type_name::<C>(),
+            factory: FactoryFn::Async(Arc::new(move |registry| {
+                let component = factory(registry);
+                Box::pin(async move { component.await.map(DynComponentRef::new) })
+            })),
+        }
+    }
+
+    /// Scope of the created components
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+
+    /// Type name of the created components
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Whether the components are created asynchronously,
+    /// they are only created by [`RequestRegistry::get_scoped_component`]
+    pub fn is_async(&self) -> bool {
+        matches!(self.factory, FactoryFn::Async(_))
+    }
+
+    /// Create a new component, its dependencies are looked up in `registry`.
+    ///
+    /// It fails for a component created asynchronously.
+    pub fn create<T>(&self, registry: RegistryRef<'_>) -> Result<ComponentRef<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        self.create_dyn(registry)?
+            .downcast::<T>()
+            .ok_or(AppError::ComponentNotExist(std::any::type_name::<T>()))
+    }
+
+    fn create_dyn(&self, registry: RegistryRef<'_>) -> Result<DynComponentRef> {
+        match &self.factory {
+            FactoryFn::Sync(factory) => factory(registry),
+            FactoryFn::Async(_) => Err(AppError::OtherError(anyhow!(
+                "{} component is created asynchronously, it can only be created for a web request",
+                self.type_name
+            ))),
+        }
+    }
+}
@@ -2,7 +2,8 @@
 #![doc = include_str!("../../DI.md")]
 use crate::app::AppBuilder;
 use crate::config::ConfigRegistry;
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::plugin::graph::{DependencyGraph, NodeKind};
 use crate::plugin::ComponentRegistry;
 
 pub use inventory::submit;
@@ -31,6 +32,16 @@ pub trait Service: Clone + Sized + 'static {
 pub trait ServiceRegistrar: Send + Sync + 'static {
     /// Install the Service component into the App
     fn install_service(&self, app: &mut AppBuilder) -> Result<()>;
+
+    /// Type name of the Service component
+    fn service_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Type names of the components the Service requires
+    fn dependencies(&self) -> Vec<&'static str> {
+        vec![]
+    }
 }
 
 inventory::collect!(&'static dyn ServiceRegistrar);
@@ -47,32 +58,29 @@ macro_rules! submit_service {
 
 /// Find all ServiceRegistrar and install them into the app
 pub fn auto_inject_service(app: &mut AppBuilder) -> Result<()> {
-    let registrars: Vec<&'static &dyn ServiceRegistrar> =
-        inventory::iter::<&dyn ServiceRegistrar>().collect();
+    let registrars: Vec<&'static dyn ServiceRegistrar> = inventory::iter::<&dyn ServiceRegistrar>()
+        .copied()
+        .collect();
     let total = registrars.len();
-    let mut pending: Vec<&'static &dyn ServiceRegistrar> = registrars;
+    let mut pending = registrars;
     let mut installed = 0;
 
     while !pending.is_empty() {
         let mut next_pending = Vec::new();
-        let mut progress_made = false;
+        let mut errors = Vec::new();
 
-        for registrar in pending {
+        for registrar in &pending {
             match registrar.install_service(app) {
-                Ok(()) => {
-                    installed += 1;
-                    progress_made = true;
-                }
-                Err(_) => {
-                    next_pending.push(registrar);
+                Ok(()) => installed += 1,
+                Err(e) => {
+                    next_pending.push(*registrar);
+                    errors.push(e);
                 }
             }
         }
 
-        if !progress_made && !next_pending.is_empty() {
-            if let Some(first) = next_pending.first() {
-                return first.install_service(app);
-            }
+        if next_pending.len() == pending.len() {
+            return Err(unresolved_services(&next_pending, errors));
         }
 
         pending = next_pending;
@@ -81,3 +89,91 @@ pub fn auto_inject_service(app: &mut AppBuilder) -> Result<()> {
     log::debug!("Installed {installed}/{total} services");
     Ok(())
 }
+
+/// Explain why none of the `pending` services can be installed:
+/// the services depending on each other, or the errors of the services which don't wait for another service
+fn unresolved_services(pending: &[&dyn ServiceRegistrar], errors: Vec<AppError>) -> AppError {
+    let mut graph = DependencyGraph::default();
+    for registrar in pending {
+        graph.add_node(NodeKind::Service, registrar.service_name());
+    }
+    for registrar in pending {
+        for dep in registrar.dependencies() {
+            if graph.contains(dep) {
+                graph.add_edge(registrar.service_name(), dep);
+            }
+        }
+    }
+    if let Some(cycle) = graph.find_cycle() {
+        return AppError::DependencyCycle(cycle);
+    }
+    let unresolved = pending
+        .iter()
+        .zip(errors)
+        .filter(|(registrar, _)| {
+            graph
+                .dependencies(registrar.service_name())
+                .next()
+                .is_none()
+        })
+        .map(|(registrar, e)| (registrar.service_name().to_string(), e.to_string()))
+        .collect();
+    AppError::UnresolvedDependencies(unresolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unresolved_services, ServiceRegistrar};
+    use crate::app::AppBuilder;
+    use crate::error::{AppError, Result};
+
+    struct PendingService {
+        name: &'static str,
+        dependencies: Vec<&'static str>,
+    }
+
+    impl ServiceRegistrar for PendingService {
+        fn install_service(&self, _app: &mut AppBuilder) -> Result<()> {
+            Err(AppError::ComponentNotExist(self.dependencies[0]))
+        }
+
+        fn service_name(&self) -> &'static str {
+            self.name
+        }
+
+        fn dependencies(&self) -> Vec<&'static str> {
+            self.dependencies.clone()
+        }
+    }
+
+    fn unresolved(services: &[PendingService]) -> AppError {
+        let pending: Vec<&dyn ServiceRegistrar> = services
+            .iter()
+            .map(|s| s as &dyn ServiceRegistrar)
+            .collect();
+        let errors = services
+            .iter()
+            .map(|s| AppError::ComponentNotExist(s.dependencies[0]))
+            .collect();
+        unresolved_services(&pending, errors)
+    }
+
+    #[test]
+    fn test_unresolved_services() {
+        let service = |name, dependencies| PendingService { name, dependencies };
+
+        let cycle = unresolved(&[
+            service("UserService", vec!["Db", "OrderService"]),
+            service("OrderService", vec!["UserService"]),
+        ]);
+        assert!(matches!(cycle, AppError::DependencyCycle(path)
+            if path == ["UserService", "OrderService", "UserService"]));
+
+        let missing = unresolved(&[
+            service("UserService", vec!["OrderService"]),
+            service("OrderService", vec!["Redis"]),
+        ]);
+        assert!(matches!(missing, AppError::UnresolvedDependencies(reasons)
+            if reasons == [("OrderService".to_string(), "Redis component not exists".to_string())]));
+    }
+}
@@ -1,4 +1,8 @@
-/// shutdown signal
+/// Wait for the Ctrl+C or SIGTERM signal of the process.
+///
+/// The application listens for these signals itself and broadcasts the shutdown
+/// through its [`Lifecycle`](crate::lifecycle::Lifecycle),
+/// schedulers should wait on [`Lifecycle::shutdown_signal`](crate::lifecycle::Lifecycle::shutdown_signal) instead.
 pub async fn shutdown_signal(plugin_name: &'static str) {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
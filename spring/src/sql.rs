@@ -0,0 +1,131 @@
+/// Whether the statement only reads data and can be sent to a read replica.
+///
+/// The check is conservative, only plain queries are read-only: a statement starting with
+/// `SELECT`, `SHOW`, `EXPLAIN`, `DESCRIBE` or `VALUES` is read-only unless
+///
+/// * it contains a keyword that writes or locks, like `INTO` or `FOR UPDATE`
+/// * it calls a function other than the standard aggregate and scalar functions, since functions
+///   can write or lock, e.g. `nextval('seq')`, `setval(...)` or `pg_advisory_lock(...)`
+///
+/// A query calling a function known to be read-only must be sent to a replica explicitly.
+pub fn is_read_only(sql: &str) -> bool {
+    let mut words = words(sql);
+    let reads = match words.next() {
+        Some((first, _)) => matches!(
+            first.as_str(),
+            "SELECT" | "SHOW" | "EXPLAIN" | "DESCRIBE" | "DESC" | "VALUES"
+        ),
+        None => false,
+    };
+    reads && words.all(|(word, call)| !writes(&word) && (!call || reads_only(&word)))
+}
+
+/// Words of the statement in uppercase, with whether they are followed by a parenthesis
+fn words(sql: &str) -> impl Iterator<Item = (String, bool)> + '_ {
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut rest = sql;
+    std::iter::from_fn(move || {
+        let start = rest.find(is_word_char)?;
+        rest = &rest[start..];
+        let end = rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+        let word = rest[..end].to_ascii_uppercase();
+        rest = &rest[end..];
+        Some((word, rest.trim_start().starts_with('(')))
+    })
+}
+
+fn writes(word: &str) -> bool {
+    matches!(
+        word,
+        "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "INTO" | "SHARE" | "LOCK" | "ANALYZE"
+    )
+}
+
+/// Keywords followed by a parenthesis and functions without side effects
+fn reads_only(word: &str) -> bool {
+    matches!(
+        word,
+        "SELECT"
+            | "FROM"
+            | "JOIN"
+            | "LATERAL"
+            | "USING"
+            | "ON"
+            | "WHERE"
+            | "AND"
+            | "OR"
+            | "NOT"
+            | "IN"
+            | "EXISTS"
+            | "ANY"
+            | "ALL"
+            | "SOME"
+            | "AS"
+            | "WITH"
+            | "UNION"
+            | "INTERSECT"
+            | "EXCEPT"
+            | "VALUES"
+            | "OVER"
+            | "FILTER"
+            | "WITHIN"
+            | "CAST"
+            | "COUNT"
+            | "SUM"
+            | "AVG"
+            | "MIN"
+            | "MAX"
+            | "COALESCE"
+            | "NULLIF"
+            | "GREATEST"
+            | "LEAST"
+            | "LOWER"
+            | "UPPER"
+            | "LENGTH"
+            | "TRIM"
+            | "SUBSTRING"
+            | "CONCAT"
+            | "ABS"
+            | "ROUND"
+            | "FLOOR"
+            | "CEIL"
+            | "NOW"
+            | "EXTRACT"
+            | "ROW_NUMBER"
+            | "RANK"
+            | "DENSE_RANK"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_read_only;
+
+    #[test]
+    fn test_is_read_only() {
+        assert!(is_read_only("SELECT * FROM users WHERE id = $1"));
+        assert!(is_read_only("  (select count(*) from orders)"));
+        assert!(is_read_only("EXPLAIN SELECT 1"));
+        assert!(is_read_only("show tables"));
+        assert!(is_read_only(
+            "SELECT * FROM users WHERE id IN (SELECT user_id FROM orders) AND lower(name) = $1"
+        ));
+
+        assert!(!is_read_only("INSERT INTO users (name) VALUES ($1)"));
+        assert!(!is_read_only(
+            "WITH deleted AS (DELETE FROM t RETURNING *) SELECT * FROM deleted"
+        ));
+        assert!(!is_read_only("SELECT * FROM users FOR UPDATE"));
+        assert!(!is_read_only("SELECT * INTO backup FROM users"));
+        assert!(!is_read_only("EXPLAIN ANALYZE DELETE FROM users"));
+        assert!(!is_read_only(""));
+    }
+
+    #[test]
+    fn test_function_calls() {
+        assert!(!is_read_only("SELECT nextval('order_seq')"));
+        assert!(!is_read_only("SELECT setval('order_seq', 42)"));
+        assert!(!is_read_only("select pg_advisory_lock(1)"));
+        assert!(!is_read_only("SELECT my_function ($1)"));
+    }
+}
@@ -315,3 +315,87 @@ async fn test_try_get_component() {
     let result = app.try_get_component::<NonExistent>();
     assert!(result.is_err());
 }
+
+// Conditional components
+trait Greeter: Send + Sync {
+    fn greet(&self) -> &'static str;
+}
+
+struct DefaultGreeter;
+
+impl Greeter for DefaultGreeter {
+    fn greet(&self) -> &'static str {
+        "default"
+    }
+}
+
+struct CustomGreeter;
+
+impl Greeter for CustomGreeter {
+    fn greet(&self) -> &'static str {
+        "custom"
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct AuditLog(&'static str);
+
+// Default component which the application can replace
+#[component(condition = on_missing_component)]
+fn create_default_greeter() -> std::sync::Arc<dyn Greeter> {
+    std::sync::Arc::new(DefaultGreeter)
+}
+
+#[component(condition = on_property("audit.enabled"), condition = on_profile("!prod"))]
+fn create_audit_log() -> AuditLog {
+    AuditLog("audit")
+}
+
+#[tokio::test]
+async fn test_conditional_component() {
+    use spring::async_trait;
+    use spring::plugin::{MutableComponentRegistry, Plugin};
+    use std::sync::Arc;
+
+    let toml_config = r#"
+        [test-db]
+        host = "conditionhost"
+        port = 2222
+        [audit]
+        enabled = true
+    "#;
+
+    let app = App::new()
+        .use_config_str(toml_config)
+        .build()
+        .await
+        .expect("Failed to build app");
+    let greeter = app.get_component::<Arc<dyn Greeter>>().unwrap();
+    assert_eq!(greeter.greet(), "default");
+    assert_eq!(app.get_component::<AuditLog>(), Some(AuditLog("audit")));
+
+    struct GreeterPlugin;
+
+    #[async_trait]
+    impl Plugin for GreeterPlugin {
+        async fn build(&self, app: &mut AppBuilder) {
+            app.add_component::<Arc<dyn Greeter>>(Arc::new(CustomGreeter));
+        }
+    }
+
+    let toml_config = r#"
+        [test-db]
+        host = "conditionhost"
+        port = 2222
+    "#;
+
+    let app = App::new()
+        .use_config_str(toml_config)
+        .add_plugin(GreeterPlugin)
+        .build()
+        .await
+        .expect("Failed to build app");
+    let greeter = app.get_component::<Arc<dyn Greeter>>().unwrap();
+    assert_eq!(greeter.greet(), "custom");
+    assert!(!app.has_component::<AuditLog>());
+}
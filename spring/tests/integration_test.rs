@@ -3,6 +3,8 @@
 use spring::app::AppBuilder;
 use spring::async_trait;
 use spring::config::Configurable;
+use spring::extractor::Component;
+use spring::plugin::service::Service;
 use spring::plugin::{ComponentRegistry, MutableComponentRegistry, Plugin};
 
 // Test component
@@ -326,3 +328,491 @@ async fn test_plugin_initialization_order() {
     assert_eq!(initialization_order[1], "second");
 }
 
+#[derive(Clone, Debug, PartialEq)]
+struct ConnectionPool(&'static str);
+
+#[derive(Clone, Service)]
+struct ReplicaService {
+    #[inject(component = "replica")]
+    replica: Option<ConnectionPool>,
+    #[inject(component = "reporting")]
+    reporting: Option<ConnectionPool>,
+}
+
+#[derive(Clone, Service)]
+#[service(prototype)]
+struct ReportService {
+    #[inject(component = "primary")]
+    primary: ConnectionPool,
+    #[inject(component)]
+    default: ConnectionPool,
+}
+
+#[tokio::test]
+async fn test_inject_named_component() {
+    let app = spring::App::new()
+        .add_component(ConnectionPool("default"))
+        .add_named_component("primary", ConnectionPool("primary"))
+        .add_named_component("replica", ConnectionPool("replica"))
+        .isolate_global(true)
+        .build()
+        .await
+        .expect("app build failed");
+
+    let service = app.get_expect_component::<ReplicaService>();
+    assert_eq!(service.replica, Some(ConnectionPool("replica")));
+    assert_eq!(service.reporting, None);
+
+    let report = ReportService::build().expect("build report service failed");
+    assert_eq!(report.primary, ConnectionPool("primary"));
+    assert_eq!(report.default, ConnectionPool("default"));
+}
+
+trait OrderListener: Send + Sync {
+    fn name(&self) -> &'static str;
+}
+
+struct AuditListener;
+
+impl OrderListener for AuditListener {
+    fn name(&self) -> &'static str {
+        "audit"
+    }
+}
+
+struct MailListener;
+
+impl OrderListener for MailListener {
+    fn name(&self) -> &'static str {
+        "mail"
+    }
+}
+
+#[derive(Clone, Service)]
+struct OrderService {
+    #[inject(component)]
+    listener: Option<std::sync::Arc<dyn OrderListener>>,
+    #[inject(components)]
+    listeners: Vec<std::sync::Arc<dyn OrderListener>>,
+}
+
+#[tokio::test]
+async fn test_inject_trait_object_component() {
+    use std::sync::Arc;
+
+    let app = spring::App::new()
+        .add_component::<Arc<dyn OrderListener>>(Arc::new(AuditListener))
+        .append_component::<Arc<dyn OrderListener>>(Arc::new(MailListener))
+        .isolate_global(true)
+        .build()
+        .await
+        .expect("app build failed");
+
+    let service = app.get_expect_component::<OrderService>();
+    assert_eq!(service.listener.map(|l| l.name()), Some("audit"));
+    let names: Vec<_> = service.listeners.iter().map(|l| l.name()).collect();
+    assert_eq!(names, ["audit", "mail"]);
+}
+
+#[derive(Clone, Service)]
+#[service(condition = on_property("report.enabled"))]
+struct ReportScheduler {
+    #[inject(component)]
+    pool: Option<ConnectionPool>,
+}
+
+#[derive(Clone, Service)]
+#[service(condition = on_missing_component)]
+struct ReportStore {
+    #[inject(component)]
+    pool: Option<ConnectionPool>,
+}
+
+#[tokio::test]
+async fn test_conditional_service() {
+    let app = spring::App::new()
+        .use_config_str("[report]\nenabled = true")
+        .add_component(ConnectionPool("default"))
+        .build()
+        .await
+        .expect("app build failed");
+    let scheduler = app.get_expect_component::<ReportScheduler>();
+    assert_eq!(scheduler.pool, Some(ConnectionPool("default")));
+    let store = app.get_expect_component::<ReportStore>();
+    assert_eq!(store.pool, Some(ConnectionPool("default")));
+
+    let app = spring::App::new()
+        .use_config_str("[report]\nenabled = false")
+        .add_component(ReportStore { pool: None })
+        .build()
+        .await
+        .expect("app build failed");
+    assert!(!app.has_component::<ReportScheduler>());
+    assert_eq!(app.get_expect_component::<ReportStore>().pool, None);
+}
+
+#[derive(Clone, Default)]
+struct CallbackEvents(std::sync::Arc<std::sync::Mutex<Vec<&'static str>>>);
+
+impl CallbackEvents {
+    fn push(&self, event: &'static str) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+#[derive(Clone, Service)]
+#[service(init = "connect", destroy = "close")]
+struct SessionStore {
+    #[inject(component)]
+    events: Option<CallbackEvents>,
+}
+
+impl SessionStore {
+    async fn connect(&self) -> spring::error::Result<()> {
+        if let Some(events) = &self.events {
+            events.push("store initialized");
+        }
+        Ok(())
+    }
+
+    async fn close(&self) -> anyhow::Result<()> {
+        if let Some(events) = &self.events {
+            events.push("store destroyed");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Service)]
+#[service(init = "start", destroy = "stop")]
+struct SessionService {
+    #[inject(component)]
+    store: SessionStore,
+}
+
+impl SessionService {
+    async fn start(&self) -> spring::error::Result<()> {
+        if let Some(events) = &self.store.events {
+            events.push("service initialized");
+        }
+        Ok(())
+    }
+
+    async fn stop(&self) -> spring::error::Result<()> {
+        if let Some(events) = &self.store.events {
+            events.push("service destroyed");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_service_callbacks() {
+    use std::sync::Arc;
+
+    let events = CallbackEvents::default();
+    let mut app = spring::App::new();
+    app.add_component(events.clone())
+        .add_scheduler(|_| Box::new(std::future::pending()))
+        .add_startup_hook(|app: Arc<spring::App>| {
+            Box::new(async move {
+                app.lifecycle().shutdown();
+                Ok("started".to_string())
+            })
+        })
+        .shutdown_timeout(std::time::Duration::from_millis(50));
+    app.run().await;
+
+    assert_eq!(
+        *events.0.lock().unwrap(),
+        [
+            "store initialized",
+            "service initialized",
+            "service destroyed",
+            "store destroyed"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_service_dependency_graph() {
+    use spring::plugin::graph::NodeKind;
+
+    let app = spring::App::new()
+        .isolate_global(true)
+        .build()
+        .await
+        .expect("app build failed");
+    let graph = app.dependency_graph();
+
+    let service = std::any::type_name::<SessionService>();
+    assert!(graph
+        .nodes()
+        .iter()
+        .any(|n| n.kind == NodeKind::Service && n.name == service));
+    assert_eq!(
+        graph.dependencies(service).collect::<Vec<_>>(),
+        [std::any::type_name::<SessionStore>()]
+    );
+    assert!(graph.to_dot().contains("shape=component"));
+}
+
+static NEXT_DRAFT_ID: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn next_draft_id() -> usize {
+    NEXT_DRAFT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+}
+
+#[derive(Clone, Service)]
+#[service(scope = "prototype")]
+struct DraftOrder {
+    #[inject(component)]
+    store: SessionStore,
+    #[inject(func = next_draft_id())]
+    id: usize,
+}
+
+#[tokio::test]
+async fn test_prototype_service() {
+    let app = spring::App::new()
+        .isolate_global(true)
+        .build()
+        .await
+        .expect("app build failed");
+
+    let first = app.get_expect_component::<DraftOrder>();
+    let second = app.get_expect_component::<DraftOrder>();
+    assert_ne!(first.id, second.id);
+    assert!(first.store.events.is_none());
+}
+
+#[derive(Clone, Default)]
+struct RecordedEvents(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+#[derive(Clone, Service)]
+struct EventRecorder {
+    #[inject(component)]
+    events: Option<RecordedEvents>,
+}
+
+impl EventRecorder {
+    fn record(&self, event: String) {
+        if let Some(events) = &self.events {
+            events.0.lock().unwrap().push(event);
+        }
+    }
+}
+
+struct UserRegistered(&'static str);
+
+#[spring::event_listener]
+async fn send_welcome_mail(event: &UserRegistered, Component(recorder): Component<EventRecorder>) {
+    tokio::task::yield_now().await;
+    recorder.record(format!("mail sent to {}", event.0));
+}
+
+#[spring::event_listener(order = -1)]
+fn audit_registration(event: &UserRegistered, Component(recorder): Component<EventRecorder>) {
+    recorder.record(format!("{} registered", event.0));
+}
+
+#[spring::event_listener]
+fn on_app_started(
+    _event: &spring::event::AppStarted,
+    Component(recorder): Component<EventRecorder>,
+) {
+    recorder.record("started".to_string());
+}
+
+#[spring::event_listener]
+fn on_app_ready(_event: &spring::event::AppReady, Component(recorder): Component<EventRecorder>) {
+    recorder.record("ready".to_string());
+}
+
+#[spring::event_listener]
+async fn on_shutdown_started(
+    _event: &spring::event::ShutdownStarted,
+    Component(recorder): Component<EventRecorder>,
+) {
+    recorder.record("shutdown started".to_string());
+}
+
+#[tokio::test]
+async fn test_event_listeners() {
+    use spring::event::ApplicationEventPublisher;
+
+    let events = RecordedEvents::default();
+    let app = spring::App::new()
+        .isolate_global(true)
+        .add_component(events.clone())
+        .build()
+        .await
+        .expect("app build failed");
+
+    let publisher = app.get_expect_component::<ApplicationEventPublisher>();
+    assert_eq!(publisher.listeners::<UserRegistered>().count(), 2);
+    publisher.publish(UserRegistered("alice")).await;
+
+    assert_eq!(
+        *events.0.lock().unwrap(),
+        ["alice registered", "mail sent to alice"]
+    );
+}
+
+#[tokio::test]
+async fn test_lifecycle_events() {
+    use std::sync::Arc;
+
+    let events = RecordedEvents::default();
+    let mut app = spring::App::new();
+    app.add_component(events.clone())
+        .add_scheduler(|_| Box::new(std::future::pending()))
+        .add_startup_hook(|app: Arc<spring::App>| {
+            Box::new(async move {
+                app.lifecycle().shutdown();
+                Ok("started".to_string())
+            })
+        })
+        .shutdown_timeout(std::time::Duration::from_millis(50));
+    app.run().await;
+
+    assert_eq!(
+        *events.0.lock().unwrap(),
+        ["started", "ready", "shutdown started"]
+    );
+}
+
+static SQUARE_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[spring::cache("square:{n}", name = "squares", expire = 60)]
+async fn cached_square(n: u64) -> u64 {
+    SQUARE_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    n * n
+}
+
+#[spring::cache("square:{n}", name = "squares", unless = result.is_none())]
+async fn cached_checked_square(n: u64) -> Result<Option<u64>, String> {
+    SQUARE_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    Ok(n.checked_mul(n))
+}
+
+/// App whose cache manager is used by the cached functions of the test
+async fn cache_app() -> std::sync::Arc<spring::App> {
+    spring::App::new()
+        .isolate_global(true)
+        .build()
+        .await
+        .expect("app build failed")
+}
+
+#[tokio::test]
+async fn test_memory_cache() {
+    use std::sync::atomic::Ordering;
+
+    // without an `Arc<dyn CacheManager>` component, the values are cached in memory
+    let app = cache_app().await;
+    assert_eq!(cached_square(7).await, 49);
+    assert_eq!(cached_square(7).await, 49);
+    assert_eq!(SQUARE_CALLS.load(Ordering::SeqCst), 1);
+
+    let cache = spring::cache::cache_manager(&app).cache("squares");
+    assert_eq!(cache.get("square:7").await.unwrap(), Some(b"49".to_vec()));
+
+    // each app has its own caches
+    let other = cache_app().await;
+    let other_cache = spring::cache::cache_manager(&other).cache("squares");
+    assert_eq!(other_cache.get("square:7").await.unwrap(), None);
+    assert_eq!(cached_square(7).await, 49);
+    assert_eq!(SQUARE_CALLS.load(Ordering::SeqCst), 2);
+
+    other_cache.evict("square:7").await.unwrap();
+    assert_eq!(cached_checked_square(7).await, Ok(Some(49)));
+    assert_eq!(cached_checked_square(7).await, Ok(Some(49)));
+    assert_eq!(SQUARE_CALLS.load(Ordering::SeqCst), 3);
+}
+
+#[spring::cache("user:{id}", name = "user_names")]
+async fn cached_user_name(id: u64) -> Option<String> {
+    (id == 1).then(|| "alice".to_string())
+}
+
+#[spring::cache_put("user:{id}", name = "user_names")]
+async fn rename_user(id: u64, name: &str) -> Result<Option<String>, String> {
+    Ok(Some(name.to_string()))
+}
+
+#[spring::cache_evict("user:{id}", name = "user_names")]
+async fn delete_user(id: u64) -> Result<(), String> {
+    if id == 0 {
+        return Err("user 0 can't be deleted".to_string());
+    }
+    Ok(())
+}
+
+#[spring::cache_evict(all_entries, name = "user_names")]
+async fn delete_all_users() {}
+
+#[tokio::test]
+async fn test_cache_put_and_evict() {
+    let app = cache_app().await;
+    let cache = spring::cache::cache_manager(&app).cache("user_names");
+
+    assert_eq!(cached_user_name(1).await.as_deref(), Some("alice"));
+    assert_eq!(rename_user(1, "bob").await, Ok(Some("bob".to_string())));
+    assert_eq!(cached_user_name(1).await.as_deref(), Some("bob"));
+
+    delete_user(1).await.unwrap();
+    assert_eq!(cache.get("user:1").await.unwrap(), None);
+    assert_eq!(cached_user_name(1).await.as_deref(), Some("alice"));
+
+    // the entry is kept when the function fails
+    cached_user_name(0).await;
+    assert!(delete_user(0).await.is_err());
+    assert!(cache.get("user:0").await.unwrap().is_some());
+
+    delete_all_users().await;
+    assert_eq!(cache.get("user:0").await.unwrap(), None);
+    assert_eq!(cache.get("user:1").await.unwrap(), None);
+}
+
+static SLOW_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[spring::cache("slow:{n}", name = "slow_values", sync = "local")]
+async fn cached_slow_value(n: u64) -> u64 {
+    SLOW_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    n * 10
+}
+
+#[tokio::test]
+async fn test_cache_sync() {
+    use std::sync::atomic::Ordering;
+
+    let _app = cache_app().await;
+    let values = futures_util::future::join_all((0..8).map(|_| cached_slow_value(3))).await;
+    assert_eq!(values, vec![30; 8]);
+    assert_eq!(SLOW_CALLS.load(Ordering::SeqCst), 1);
+}
+
+static EMAIL_LOOKUPS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[spring::cache("email:{id}", name = "emails", expire = 600, negative_ttl = 0)]
+async fn cached_email(id: u64) -> Result<Option<String>, String> {
+    EMAIL_LOOKUPS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    Ok((id == 1).then(|| "alice@example.com".to_string()))
+}
+
+#[tokio::test]
+async fn test_cache_negative_ttl() {
+    use std::sync::atomic::Ordering;
+
+    let _app = cache_app().await;
+    assert!(cached_email(1).await.unwrap().is_some());
+    assert!(cached_email(1).await.unwrap().is_some());
+    assert_eq!(EMAIL_LOOKUPS.load(Ordering::SeqCst), 1);
+
+    // the missing emails expire immediately
+    assert_eq!(cached_email(2).await, Ok(None));
+    assert_eq!(cached_email(2).await, Ok(None));
+    assert_eq!(EMAIL_LOOKUPS.load(Ordering::SeqCst), 3);
+}